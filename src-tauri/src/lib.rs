@@ -5,7 +5,14 @@ use tauri::Manager;
 
 use log::LevelFilter;
 use services::network::{get_network_speed as network_get_speed, NetworkSpeed};
-use services::process::{get_running_linglong_apps as process_get_running_apps, kill_linglong_app as process_kill_app, LinglongAppInfo};
+use services::process::{
+    get_running_linglong_apps as process_get_running_apps,
+    kill_linglong_app as process_kill_app,
+    stop_linglong_app,
+    stop_all_linglong_apps,
+    LinglongAppInfo,
+    StopAppResult,
+};
 use services::{
     get_installed_apps,
     uninstall_linglong_app,
@@ -14,18 +21,100 @@ use services::{
     install_linglong_app,
     cancel_linglong_install,
     InstalledApp,
+    RunAppOptions,
+};
+use services::resource_monitor::{
+    get_app_resource_usage,
+    start_resource_monitor,
+    stop_resource_monitor,
+    AppResourceUsage,
+};
+use services::app_watcher::start_app_watcher;
+use services::autostart::{
+    list_autostart_apps,
+    enable_autostart,
+    disable_autostart,
+    AutostartEntry,
+    AutostartOptions,
+};
+use services::desktop_entry::{
+    list_desktop_entries,
+    create_desktop_shortcut,
+    remove_desktop_shortcut,
+    repair_desktop_entries,
+    DesktopEntryInfo,
+    DesktopRepairReport,
+};
+use services::launch_log::{get_launch_logs, read_launch_log, LaunchLogEntry};
+use services::terminal::{
+    open_terminal_session,
+    write_terminal,
+    resize_terminal,
+    close_terminal,
+    list_terminal_sessions,
+    TerminalRequest,
+    TerminalSession,
+};
+use services::prune::{preview_prune, prune_linglong_apps, PrunePreview, PruneReport};
+use services::disk_usage::{get_disk_usage, DiskUsageReport};
+use services::dependencies::{
+    get_dependency_graph,
+    get_app_dependents,
+    get_app_dependencies,
+    check_uninstall,
+    ensure_safe_uninstall,
+    DependencyGraph,
+    DependencyNode,
+    UninstallCheck,
 };
-use services::prune::prune_linglong_apps;
 use services::linglong::{
     search_remote_app,
+    get_remote_app_versions,
     get_ll_cli_version,
     SearchResultItem,
+    RemoteAppVersion,
 };
-use services::linglong_env::{
-    check_linglong_env,
+use services::linglong_env::{check_linglong_env, LinglongEnvCheckResult};
+use services::env_install::{
     install_linglong_env,
-    LinglongEnvCheckResult,
+    cancel_install_linglong_env,
+    get_install_linglong_env_transcript,
+    verify_install_linglong_env_script,
+    EnvInstallOutput,
     InstallLinglongResult,
+    ScriptVerification,
+};
+use services::repo::{
+    get_repo_config,
+    add_repo,
+    remove_repo,
+    update_repo,
+    set_default_repo,
+    set_repo_priority,
+    LinglongRepoConfig,
+};
+use services::repo_health::{check_repo_health, RepoHealthReport};
+use services::updates::{check_app_updates, AppUpdateCheck};
+use services::update_scheduler::{
+    start_update_scheduler,
+    get_update_scheduler_status,
+    set_update_scheduler_config,
+    UpdateSchedulerConfig,
+    UpdateSchedulerStatus,
+};
+use services::auto_update::{
+    get_auto_update_policy,
+    set_auto_update_policy,
+    run_auto_update_now,
+    AutoUpdatePolicy,
+    AutoUpdateSummary,
+};
+use services::history::{get_operation_history, OperationRecord};
+use services::mirror_bench::{
+    run_mirror_benchmark,
+    get_mirror_benchmark_history,
+    apply_mirror_ranking,
+    MirrorBenchmarkRun,
 };
 use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
 
@@ -36,8 +125,56 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-async fn search_remote_app_cmd(app_id: String) -> Result<Vec<SearchResultItem>, String> {
-    search_remote_app(app_id).await
+async fn search_remote_app_cmd(
+    app_id: String,
+    repos: Option<Vec<String>>,
+) -> Result<Vec<SearchResultItem>, String> {
+    search_remote_app(app_id, repos).await
+}
+
+#[tauri::command]
+async fn get_remote_app_versions_cmd(app_id: String) -> Result<Vec<RemoteAppVersion>, String> {
+    get_remote_app_versions(app_id).await
+}
+
+#[tauri::command]
+async fn check_app_updates_cmd(repos: Option<Vec<String>>) -> Result<AppUpdateCheck, String> {
+    check_app_updates(repos).await
+}
+
+#[tauri::command]
+async fn get_update_scheduler_status_cmd() -> Result<UpdateSchedulerStatus, String> {
+    get_update_scheduler_status().await
+}
+
+#[tauri::command]
+async fn set_update_scheduler_config_cmd(
+    config: UpdateSchedulerConfig,
+) -> Result<UpdateSchedulerConfig, String> {
+    log::info!("[set_update_scheduler_config] Command invoked: {:?}", config);
+    set_update_scheduler_config(config).await
+}
+
+#[tauri::command]
+async fn get_auto_update_policy_cmd() -> Result<AutoUpdatePolicy, String> {
+    get_auto_update_policy().await
+}
+
+#[tauri::command]
+async fn set_auto_update_policy_cmd(policy: AutoUpdatePolicy) -> Result<AutoUpdatePolicy, String> {
+    log::info!("[set_auto_update_policy] Command invoked: {:?}", policy);
+    set_auto_update_policy(policy).await
+}
+
+#[tauri::command]
+async fn run_auto_update_cmd() -> Result<AutoUpdateSummary, String> {
+    log::info!("[run_auto_update] Command invoked");
+    run_auto_update_now().await
+}
+
+#[tauri::command]
+async fn get_operation_history_cmd(kind: Option<String>) -> Result<Vec<OperationRecord>, String> {
+    get_operation_history(kind).await
 }
 
 #[tauri::command]
@@ -53,8 +190,87 @@ async fn check_linglong_env_cmd() -> Result<LinglongEnvCheckResult, String> {
 }
 
 #[tauri::command]
-async fn install_linglong_env_cmd(script: String) -> Result<InstallLinglongResult, String> {
-    install_linglong_env(script).await
+async fn install_linglong_env_cmd(app_handle: tauri::AppHandle) -> Result<InstallLinglongResult, String> {
+    install_linglong_env(app_handle).await
+}
+
+#[tauri::command]
+async fn verify_install_linglong_env_script_cmd() -> Result<ScriptVerification, String> {
+    verify_install_linglong_env_script().await
+}
+
+#[tauri::command]
+async fn cancel_install_linglong_env_cmd() -> Result<String, String> {
+    log::info!("[cancel_install_linglong_env] Command invoked");
+    cancel_install_linglong_env().await
+}
+
+#[tauri::command]
+async fn get_install_linglong_env_transcript_cmd() -> Result<Vec<EnvInstallOutput>, String> {
+    get_install_linglong_env_transcript().await
+}
+
+#[tauri::command]
+async fn get_repo_config_cmd() -> Result<LinglongRepoConfig, String> {
+    get_repo_config().await
+}
+
+#[tauri::command]
+async fn add_repo_cmd(
+    name: String,
+    url: String,
+    alias: Option<String>,
+) -> Result<LinglongRepoConfig, String> {
+    log::info!("[add_repo] Command invoked: name={}, url={}, alias={:?}", name, url, alias);
+    add_repo(name, url, alias).await
+}
+
+#[tauri::command]
+async fn remove_repo_cmd(name: String) -> Result<LinglongRepoConfig, String> {
+    log::info!("[remove_repo] Command invoked: name={}", name);
+    remove_repo(name).await
+}
+
+#[tauri::command]
+async fn update_repo_cmd(name: String, url: String) -> Result<LinglongRepoConfig, String> {
+    log::info!("[update_repo] Command invoked: name={}, url={}", name, url);
+    update_repo(name, url).await
+}
+
+#[tauri::command]
+async fn set_default_repo_cmd(name: String) -> Result<LinglongRepoConfig, String> {
+    log::info!("[set_default_repo] Command invoked: name={}", name);
+    set_default_repo(name).await
+}
+
+#[tauri::command]
+async fn set_repo_priority_cmd(name: String, priority: i64) -> Result<LinglongRepoConfig, String> {
+    log::info!("[set_repo_priority] Command invoked: name={}, priority={}", name, priority);
+    set_repo_priority(name, priority).await
+}
+
+#[tauri::command]
+async fn check_repo_health_cmd() -> Result<RepoHealthReport, String> {
+    check_repo_health().await
+}
+
+#[tauri::command]
+async fn run_mirror_benchmark_cmd(repos: Option<Vec<String>>) -> Result<MirrorBenchmarkRun, String> {
+    run_mirror_benchmark(repos).await
+}
+
+#[tauri::command]
+async fn get_mirror_benchmark_history_cmd() -> Result<Vec<MirrorBenchmarkRun>, String> {
+    get_mirror_benchmark_history().await
+}
+
+#[tauri::command]
+async fn apply_mirror_ranking_cmd(
+    order: Vec<String>,
+    set_default: bool,
+) -> Result<LinglongRepoConfig, String> {
+    log::info!("[apply_mirror_ranking] Command invoked: order={:?}, set_default={}", order, set_default);
+    apply_mirror_ranking(order, set_default).await
 }
 
 #[tauri::command]
@@ -72,13 +288,49 @@ async fn kill_linglong_app(app_name: String) -> Result<String, String> {
     process_kill_app(app_name).await
 }
 
+#[tauri::command]
+async fn stop_linglong_app_cmd(target: String, grace_ms: Option<u64>) -> Result<StopAppResult, String> {
+    log::info!("[stop_linglong_app] Command invoked: target={}, grace_ms={:?}", target, grace_ms);
+    stop_linglong_app(target, grace_ms).await
+}
+
+#[tauri::command]
+async fn stop_all_linglong_apps_cmd(grace_ms: Option<u64>) -> Result<Vec<StopAppResult>, String> {
+    log::info!("[stop_all_linglong_apps] Command invoked: grace_ms={:?}", grace_ms);
+    stop_all_linglong_apps(grace_ms).await
+}
+
+#[tauri::command]
+async fn get_app_resource_usage_cmd(window_ms: Option<u64>) -> Result<Vec<AppResourceUsage>, String> {
+    get_app_resource_usage(window_ms).await
+}
+
+#[tauri::command]
+async fn start_resource_monitor_cmd(
+    app_handle: tauri::AppHandle,
+    interval_ms: Option<u64>,
+) -> Result<(), String> {
+    log::info!("[start_resource_monitor] Command invoked: interval_ms={:?}", interval_ms);
+    start_resource_monitor(app_handle, interval_ms).await
+}
+
+#[tauri::command]
+async fn stop_resource_monitor_cmd() -> Result<(), String> {
+    log::info!("[stop_resource_monitor] Command invoked");
+    stop_resource_monitor().await
+}
+
 #[tauri::command]
 async fn get_installed_linglong_apps(include_base_service: bool) -> Result<Vec<InstalledApp>, String> {
     get_installed_apps(include_base_service).await
 }
 
 #[tauri::command]
-async fn uninstall_app(app_id: String, version: String) -> Result<String, String> {
+async fn uninstall_app(app_id: String, version: String, force: Option<bool>) -> Result<String, String> {
+    log::info!("[uninstall_app] Command invoked: app_id={}, version={}, force={:?}", app_id, version, force);
+    if !force.unwrap_or(false) {
+        ensure_safe_uninstall(&app_id, &version).await?;
+    }
     uninstall_linglong_app(app_id, version).await
 }
 
@@ -88,8 +340,96 @@ async fn search_versions(app_id: String) -> Result<Vec<InstalledApp>, String> {
 }
 
 #[tauri::command]
-async fn run_app(app_id: String) -> Result<String, String> {
-    run_linglong_app(app_id).await
+async fn run_app(
+    app_handle: tauri::AppHandle,
+    app_id: String,
+    options: Option<RunAppOptions>,
+) -> Result<String, String> {
+    log::info!("[run_app] Command invoked: app_id={}, options={:?}", app_id, options);
+    run_linglong_app(app_handle, app_id, options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn get_launch_logs_cmd(app_id: Option<String>) -> Result<Vec<LaunchLogEntry>, String> {
+    get_launch_logs(app_id).await
+}
+
+#[tauri::command]
+async fn read_launch_log_cmd(name: String) -> Result<String, String> {
+    read_launch_log(name).await
+}
+
+#[tauri::command]
+async fn list_desktop_entries_cmd(app_ids: Option<Vec<String>>) -> Result<Vec<DesktopEntryInfo>, String> {
+    list_desktop_entries(app_ids).await
+}
+
+#[tauri::command]
+async fn create_desktop_shortcut_cmd(app_id: String) -> Result<Vec<String>, String> {
+    log::info!("[create_desktop_shortcut] Command invoked: app_id={}", app_id);
+    create_desktop_shortcut(app_id).await
+}
+
+#[tauri::command]
+async fn remove_desktop_shortcut_cmd(app_id: String) -> Result<Vec<String>, String> {
+    log::info!("[remove_desktop_shortcut] Command invoked: app_id={}", app_id);
+    remove_desktop_shortcut(app_id).await
+}
+
+#[tauri::command]
+async fn repair_desktop_entries_cmd(app_id: String) -> Result<DesktopRepairReport, String> {
+    log::info!("[repair_desktop_entries] Command invoked: app_id={}", app_id);
+    repair_desktop_entries(app_id).await
+}
+
+#[tauri::command]
+async fn list_autostart_apps_cmd() -> Result<Vec<AutostartEntry>, String> {
+    list_autostart_apps().await
+}
+
+#[tauri::command]
+async fn enable_autostart_cmd(
+    app_id: String,
+    options: Option<AutostartOptions>,
+) -> Result<AutostartEntry, String> {
+    log::info!("[enable_autostart] Command invoked: app_id={}, options={:?}", app_id, options);
+    enable_autostart(app_id, options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn disable_autostart_cmd(app_id: String) -> Result<Vec<String>, String> {
+    log::info!("[disable_autostart] Command invoked: app_id={}", app_id);
+    disable_autostart(app_id).await
+}
+
+#[tauri::command]
+async fn open_terminal_session_cmd(
+    app_handle: tauri::AppHandle,
+    request: TerminalRequest,
+) -> Result<TerminalSession, String> {
+    log::info!("[open_terminal_session] Command invoked: {:?}", request);
+    open_terminal_session(app_handle, request).await
+}
+
+#[tauri::command]
+async fn write_terminal_cmd(session_id: String, data: String) -> Result<(), String> {
+    write_terminal(session_id, data).await
+}
+
+#[tauri::command]
+async fn resize_terminal_cmd(session_id: String, cols: u16, rows: u16) -> Result<(), String> {
+    resize_terminal(session_id, cols, rows).await
+}
+
+#[tauri::command]
+async fn close_terminal_cmd(session_id: String) -> Result<(), String> {
+    log::info!("[close_terminal] Command invoked: session_id={}", session_id);
+    close_terminal(session_id).await
+}
+
+#[tauri::command]
+async fn list_terminal_sessions_cmd() -> Result<Vec<TerminalSession>, String> {
+    list_terminal_sessions().await
 }
 
 #[tauri::command]
@@ -106,8 +446,39 @@ async fn install_app(
 }
 
 #[tauri::command]
-async fn prune_apps() -> Result<String, String> {
-    prune_linglong_apps().await
+async fn prune_apps(app_handle: tauri::AppHandle) -> Result<PruneReport, String> {
+    log::info!("[prune_apps] Command invoked");
+    prune_linglong_apps(app_handle).await
+}
+
+#[tauri::command]
+async fn preview_prune_cmd() -> Result<PrunePreview, String> {
+    preview_prune().await
+}
+
+#[tauri::command]
+async fn get_disk_usage_cmd() -> Result<DiskUsageReport, String> {
+    get_disk_usage().await
+}
+
+#[tauri::command]
+async fn get_dependency_graph_cmd() -> Result<DependencyGraph, String> {
+    get_dependency_graph().await
+}
+
+#[tauri::command]
+async fn get_app_dependents_cmd(app_id: String, version: Option<String>) -> Result<Vec<DependencyNode>, String> {
+    get_app_dependents(app_id, version).await
+}
+
+#[tauri::command]
+async fn get_app_dependencies_cmd(app_id: String, version: Option<String>) -> Result<Vec<DependencyNode>, String> {
+    get_app_dependencies(app_id, version).await
+}
+
+#[tauri::command]
+async fn check_uninstall_cmd(app_id: String, version: String) -> Result<UninstallCheck, String> {
+    check_uninstall(app_id, version).await
 }
 
 #[tauri::command]
@@ -158,22 +529,73 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_zustand::init())
+        .setup(|app| {
+            start_update_scheduler(app.handle().clone());
+            start_app_watcher(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             get_network_speed,
             get_running_linglong_apps,
             kill_linglong_app,
+            stop_linglong_app_cmd,
+            stop_all_linglong_apps_cmd,
+            get_app_resource_usage_cmd,
+            start_resource_monitor_cmd,
+            stop_resource_monitor_cmd,
             get_installed_linglong_apps,
             uninstall_app,
             search_versions,
             run_app,
+            get_launch_logs_cmd,
+            read_launch_log_cmd,
+            list_desktop_entries_cmd,
+            create_desktop_shortcut_cmd,
+            remove_desktop_shortcut_cmd,
+            repair_desktop_entries_cmd,
+            list_autostart_apps_cmd,
+            enable_autostart_cmd,
+            disable_autostart_cmd,
+            open_terminal_session_cmd,
+            write_terminal_cmd,
+            resize_terminal_cmd,
+            close_terminal_cmd,
+            list_terminal_sessions_cmd,
             install_app,
             cancel_install,
             prune_apps,
+            preview_prune_cmd,
+            get_disk_usage_cmd,
+            get_dependency_graph_cmd,
+            get_app_dependents_cmd,
+            get_app_dependencies_cmd,
+            check_uninstall_cmd,
             search_remote_app_cmd,
+            get_remote_app_versions_cmd,
+            check_app_updates_cmd,
+            get_update_scheduler_status_cmd,
+            set_update_scheduler_config_cmd,
+            get_auto_update_policy_cmd,
+            set_auto_update_policy_cmd,
+            run_auto_update_cmd,
+            get_operation_history_cmd,
             get_ll_cli_version_cmd,
             check_linglong_env_cmd,
             install_linglong_env_cmd,
+            verify_install_linglong_env_script_cmd,
+            cancel_install_linglong_env_cmd,
+            get_install_linglong_env_transcript_cmd,
+            get_repo_config_cmd,
+            add_repo_cmd,
+            remove_repo_cmd,
+            update_repo_cmd,
+            set_default_repo_cmd,
+            set_repo_priority_cmd,
+            check_repo_health_cmd,
+            run_mirror_benchmark_cmd,
+            get_mirror_benchmark_history_cmd,
+            apply_mirror_ranking_cmd,
             quit_app,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,232 @@
+//! 应用元信息缓存
+//!
+//! `ll-cli info` 的结果按 appId + 版本缓存，同一版本的元信息不会变化，
+//! 运行列表等高频轮询场景只需为新出现的版本调用一次 `ll-cli info`。
+//! 不带版本的引用（如旧版 `ll-cli ps` 的文本输出）解析到的版本会短暂记住，
+//! 有效期内直接命中缓存；过期后重新查询，以便感知应用升级。
+
+use log::warn;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+use crate::services::ll_cli_command;
+
+/// `ll-cli info` 输出中用到的字段
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct AppInfo {
+    pub id: String,
+    pub version: String,
+    pub arch: Vec<String>,
+    pub channel: String,
+    pub base: String,
+    pub runtime: String,
+}
+
+impl AppInfo {
+    /// base 的来源（如 `main:org.deepin.base/23.1.0/x86_64` 中的 `main`）
+    pub fn source(&self) -> String {
+        self.base.split(':').next().unwrap_or("").to_string()
+    }
+}
+
+/// 应用引用 `[channel:]id[/version[/arch]]` 的组成部分
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AppRef {
+    pub channel: Option<String>,
+    pub id: String,
+    pub version: Option<String>,
+    pub arch: Option<String>,
+}
+
+impl AppRef {
+    pub fn parse(reference: &str) -> Self {
+        let reference = reference.trim();
+        let (channel, rest) = match reference.split_once(':') {
+            Some((channel, rest)) => (Some(channel.to_string()), rest),
+            None => (None, reference),
+        };
+        let mut parts = rest.split('/');
+        let id = parts.next().unwrap_or("").to_string();
+        let non_empty = |s: Option<&str>| s.filter(|s| !s.is_empty()).map(str::to_string);
+        let version = non_empty(parts.next());
+        let arch = non_empty(parts.next());
+        Self {
+            channel,
+            id,
+            version,
+            arch,
+        }
+    }
+}
+
+/// 不带版本的引用解析结果的有效期
+const BARE_REF_TTL: Duration = Duration::from_secs(30);
+
+static INFO_CACHE: Lazy<Mutex<HashMap<(String, String), AppInfo>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// appId 到 `ll-cli info <appId>` 最近一次解析到的版本及解析时间
+static BARE_REF_VERSION: Lazy<Mutex<HashMap<String, (String, Instant)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cached(id: &str, version: &str) -> Option<AppInfo> {
+    INFO_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&(id.to_string(), version.to_string())).cloned())
+}
+
+/// 不带版本的引用：有效期内取上次解析到的版本的缓存
+fn cached_bare(id: &str) -> Option<AppInfo> {
+    let version = BARE_REF_VERSION
+        .lock()
+        .ok()?
+        .get(id)
+        .filter(|(_, at)| at.elapsed() < BARE_REF_TTL)
+        .map(|(version, _)| version.clone())?;
+    cached(id, &version)
+}
+
+fn remember_bare(id: &str, info: &AppInfo) {
+    if info.version.is_empty() {
+        return;
+    }
+    if let Ok(mut map) = BARE_REF_VERSION.lock() {
+        map.insert(id.to_string(), (info.version.clone(), Instant::now()));
+    }
+}
+
+fn store(info: &AppInfo) {
+    if info.id.is_empty() || info.version.is_empty() {
+        return;
+    }
+    if let Ok(mut cache) = INFO_CACHE.lock() {
+        cache.insert((info.id.clone(), info.version.clone()), info.clone());
+    }
+}
+
+/// 执行 `ll-cli info <reference>` 并解析
+fn query_app_info(reference: &str) -> Result<AppInfo, String> {
+    let output = ll_cli_command()
+        .arg("info")
+        .arg(reference)
+        .output()
+        .map_err(|e| format!("Failed to execute 'll-cli info {}': {}", reference, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ll-cli info {} failed: {}",
+            reference,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    serde_json::from_slice::<AppInfo>(&output.stdout)
+        .map_err(|e| format!("Failed to parse 'll-cli info {}' output: {}", reference, e))
+}
+
+/// 批量获取应用元信息
+///
+/// 优先命中缓存（不带版本的引用在有效期内使用上次解析到的版本），其余并发调用 `ll-cli info`。
+/// 返回值与输入一一对应，获取失败的为 None。
+pub async fn get_app_infos(references: &[String]) -> Vec<Option<AppInfo>> {
+    let mut results: Vec<Option<AppInfo>> = references
+        .iter()
+        .map(|reference| {
+            let app_ref = AppRef::parse(reference);
+            match app_ref.version {
+                Some(version) => cached(&app_ref.id, &version),
+                None => cached_bare(&app_ref.id),
+            }
+        })
+        .collect();
+
+    // 相同引用只查询一次
+    let mut missing: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, reference) in references.iter().enumerate() {
+        if results[index].is_none() {
+            missing.entry(reference.clone()).or_default().push(index);
+        }
+    }
+
+    let mut tasks = JoinSet::new();
+    for reference in missing.keys().cloned() {
+        tasks.spawn_blocking(move || {
+            let result = query_app_info(&reference);
+            (reference, result)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((reference, result)) = joined else {
+            continue;
+        };
+        match result {
+            Ok(info) => {
+                store(&info);
+                let app_ref = AppRef::parse(&reference);
+                if app_ref.version.is_none() {
+                    remember_bare(&app_ref.id, &info);
+                }
+                for &index in missing.get(&reference).into_iter().flatten() {
+                    results[index] = Some(info.clone());
+                }
+            }
+            Err(e) => warn!("[AppInfo] {}", e),
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_app_ref() {
+        let full = AppRef::parse("main:org.deepin.calculator/5.7.21.4/x86_64");
+        assert_eq!(full.channel.as_deref(), Some("main"));
+        assert_eq!(full.id, "org.deepin.calculator");
+        assert_eq!(full.version.as_deref(), Some("5.7.21.4"));
+        assert_eq!(full.arch.as_deref(), Some("x86_64"));
+
+        let bare = AppRef::parse("org.deepin.calculator");
+        assert_eq!(bare.channel, None);
+        assert_eq!(bare.id, "org.deepin.calculator");
+        assert_eq!(bare.version, None);
+    }
+
+    #[test]
+    fn test_info_parsing_tolerates_missing_fields() {
+        let info: AppInfo = serde_json::from_str(
+            r#"{"id":"org.example.app","version":"1.0.0.0","arch":["x86_64"],"base":"main:org.deepin.base/23.1.0/x86_64","extra":1}"#,
+        )
+        .unwrap();
+        assert_eq!(info.source(), "main");
+        assert_eq!(info.channel, "");
+
+        store(&info);
+        assert_eq!(cached("org.example.app", "1.0.0.0"), Some(info));
+        assert_eq!(cached("org.example.app", "2.0.0.0"), None);
+    }
+
+    #[test]
+    fn test_bare_reference_uses_remembered_version() {
+        let info = AppInfo {
+            id: "org.example.bare".to_string(),
+            version: "2.0.0.0".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(cached_bare("org.example.bare"), None);
+        store(&info);
+        remember_bare("org.example.bare", &info);
+        assert_eq!(cached_bare("org.example.bare"), Some(info));
+
+        // 过期后需重新查询
+        if let Ok(mut map) = BARE_REF_VERSION.lock() {
+            map.get_mut("org.example.bare").unwrap().1 -= BARE_REF_TTL;
+        }
+        assert_eq!(cached_bare("org.example.bare"), None);
+    }
+}
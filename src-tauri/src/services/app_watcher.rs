@@ -0,0 +1,255 @@
+//! 运行中应用监视器
+//!
+//! 有应用运行时，每秒通过 /proc 检查各容器进程是否存活，并以较低频率比对
+//! `ll-cli ps` 发现新容器，发送 `app-started` / `app-exited` 事件。
+//! 没有应用运行时停止轮询，直到商店启动应用或查询运行列表时被唤醒。
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::services::app_info::AppRef;
+use crate::services::process::{list_ps, PsEntry};
+use crate::utils::linux::proc::is_alive;
+use crate::utils::time::now_millis;
+
+const APP_STARTED_EVENT: &str = "app-started";
+const APP_EXITED_EVENT: &str = "app-exited";
+/// 检查进程存活的间隔
+const LIVENESS_INTERVAL: Duration = Duration::from_secs(1);
+/// 每隔多少次存活检查执行一次 `ll-cli ps`
+const PS_EVERY: u32 = 5;
+/// 被唤醒后即使没有发现运行中的应用也继续轮询的时间，等待容器创建完成
+const SETTLE_PERIOD: Duration = Duration::from_secs(15);
+/// 启动进程退出码的有效期
+const EXIT_STATUS_TTL: Duration = Duration::from_secs(10);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static WAKE: Lazy<Notify> = Lazy::new(Notify::new);
+/// 退出码及记录时间
+type ExitStatus = (Option<i32>, Instant);
+
+/// 商店启动的 `ll-cli run` 进程的退出码，按 appId 记录
+static EXIT_STATUS: Lazy<Mutex<HashMap<String, ExitStatus>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 应用启动/退出事件
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRunEvent {
+    pub app_id: String,
+    pub container_id: String,
+    pub pid: String,
+    /// 首次发现运行的时间（Unix 毫秒）
+    pub started_at: u64,
+    /// 仅对由商店启动的应用可用
+    pub exit_code: Option<i32>,
+    /// 运行时长，仅 app-exited 事件有值
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct TrackedApp {
+    event: AppRunEvent,
+    pid: Option<u32>,
+}
+
+/// 容器 ID 为空时（旧版 ll-cli）以 pid 作为键
+fn entry_key(entry: &PsEntry) -> String {
+    if entry.container_id.is_empty() {
+        format!("pid:{}", entry.pid)
+    } else {
+        entry.container_id.clone()
+    }
+}
+
+/// 比较已跟踪的容器与最新的 `ll-cli ps` 结果，返回新出现的容器与已消失容器的键
+fn diff_entries<'a>(
+    tracked: &HashMap<String, TrackedApp>,
+    entries: &'a [PsEntry],
+) -> (Vec<&'a PsEntry>, Vec<String>) {
+    let current: HashMap<String, &PsEntry> = entries.iter().map(|e| (entry_key(e), e)).collect();
+    let started = entries
+        .iter()
+        .filter(|e| !tracked.contains_key(&entry_key(e)))
+        .collect();
+    let mut exited: Vec<String> = tracked
+        .keys()
+        .filter(|key| !current.contains_key(*key))
+        .cloned()
+        .collect();
+    exited.sort();
+    (started, exited)
+}
+
+/// 唤醒监视器，立即检查运行中的应用
+pub fn wake_app_watcher() {
+    WAKE.notify_one();
+}
+
+/// 记录商店启动的 `ll-cli run` 进程退出码，供 app-exited 事件使用
+pub(crate) fn record_exit_status(app_id: &str, code: Option<i32>) {
+    if let Ok(mut map) = EXIT_STATUS.lock() {
+        map.retain(|_, (_, at)| at.elapsed() < EXIT_STATUS_TTL);
+        map.insert(app_id.to_string(), (code, Instant::now()));
+    }
+}
+
+fn take_exit_status(app_id: &str) -> Option<i32> {
+    let mut map = EXIT_STATUS.lock().ok()?;
+    match map.remove(app_id) {
+        Some((code, at)) if at.elapsed() < EXIT_STATUS_TTL => code,
+        _ => None,
+    }
+}
+
+struct Watcher {
+    app_handle: AppHandle,
+    tracked: HashMap<String, TrackedApp>,
+}
+
+impl Watcher {
+    /// 比对 `ll-cli ps` 结果并发送事件
+    async fn sync(&mut self) {
+        let entries = match tokio::task::spawn_blocking(list_ps).await {
+            Ok(Ok(entries)) => entries,
+            Ok(Err(e)) => {
+                warn!("[AppWatcher] {}", e);
+                return;
+            }
+            Err(e) => {
+                warn!("[AppWatcher] ll-cli ps task failed: {}", e);
+                return;
+            }
+        };
+
+        let (started, exited) = diff_entries(&self.tracked, &entries);
+        let started: Vec<PsEntry> = started.into_iter().cloned().collect();
+        for key in exited {
+            self.exit(&key);
+        }
+        for entry in started {
+            let event = AppRunEvent {
+                app_id: AppRef::parse(&entry.app).id,
+                container_id: entry.container_id.clone(),
+                pid: entry.pid.clone(),
+                started_at: now_millis(),
+                ..Default::default()
+            };
+            info!("[AppWatcher] Started: {} ({})", event.app_id, event.pid);
+            let _ = self.app_handle.emit(APP_STARTED_EVENT, &event);
+            self.tracked.insert(
+                entry_key(&entry),
+                TrackedApp {
+                    pid: entry.pid.trim().parse().ok(),
+                    event,
+                },
+            );
+        }
+    }
+
+    fn exit(&mut self, key: &str) {
+        let Some(tracked) = self.tracked.remove(key) else {
+            return;
+        };
+        let mut event = tracked.event;
+        event.exit_code = take_exit_status(&event.app_id);
+        event.duration_ms = Some(now_millis().saturating_sub(event.started_at));
+        info!("[AppWatcher] Exited: {} after {:?} ms", event.app_id, event.duration_ms);
+        let _ = self.app_handle.emit(APP_EXITED_EVENT, &event);
+    }
+
+    /// 是否有已跟踪的进程退出
+    fn any_dead(&self) -> bool {
+        self.tracked
+            .values()
+            .any(|tracked| tracked.pid.is_some_and(|pid| !is_alive(pid)))
+    }
+
+    async fn run(mut self) {
+        let mut active_until = Instant::now() + SETTLE_PERIOD;
+        loop {
+            if self.tracked.is_empty() && Instant::now() >= active_until {
+                info!("[AppWatcher] No running apps, idle");
+                WAKE.notified().await;
+                active_until = Instant::now() + SETTLE_PERIOD;
+            }
+
+            self.sync().await;
+
+            for _ in 0..PS_EVERY {
+                tokio::select! {
+                    _ = tokio::time::sleep(LIVENESS_INTERVAL) => {}
+                    _ = WAKE.notified() => {
+                        active_until = Instant::now() + SETTLE_PERIOD;
+                        break;
+                    }
+                }
+                if self.any_dead() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// 启动运行中应用监视器，重复调用无效
+pub fn start_app_watcher(app_handle: AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("[AppWatcher] Starting");
+    let watcher = Watcher {
+        app_handle,
+        tracked: HashMap::new(),
+    };
+    tauri::async_runtime::spawn(watcher.run());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(app: &str, container_id: &str, pid: &str) -> PsEntry {
+        PsEntry {
+            app: app.to_string(),
+            container_id: container_id.to_string(),
+            pid: pid.to_string(),
+        }
+    }
+
+    fn tracked(app_id: &str) -> TrackedApp {
+        TrackedApp {
+            event: AppRunEvent {
+                app_id: app_id.to_string(),
+                ..Default::default()
+            },
+            pid: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_entries() {
+        let mut current = HashMap::new();
+        current.insert("c1".to_string(), tracked("org.a"));
+        current.insert("c2".to_string(), tracked("org.b"));
+
+        let entries = vec![entry("org.a", "c1", "1"), entry("org.c", "c3", "3"), entry("org.d", "", "4")];
+        let (started, exited) = diff_entries(&current, &entries);
+        let started: Vec<&str> = started.iter().map(|e| e.app.as_str()).collect();
+        assert_eq!(started, vec!["org.c", "org.d"]);
+        assert_eq!(exited, vec!["c2"]);
+    }
+
+    #[test]
+    fn test_exit_status_is_taken_once() {
+        record_exit_status("org.example.watch", Some(1));
+        assert_eq!(take_exit_status("org.example.watch"), Some(1));
+        assert_eq!(take_exit_status("org.example.watch"), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! 无人值守自动更新
+//!
+//! 默认关闭。开启后由后台检查更新流程调用，按应用白名单/黑名单筛选可更新应用，
+//! 逐个通过 `ll-cli upgrade` 升级（不弹出前端安装界面）。正在运行的应用及因安装槽位
+//! 被占用而未能更新的应用记入待重试列表，在之后的检查中优先重试。
+//! 每次执行（包括没有可更新应用的情况）的结果都写入操作历史。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::services::history::{record_operation, OperationItem, OperationRecord};
+use crate::services::install::{upgrade_linglong_app, UpgradeError};
+use crate::services::process::get_running_linglong_apps;
+use crate::services::updates::{check_app_updates, AppUpdateInfo};
+use crate::utils::paths::app_data_file;
+use crate::utils::time::now_millis;
+
+/// 自动更新策略文件
+const POLICY_FILE: &str = "auto-update.json";
+/// 待重试应用记录文件
+const PENDING_FILE: &str = "auto-update-pending.json";
+/// 操作历史中的类型
+const OPERATION_KIND: &str = "auto-update";
+
+/// 同一时间只允许一次自动更新
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 自动更新策略
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoUpdatePolicy {
+    pub enabled: bool,
+    /// 仅自动更新这些应用，为空时表示所有应用
+    pub include: Vec<String>,
+    /// 不自动更新的应用，优先于 include
+    pub exclude: Vec<String>,
+}
+
+impl AutoUpdatePolicy {
+    pub fn allows(&self, app_id: &str) -> bool {
+        if self.exclude.iter().any(|id| id == app_id) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|id| id == app_id)
+    }
+}
+
+/// 单次自动更新的结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoUpdateSummary {
+    pub updated: Vec<OperationItem>,
+    pub failed: Vec<OperationItem>,
+    pub skipped: Vec<OperationItem>,
+    /// 待重试的应用
+    pub pending: Vec<String>,
+}
+
+fn read_json<T: for<'de> Deserialize<'de> + Default>(path: &Path) -> T {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_json<T: Serialize>(name: &str, value: &T) -> Result<(), String> {
+    let path = app_data_file(name)?;
+    let content = serde_json::to_string_pretty(value).map_err(|e| format!("序列化失败: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("写入 {:?} 失败: {}", path, e))
+}
+
+pub(crate) fn load_policy() -> AutoUpdatePolicy {
+    app_data_file(POLICY_FILE)
+        .map(|path| read_json(&path))
+        .unwrap_or_default()
+}
+
+fn load_pending() -> BTreeSet<String> {
+    app_data_file(PENDING_FILE)
+        .map(|path| read_json(&path))
+        .unwrap_or_default()
+}
+
+/// 是否有等待重试的应用
+pub(crate) fn has_pending() -> bool {
+    !load_pending().is_empty()
+}
+
+/// 按策略筛选需要自动更新的应用，待重试的应用排在前面
+///
+/// 仅处理 binary 模块，每个应用只取一条。
+pub(crate) fn plan_updates(
+    updates: &[AppUpdateInfo],
+    policy: &AutoUpdatePolicy,
+    pending: &BTreeSet<String>,
+) -> Vec<AppUpdateInfo> {
+    let mut seen = BTreeSet::new();
+    let mut planned: Vec<AppUpdateInfo> = updates
+        .iter()
+        .filter(|u| u.module == "binary" && policy.allows(&u.app_id))
+        .filter(|u| seen.insert(u.app_id.clone()))
+        .cloned()
+        .collect();
+    planned.sort_by_key(|u| !pending.contains(&u.app_id));
+    planned
+}
+
+fn item(update: &AppUpdateInfo, status: &str, message: Option<String>) -> OperationItem {
+    OperationItem {
+        app_id: update.app_id.clone(),
+        from_version: Some(update.current_version.clone()),
+        to_version: Some(update.latest_version.clone()),
+        status: status.to_string(),
+        message,
+    }
+}
+
+/// 清除 RUNNING 标记
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 根据检查结果执行自动更新
+pub async fn run_auto_update(updates: &[AppUpdateInfo]) -> Result<AutoUpdateSummary, String> {
+    let policy = load_policy();
+    if !policy.enabled {
+        return Err("自动更新未开启".to_string());
+    }
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("自动更新正在进行".to_string());
+    }
+    let _guard = RunningGuard;
+
+    let started_at = now_millis();
+    let mut pending = load_pending();
+    let planned = plan_updates(updates, &policy, &pending);
+    // 已没有可更新版本的应用不再重试
+    pending.retain(|id| planned.iter().any(|u| &u.app_id == id));
+    // 本次执行前已在待重试列表中的应用
+    let retrying = pending.clone();
+
+    let mut summary = AutoUpdateSummary::default();
+    if planned.is_empty() {
+        let _ = write_json(PENDING_FILE, &pending);
+        finish(started_at, &summary, &retrying);
+        return Ok(summary);
+    }
+
+    info!("[AutoUpdate] {} app(s) planned", planned.len());
+    let running: BTreeSet<String> = match get_running_linglong_apps().await {
+        Ok(apps) => apps.into_iter().map(|app| app.name).collect(),
+        Err(e) => {
+            // 无法确认运行状态时不冒险更新
+            warn!("[AutoUpdate] Failed to list running apps: {}", e);
+            for update in &planned {
+                pending.insert(update.app_id.clone());
+                summary
+                    .skipped
+                    .push(item(update, "skipped", Some(format!("无法获取运行中的应用: {}", e))));
+            }
+            summary.pending = pending.iter().cloned().collect();
+            let _ = write_json(PENDING_FILE, &pending);
+            finish(started_at, &summary, &retrying);
+            return Ok(summary);
+        }
+    };
+
+    for update in &planned {
+        if running.contains(&update.app_id) {
+            info!("[AutoUpdate] Skip running app: {}", update.app_id);
+            pending.insert(update.app_id.clone());
+            summary
+                .skipped
+                .push(item(update, "skipped", Some("应用正在运行".to_string())));
+            continue;
+        }
+
+        let result = upgrade_linglong_app(update.app_id.clone()).await;
+        match result {
+            Ok(_) => {
+                info!("[AutoUpdate] Updated {} to {}", update.app_id, update.latest_version);
+                pending.remove(&update.app_id);
+                summary.updated.push(item(update, "success", None));
+            }
+            // 用户正在手动安装其他应用，稍后重试
+            Err(UpgradeError::Busy(e)) => {
+                pending.insert(update.app_id.clone());
+                summary.skipped.push(item(update, "skipped", Some(e)));
+            }
+            Err(UpgradeError::Failed(e)) => {
+                warn!("[AutoUpdate] Failed to update {}: {}", update.app_id, e);
+                pending.remove(&update.app_id);
+                summary.failed.push(item(update, "failed", Some(e)));
+            }
+        }
+    }
+
+    summary.pending = pending.iter().cloned().collect();
+    if let Err(e) = write_json(PENDING_FILE, &pending) {
+        warn!("[AutoUpdate] {}", e);
+    }
+    finish(started_at, &summary, &retrying);
+    Ok(summary)
+}
+
+/// 操作历史中的摘要，重试的应用单独计数
+fn summary_text(summary: &AutoUpdateSummary, retrying: &BTreeSet<String>) -> String {
+    let mut text = format!(
+        "自动更新：成功 {} 个，失败 {} 个，跳过 {} 个",
+        summary.updated.len(),
+        summary.failed.len(),
+        summary.skipped.len()
+    );
+    if !retrying.is_empty() {
+        text.push_str(&format!("（其中重试 {} 个）", retrying.len()));
+    }
+    text
+}
+
+/// 写入操作历史，没有可更新的应用时也记录
+fn finish(started_at: u64, summary: &AutoUpdateSummary, retrying: &BTreeSet<String>) {
+    let text = summary_text(summary, retrying);
+    info!("[AutoUpdate] {}", text);
+    record_operation(&OperationRecord {
+        kind: OPERATION_KIND.to_string(),
+        started_at,
+        finished_at: now_millis(),
+        summary: text,
+        items: summary
+            .updated
+            .iter()
+            .chain(summary.failed.iter())
+            .chain(summary.skipped.iter())
+            .cloned()
+            .map(|mut item| {
+                // 标记重试的应用
+                if retrying.contains(&item.app_id) {
+                    item.message = Some(match item.message {
+                        Some(message) => format!("重试：{}", message),
+                        None => "重试".to_string(),
+                    });
+                }
+                item
+            })
+            .collect(),
+    });
+}
+
+/// 立即检查更新并执行一次自动更新
+pub async fn run_auto_update_now() -> Result<AutoUpdateSummary, String> {
+    let check = check_app_updates(None).await?;
+    if !check.failed.is_empty() {
+        warn!("[AutoUpdate] Update check failed for: {}", check.failed.join(", "));
+    }
+    run_auto_update(&check.updates).await
+}
+
+/// 获取自动更新策略
+pub async fn get_auto_update_policy() -> Result<AutoUpdatePolicy, String> {
+    Ok(load_policy())
+}
+
+/// 保存自动更新策略
+pub async fn set_auto_update_policy(policy: AutoUpdatePolicy) -> Result<AutoUpdatePolicy, String> {
+    if let Some(id) = policy.include.iter().find(|id| policy.exclude.contains(id)) {
+        return Err(format!("应用 {} 不能同时在自动更新列表和排除列表中", id));
+    }
+    write_json(POLICY_FILE, &policy)?;
+    info!("[AutoUpdate] Policy saved: {:?}", policy);
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(app_id: &str, module: &str) -> AppUpdateInfo {
+        AppUpdateInfo::test(app_id, "1.1.0.0").with_module(module)
+    }
+
+    #[test]
+    fn test_policy_allows() {
+        let all = AutoUpdatePolicy {
+            enabled: true,
+            include: vec![],
+            exclude: vec!["org.b".to_string()],
+        };
+        assert!(all.allows("org.a"));
+        assert!(!all.allows("org.b"));
+
+        let only = AutoUpdatePolicy {
+            enabled: true,
+            include: vec!["org.a".to_string()],
+            exclude: vec![],
+        };
+        assert!(only.allows("org.a"));
+        assert!(!only.allows("org.c"));
+    }
+
+    #[test]
+    fn test_summary_text_counts_retries() {
+        let summary = AutoUpdateSummary {
+            skipped: vec![item(&update("org.a", "binary"), "skipped", None)],
+            ..Default::default()
+        };
+        assert_eq!(summary_text(&summary, &BTreeSet::new()), "自动更新：成功 0 个，失败 0 个，跳过 1 个");
+        let retrying: BTreeSet<String> = ["org.a".to_string()].into_iter().collect();
+        assert_eq!(
+            summary_text(&summary, &retrying),
+            "自动更新：成功 0 个，失败 0 个，跳过 1 个（其中重试 1 个）"
+        );
+    }
+
+    #[test]
+    fn test_plan_updates() {
+        let updates = vec![
+            update("org.a", "binary"),
+            update("org.a", "develop"),
+            update("org.b", "binary"),
+            update("org.c", "binary"),
+        ];
+        let policy = AutoUpdatePolicy {
+            enabled: true,
+            include: vec![],
+            exclude: vec!["org.b".to_string()],
+        };
+        let pending: BTreeSet<String> = ["org.c".to_string()].into_iter().collect();
+        let planned: Vec<String> = plan_updates(&updates, &policy, &pending)
+            .into_iter()
+            .map(|u| u.app_id)
+            .collect();
+        // 待重试的排在前面，排除列表和 develop 模块被过滤
+        assert_eq!(planned, vec!["org.c", "org.a"]);
+    }
+}
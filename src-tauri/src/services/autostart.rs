@@ -0,0 +1,387 @@
+//! 应用开机自启动
+//!
+//! 以应用导出的桌面入口为模板写入 `~/.config/autostart/<appId>.desktop`，
+//! 可设置延迟启动、最小化启动与附加参数。最小化启动使用应用在桌面入口中声明的参数。
+//! 自启动选项另存于商店数据目录，用于在列表中回显。
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use crate::services::desktop_entry::{exec_app_id, parse_desktop_entry, primary_desktop_file, quote_exec_arg, split_exec};
+use crate::utils::paths::{app_data_file, config_dir};
+
+/// 自启动选项文件
+const OPTIONS_FILE: &str = "autostart.json";
+/// 商店写入的自启动文件标记
+const MANAGED_KEY: &str = "X-Linglong-Store-Autostart";
+/// 延迟上限（秒）
+const MAX_DELAY_SECONDS: u32 = 600;
+/// 应用声明最小化启动参数的键
+const MINIMIZE_ARG_KEY: &str = "X-Linglong-Minimize-Arg";
+/// 桌面动作中常见的最小化（启动到托盘）参数
+const MINIMIZE_ARGS: [&str; 6] = [
+    "--minimized",
+    "--start-minimized",
+    "--hidden",
+    "--tray",
+    "--start-in-tray",
+    "--silent",
+];
+
+/// 自启动选项
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutostartOptions {
+    /// 登录后延迟启动的秒数
+    pub delay_seconds: Option<u32>,
+    /// 最小化启动，追加应用声明的最小化参数
+    pub start_minimized: bool,
+    /// 附加到应用命令后的其他参数
+    pub args: Vec<String>,
+}
+
+/// 一个自启动的玲珑应用
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartEntry {
+    pub app_id: String,
+    pub path: String,
+    /// Hidden=true 或 X-GNOME-Autostart-enabled=false 时为 false
+    pub enabled: bool,
+    /// 是否由商店创建
+    pub managed: bool,
+    pub options: AutostartOptions,
+}
+
+fn autostart_dir() -> Result<PathBuf, String> {
+    config_dir()
+        .map(|dir| dir.join("autostart"))
+        .ok_or_else(|| "无法确定用户配置目录".to_string())
+}
+
+fn load_options() -> BTreeMap<String, AutostartOptions> {
+    app_data_file(OPTIONS_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_options(options: &BTreeMap<String, AutostartOptions>) -> Result<(), String> {
+    let path = app_data_file(OPTIONS_FILE)?;
+    let content = serde_json::to_string_pretty(options).map_err(|e| format!("序列化失败: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("写入 {:?} 失败: {}", path, e))
+}
+
+/// 应用声明的最小化启动参数
+///
+/// 优先取 `X-Linglong-Minimize-Arg`，否则在桌面动作的 Exec 中查找常见的最小化参数。
+fn minimize_arg(content: &str) -> Option<String> {
+    if let Some(arg) = parse_desktop_entry(content).get(MINIMIZE_ARG_KEY).filter(|a| !a.is_empty()) {
+        return Some(arg.clone());
+    }
+    let mut in_action = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_action = line.starts_with("[Desktop Action ");
+            continue;
+        }
+        let Some(exec) = line.strip_prefix("Exec=").filter(|_| in_action) else {
+            continue;
+        };
+        let found = split_exec(exec)
+            .into_iter()
+            .skip_while(|t| t != "--")
+            .find(|t| MINIMIZE_ARGS.contains(&t.as_str()));
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+/// 生成自启动 Exec：去掉文件/URL 占位符，在应用命令后追加参数，需要延迟时用 sh 包装
+fn build_exec(exec: &str, args: &[String], delay_seconds: Option<u32>) -> Result<String, String> {
+    let mut tokens: Vec<String> = split_exec(exec)
+        .into_iter()
+        .filter(|t| !(t.len() == 2 && t.starts_with('%') && t != "%%"))
+        .map(|t| t.replace("%%", "%"))
+        .collect();
+
+    if !args.is_empty() {
+        // 未指定应用命令时追加 `--` 会改变 ll-cli 启动的程序
+        if !tokens.iter().any(|t| t == "--") {
+            return Err("该应用的启动命令不支持附加参数".to_string());
+        }
+        if let Some(arg) = args.iter().find(|a| a.contains('\n')) {
+            return Err(format!("参数不能包含换行: {:?}", arg));
+        }
+        tokens.extend(args.iter().cloned());
+    }
+
+    if let Some(delay) = delay_seconds.filter(|d| *d > 0) {
+        // 参数以位置参数传给 sh，无需再次转义
+        let mut wrapped = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("sleep {}; exec \"$0\" \"$@\"", delay),
+        ];
+        wrapped.extend(tokens);
+        tokens = wrapped;
+    }
+    Ok(tokens.iter().map(|t| quote_exec_arg(t)).collect::<Vec<_>>().join(" "))
+}
+
+/// 由应用桌面入口生成自启动文件内容，仅保留 `[Desktop Entry]` 分组
+fn build_autostart_entry(content: &str, options: &AutostartOptions) -> Result<String, String> {
+    let fields = parse_desktop_entry(content);
+    let exec = fields
+        .get("Exec")
+        .ok_or_else(|| "桌面入口缺少 Exec".to_string())?;
+    let mut args = Vec::new();
+    if options.start_minimized {
+        args.push(minimize_arg(content).ok_or_else(|| "该应用未声明最小化启动参数".to_string())?);
+    }
+    for arg in &options.args {
+        if !args.contains(arg) {
+            args.push(arg.clone());
+        }
+    }
+    let exec = build_exec(exec, &args, options.delay_seconds)?;
+
+    const DROPPED_KEYS: [&str; 5] = ["Exec", "TryExec", "Actions", "Hidden", "DBusActivatable"];
+    let mut lines = vec!["[Desktop Entry]".to_string()];
+    let mut in_main_group = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_main_group = trimmed == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group || trimmed.is_empty() {
+            continue;
+        }
+        let key = trimmed.split_once('=').map(|(k, _)| k.trim()).unwrap_or("");
+        let base_key = key.split('[').next().unwrap_or(key);
+        if DROPPED_KEYS.contains(&base_key) || base_key.starts_with("X-GNOME-Autostart") {
+            continue;
+        }
+        lines.push(trimmed.to_string());
+    }
+    lines.push(format!("Exec={}", exec));
+    // 延迟只由 Exec 中的 sleep 实现；同时写 X-GNOME-Autostart-Delay 会在 GNOME 下叠加两次
+    lines.push("X-GNOME-Autostart-enabled=true".to_string());
+    lines.push(format!("{}=true", MANAGED_KEY));
+    Ok(lines.join("\n") + "\n")
+}
+
+/// 解析自启动文件，不是玲珑应用时返回 None
+fn read_autostart_entry(path: &Path) -> Option<AutostartEntry> {
+    let content = fs::read_to_string(path).ok()?;
+    let fields = parse_desktop_entry(&content);
+    let exec = fields.get("Exec")?;
+    let app_id = exec_app_id(exec)?;
+    let is = |key: &str, value: &str| fields.get(key).is_some_and(|v| v == value);
+    Some(AutostartEntry {
+        app_id,
+        path: path.to_string_lossy().to_string(),
+        enabled: !is("Hidden", "true") && !is("X-GNOME-Autostart-enabled", "false"),
+        managed: is(MANAGED_KEY, "true"),
+        options: AutostartOptions::default(),
+    })
+}
+
+fn scan_autostart_dir(dir: &Path) -> Vec<AutostartEntry> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<AutostartEntry> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+        .filter_map(|path| read_autostart_entry(&path))
+        .collect();
+    entries.sort_by(|a, b| a.app_id.cmp(&b.app_id));
+    entries
+}
+
+/// 列出当前会自启动的玲珑应用
+pub async fn list_autostart_apps() -> Result<Vec<AutostartEntry>, String> {
+    let options = load_options();
+    Ok(scan_autostart_dir(&autostart_dir()?)
+        .into_iter()
+        .map(|mut entry| {
+            if entry.managed {
+                entry.options = options.get(&entry.app_id).cloned().unwrap_or_default();
+            }
+            entry
+        })
+        .collect())
+}
+
+/// 开启应用自启动
+///
+/// # Arguments
+/// * `app_id` - 应用 ID
+/// * `options` - 延迟与附加参数
+pub async fn enable_autostart(app_id: String, options: AutostartOptions) -> Result<AutostartEntry, String> {
+    if app_id.is_empty() || app_id.contains('/') || app_id.starts_with('.') {
+        return Err(format!("应用 ID 不合法: {}", app_id));
+    }
+    if options.delay_seconds.is_some_and(|d| d > MAX_DELAY_SECONDS) {
+        return Err(format!("延迟不能超过 {} 秒", MAX_DELAY_SECONDS));
+    }
+
+    let id = app_id.clone();
+    let source = tokio::task::spawn_blocking(move || primary_desktop_file(&id))
+        .await
+        .map_err(|e| format!("读取桌面入口任务执行失败: {}", e))??;
+    let content = fs::read_to_string(&source).map_err(|e| format!("读取 {:?} 失败: {}", source, e))?;
+    let entry = build_autostart_entry(&content, &options)?;
+
+    let dir = autostart_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建目录 {:?} 失败: {}", dir, e))?;
+    let path = dir.join(format!("{}.desktop", app_id));
+    fs::write(&path, entry).map_err(|e| format!("写入 {:?} 失败: {}", path, e))?;
+    let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o644));
+
+    let mut all = load_options();
+    all.insert(app_id.clone(), options.clone());
+    save_options(&all)?;
+    info!("[Autostart] Enabled for {}: {:?}", app_id, options);
+
+    Ok(AutostartEntry {
+        app_id,
+        path: path.to_string_lossy().to_string(),
+        enabled: true,
+        managed: true,
+        options,
+    })
+}
+
+/// 关闭应用自启动，删除自启动目录中所有启动该应用的文件
+pub async fn disable_autostart(app_id: String) -> Result<Vec<String>, String> {
+    let mut removed = Vec::new();
+    for entry in scan_autostart_dir(&autostart_dir()?) {
+        if entry.app_id != app_id {
+            continue;
+        }
+        fs::remove_file(&entry.path).map_err(|e| format!("删除 {} 失败: {}", entry.path, e))?;
+        removed.push(entry.path);
+    }
+
+    let mut all = load_options();
+    if all.remove(&app_id).is_some() {
+        save_options(&all)?;
+    }
+    info!("[Autostart] Disabled for {}: {:?}", app_id, removed);
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRY: &str = "[Desktop Entry]\n\
+        Type=Application\n\
+        Name=Chat\n\
+        Name[zh_CN]=聊天\n\
+        Exec=/usr/bin/ll-cli run org.example.chat -- chat %U\n\
+        Actions=new;\n\
+        Icon=chat\n\
+        \n\
+        [Desktop Action new]\n\
+        Exec=/usr/bin/ll-cli run org.example.chat -- chat --new\n";
+
+    #[test]
+    fn test_build_autostart_entry() {
+        let options = AutostartOptions {
+            delay_seconds: None,
+            start_minimized: false,
+            args: vec!["--minimized".to_string()],
+        };
+        let content = build_autostart_entry(ENTRY, &options).unwrap();
+        assert!(content.contains("Exec=/usr/bin/ll-cli run org.example.chat -- chat --minimized\n"));
+        assert!(content.contains("Name[zh_CN]=聊天\n"));
+        assert!(!content.contains("Actions"));
+        assert!(!content.contains("Desktop Action"));
+        assert!(content.contains("X-Linglong-Store-Autostart=true"));
+    }
+
+    #[test]
+    fn test_build_exec_with_delay() {
+        let args = vec!["--start in tray".to_string()];
+        let exec = build_exec("ll-cli run org.a -- a %F", &args, Some(10)).unwrap();
+        assert_eq!(
+            split_exec(&exec),
+            vec!["sh", "-c", "sleep 10; exec \"$0\" \"$@\"", "ll-cli", "run", "org.a", "--", "a", "--start in tray"]
+        );
+        assert_eq!(exec_app_id(&exec).as_deref(), Some("org.a"));
+
+        // 没有 `--` 时无法安全附加参数
+        assert!(build_exec("ll-cli run org.a", &args, None).is_err());
+    }
+
+    #[test]
+    fn test_autostart_delay_applied_once() {
+        let options = AutostartOptions {
+            delay_seconds: Some(15),
+            start_minimized: false,
+            args: Vec::new(),
+        };
+        let content = build_autostart_entry(ENTRY, &options).unwrap();
+        assert_eq!(content.matches("15").count(), 1);
+        assert_eq!(content.matches("sleep 15;").count(), 1);
+        assert!(!content.contains("X-GNOME-Autostart-Delay"));
+    }
+
+    #[test]
+    fn test_start_minimized() {
+        let options = AutostartOptions {
+            start_minimized: true,
+            ..Default::default()
+        };
+        // 桌面动作中声明的最小化参数
+        let with_action = format!(
+            "{}\n[Desktop Action tray]\nExec=/usr/bin/ll-cli run org.example.chat -- chat --start-minimized\n",
+            ENTRY
+        );
+        let content = build_autostart_entry(&with_action, &options).unwrap();
+        assert!(content.contains("Exec=/usr/bin/ll-cli run org.example.chat -- chat --start-minimized\n"));
+
+        // 显式声明的参数优先
+        let declared = ENTRY.replace("Icon=chat\n", "Icon=chat\nX-Linglong-Minimize-Arg=--background\n");
+        let content = build_autostart_entry(&declared, &options).unwrap();
+        assert!(content.contains("-- chat --background\n"));
+
+        // 未声明时不猜测参数
+        assert!(build_autostart_entry(ENTRY, &options).is_err());
+    }
+
+    #[test]
+    fn test_scan_autostart_dir() {
+        let dir = std::env::temp_dir().join(format!("ll-autostart-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let managed = build_autostart_entry(ENTRY, &AutostartOptions::default()).unwrap();
+        fs::write(dir.join("org.example.chat.desktop"), managed).unwrap();
+        fs::write(
+            dir.join("other.desktop"),
+            "[Desktop Entry]\nExec=ll-cli run org.example.sync\nHidden=true\n",
+        )
+        .unwrap();
+        fs::write(dir.join("native.desktop"), "[Desktop Entry]\nExec=/usr/bin/foo\n").unwrap();
+
+        let entries = scan_autostart_dir(&dir);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].app_id, "org.example.chat");
+        assert!(entries[0].managed && entries[0].enabled);
+        assert_eq!(entries[1].app_id, "org.example.sync");
+        assert!(!entries[1].managed && !entries[1].enabled);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
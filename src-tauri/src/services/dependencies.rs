@@ -0,0 +1,533 @@
+//! 应用、运行时与基础环境之间的依赖关系
+//!
+//! 根据 `get_installed_apps(true)` 与 `ll-cli info` 中的 runtime/base 引用构建依赖图，
+//! 并记录安装原因（用户主动安装或作为依赖被安装），在卸载前检查是否会破坏其他应用。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs;
+
+use crate::services::app_info::{get_app_infos, AppRef};
+use crate::services::install::{get_installed_apps, InstalledApp};
+use crate::services::version::compare_version_str;
+use crate::utils::paths::app_data_file;
+
+/// 安装原因记录文件
+const REASONS_FILE: &str = "install-reasons.json";
+/// 用户主动安装
+const REASON_EXPLICIT: &str = "explicit";
+/// 作为依赖被安装
+const REASON_DEPENDENCY: &str = "dependency";
+
+/// 依赖图中的节点，同一应用的同一版本的各模块合并为一个节点
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyNode {
+    pub app_id: String,
+    pub version: String,
+    /// app、runtime 或 base
+    pub kind: String,
+    pub modules: Vec<String>,
+    /// "explicit" | "dependency"
+    pub reason: String,
+    /// 直接依赖的节点，格式为 appId/version
+    pub dependencies: Vec<String>,
+    /// 直接依赖该节点的节点
+    pub dependents: Vec<String>,
+    /// 未能匹配到已安装项的引用
+    pub missing: Vec<String>,
+}
+
+/// 依赖图
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    /// 未能获取 `ll-cli info` 的节点，它们使用的 base 未知
+    pub missing_info: Vec<String>,
+}
+
+/// 卸载检查结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallCheck {
+    pub app_id: String,
+    pub version: String,
+    /// 卸载后没有应用会因此无法运行
+    pub safe: bool,
+    /// 卸载后会失去依赖的节点（包括间接依赖）
+    pub dependents: Vec<DependencyNode>,
+    /// 依赖信息未知、可能受影响的节点
+    pub missing_info: Vec<String>,
+    pub message: String,
+}
+
+fn node_key(app_id: &str, version: &str) -> String {
+    format!("{}/{}", app_id, version)
+}
+
+fn is_base_service(app: &InstalledApp) -> bool {
+    matches!(app.kind.as_deref(), Some("runtime") | Some("base"))
+}
+
+/// 引用的版本是否与已安装版本匹配（引用可能只写前几段，如 `23.1.0` 匹配 `23.1.0.2`）
+fn version_matches(reference: &str, installed: &str) -> bool {
+    let reference: Vec<&str> = reference.split('.').collect();
+    let installed: Vec<&str> = installed.split('.').collect();
+    reference.len() <= installed.len() && reference.iter().zip(installed.iter()).all(|(a, b)| a == b)
+}
+
+/// 将 runtime/base 引用解析为已安装的运行时或基础环境
+///
+/// 同一引用匹配多个已安装版本时取最新版本，与 ll-cli 的解析方式一致。
+pub(crate) fn resolve_reference<'a>(installed: &'a [InstalledApp], reference: &str) -> Option<&'a InstalledApp> {
+    let app_ref = AppRef::parse(reference);
+    let ref_version = app_ref.version.unwrap_or_default();
+    installed
+        .iter()
+        .filter(|app| is_base_service(app) && app.app_id == app_ref.id)
+        .filter(|app| ref_version.is_empty() || version_matches(&ref_version, &app.version))
+        .max_by(|a, b| compare_version_str(&a.version, &b.version))
+}
+
+/// 收集各应用与运行时引用的 runtime/base，按 appId/version 分组
+///
+/// base 不引用其他层，不查询。无法获取 `ll-cli info` 的项只保留 list 中的 runtime 字段，
+/// 其使用的 base 未知，这些项以 appId/version 列在返回值的第二项中。
+pub(crate) async fn collect_references(installed: &[InstalledApp]) -> (HashMap<String, Vec<String>>, Vec<String>) {
+    let mut holders: Vec<&InstalledApp> = Vec::new();
+    let mut seen = HashSet::new();
+    for app in installed.iter().filter(|app| app.kind.as_deref() != Some("base")) {
+        if seen.insert(node_key(&app.app_id, &app.version)) {
+            holders.push(app);
+        }
+    }
+    let queries: Vec<String> = holders
+        .iter()
+        .map(|app| node_key(&app.app_id, &app.version))
+        .collect();
+    let infos = get_app_infos(&queries).await;
+
+    let mut references: HashMap<String, Vec<String>> = HashMap::new();
+    let mut missing_info = Vec::new();
+    for ((app, key), info) in holders.iter().zip(queries).zip(infos) {
+        let mut refs = vec![app.runtime.clone()];
+        match info {
+            Some(info) => {
+                refs.push(info.base);
+                refs.push(info.runtime);
+            }
+            None => {
+                warn!("[Dependencies] Missing info for {}, its base is unknown", key);
+                missing_info.push(key.clone());
+            }
+        }
+        refs.retain(|r| !r.is_empty());
+        refs.dedup();
+        references.insert(key, refs);
+    }
+    (references, missing_info)
+}
+
+fn load_reasons() -> BTreeMap<String, String> {
+    app_data_file(REASONS_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_reasons(reasons: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = app_data_file(REASONS_FILE)?;
+    let content = serde_json::to_string_pretty(reasons).map_err(|e| format!("序列化失败: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("写入 {:?} 失败: {}", path, e))
+}
+
+/// 记录用户主动安装的应用，在商店安装成功后调用
+pub(crate) fn record_explicit_install(app_id: &str) {
+    let mut reasons = load_reasons();
+    if reasons.get(app_id).map(String::as_str) == Some(REASON_EXPLICIT) {
+        return;
+    }
+    reasons.insert(app_id.to_string(), REASON_EXPLICIT.to_string());
+    if let Err(e) = save_reasons(&reasons) {
+        warn!("[Dependencies] Failed to record install reason for {}: {}", app_id, e);
+    }
+}
+
+/// 安装原因：优先使用记录，没有记录时应用视为主动安装，运行时与基础环境视为依赖
+fn install_reason(app: &InstalledApp, reasons: &BTreeMap<String, String>) -> String {
+    match reasons.get(&app.app_id) {
+        Some(reason) => reason.clone(),
+        None if is_base_service(app) => REASON_DEPENDENCY.to_string(),
+        None => REASON_EXPLICIT.to_string(),
+    }
+}
+
+fn build_graph(
+    installed: &[InstalledApp],
+    references: &HashMap<String, Vec<String>>,
+    reasons: &BTreeMap<String, String>,
+) -> DependencyGraph {
+    let mut nodes: Vec<DependencyNode> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for app in installed {
+        let key = node_key(&app.app_id, &app.version);
+        let position = *index.entry(key).or_insert_with(|| {
+            nodes.push(DependencyNode {
+                app_id: app.app_id.clone(),
+                version: app.version.clone(),
+                kind: app.kind.clone().unwrap_or_else(|| "app".to_string()),
+                reason: install_reason(app, reasons),
+                ..Default::default()
+            });
+            nodes.len() - 1
+        });
+        if !app.module.is_empty() && !nodes[position].modules.contains(&app.module) {
+            nodes[position].modules.push(app.module.clone());
+        }
+    }
+
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (position, node) in nodes.iter_mut().enumerate() {
+        let key = node_key(&node.app_id, &node.version);
+        for reference in references.get(&key).into_iter().flatten() {
+            match resolve_reference(installed, reference) {
+                Some(target) => {
+                    let target_key = node_key(&target.app_id, &target.version);
+                    if target_key != key && !node.dependencies.contains(&target_key) {
+                        node.dependencies.push(target_key.clone());
+                        edges.push((position, index[&target_key]));
+                    }
+                }
+                None => {
+                    if !node.missing.contains(reference) {
+                        node.missing.push(reference.clone());
+                    }
+                }
+            }
+        }
+    }
+    for (from, to) in edges {
+        let from_key = node_key(&nodes[from].app_id, &nodes[from].version);
+        nodes[to].dependents.push(from_key);
+    }
+    DependencyGraph {
+        nodes,
+        missing_info: Vec::new(),
+    }
+}
+
+impl DependencyGraph {
+    fn node(&self, key: &str) -> Option<&DependencyNode> {
+        self.nodes.iter().find(|node| node_key(&node.app_id, &node.version) == key)
+    }
+
+    /// 指定应用的节点，未指定版本时为所有已安装版本
+    fn select(&self, app_id: &str, version: Option<&str>) -> Vec<String> {
+        self.nodes
+            .iter()
+            .filter(|node| node.app_id == app_id && version.is_none_or(|v| node.version == v))
+            .map(|node| node_key(&node.app_id, &node.version))
+            .collect()
+    }
+
+    /// 从起点出发沿依赖或被依赖方向遍历，返回经过的节点（不含起点）
+    fn walk(&self, start: &[String], next: fn(&DependencyNode) -> &Vec<String>) -> Vec<DependencyNode> {
+        let mut visited: HashSet<String> = start.iter().cloned().collect();
+        let mut queue: VecDeque<String> = start.iter().cloned().collect();
+        let mut result = Vec::new();
+        while let Some(key) = queue.pop_front() {
+            let Some(node) = self.node(&key) else {
+                continue;
+            };
+            for neighbor in next(node) {
+                if visited.insert(neighbor.clone()) {
+                    if let Some(found) = self.node(neighbor) {
+                        result.push(found.clone());
+                    }
+                    queue.push_back(neighbor.clone());
+                }
+            }
+        }
+        result
+    }
+
+    /// 依赖指定应用的所有节点（包括间接依赖）
+    pub fn dependents_of(&self, app_id: &str, version: Option<&str>) -> Vec<DependencyNode> {
+        self.walk(&self.select(app_id, version), |node| &node.dependents)
+    }
+
+    /// 指定应用需要的所有节点（包括间接依赖）
+    pub fn dependencies_of(&self, app_id: &str, version: Option<&str>) -> Vec<DependencyNode> {
+        self.walk(&self.select(app_id, version), |node| &node.dependencies)
+    }
+}
+
+/// 卸载指定版本后会失去依赖的节点
+///
+/// 引用在卸载后仍能解析到其他已安装版本时不算破坏。
+fn breaking_dependents(
+    installed: &[InstalledApp],
+    references: &HashMap<String, Vec<String>>,
+    graph: &DependencyGraph,
+    app_id: &str,
+    version: &str,
+) -> Vec<DependencyNode> {
+    let target = node_key(app_id, version);
+    let remaining: Vec<InstalledApp> = installed
+        .iter()
+        .filter(|app| node_key(&app.app_id, &app.version) != target)
+        .cloned()
+        .collect();
+    let Some(node) = graph.node(&target) else {
+        return Vec::new();
+    };
+
+    let broken: Vec<String> = node
+        .dependents
+        .iter()
+        .filter(|holder| {
+            references.get(*holder).into_iter().flatten().any(|reference| {
+                resolve_reference(installed, reference)
+                    .is_some_and(|app| node_key(&app.app_id, &app.version) == target)
+                    && resolve_reference(&remaining, reference).is_none()
+            })
+        })
+        .cloned()
+        .collect();
+
+    let mut result: Vec<DependencyNode> = broken.iter().filter_map(|key| graph.node(key).cloned()).collect();
+    for indirect in graph.walk(&broken, |node| &node.dependents) {
+        if node_key(&indirect.app_id, &indirect.version) != target && !result.contains(&indirect) {
+            result.push(indirect);
+        }
+    }
+    result
+}
+
+/// 构建已安装应用的依赖图
+pub async fn get_dependency_graph() -> Result<DependencyGraph, String> {
+    let installed = get_installed_apps(true).await?;
+    let (references, missing_info) = collect_references(&installed).await;
+    Ok(DependencyGraph {
+        missing_info,
+        ..build_graph(&installed, &references, &load_reasons())
+    })
+}
+
+/// 依赖指定应用的应用、运行时（包括间接依赖）
+pub async fn get_app_dependents(app_id: String, version: Option<String>) -> Result<Vec<DependencyNode>, String> {
+    let graph = get_dependency_graph().await?;
+    Ok(graph.dependents_of(&app_id, version.as_deref()))
+}
+
+/// 指定应用需要的运行时与基础环境（包括间接依赖）
+pub async fn get_app_dependencies(app_id: String, version: Option<String>) -> Result<Vec<DependencyNode>, String> {
+    let graph = get_dependency_graph().await?;
+    Ok(graph.dependencies_of(&app_id, version.as_deref()))
+}
+
+/// 根据依赖图得出卸载检查结果
+///
+/// 卸载的是运行时或基础环境时，依赖信息未知的应用可能正在使用它，视为不安全。
+fn uninstall_check(
+    installed: &[InstalledApp],
+    references: &HashMap<String, Vec<String>>,
+    missing_info: &[String],
+    graph: &DependencyGraph,
+    app_id: String,
+    version: String,
+) -> UninstallCheck {
+    let dependents = breaking_dependents(installed, references, graph, &app_id, &version);
+    let target = node_key(&app_id, &version);
+    let unknown: Vec<String> = if installed
+        .iter()
+        .any(|app| is_base_service(app) && node_key(&app.app_id, &app.version) == target)
+    {
+        missing_info
+            .iter()
+            .filter(|key| **key != target && !dependents.iter().any(|n| node_key(&n.app_id, &n.version) == **key))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut messages = Vec::new();
+    if !dependents.is_empty() {
+        let names: Vec<String> = dependents
+            .iter()
+            .map(|node| node_key(&node.app_id, &node.version))
+            .collect();
+        messages.push(format!("{} 仍被以下应用依赖，卸载后它们将无法运行：{}", target, names.join("、")));
+    }
+    if !unknown.is_empty() {
+        messages.push(format!("无法获取以下应用的依赖信息，它们可能依赖 {}：{}", target, unknown.join("、")));
+    }
+    UninstallCheck {
+        safe: dependents.is_empty() && unknown.is_empty(),
+        app_id,
+        version,
+        dependents,
+        missing_info: unknown,
+        message: messages.join("；"),
+    }
+}
+
+/// 检查卸载指定版本是否会导致其他应用无法运行
+pub async fn check_uninstall(app_id: String, version: String) -> Result<UninstallCheck, String> {
+    let installed = get_installed_apps(true).await?;
+    let (references, missing_info) = collect_references(&installed).await;
+    let graph = build_graph(&installed, &references, &load_reasons());
+    Ok(uninstall_check(&installed, &references, &missing_info, &graph, app_id, version))
+}
+
+/// 卸载前的保护：会破坏其他应用时返回错误
+pub async fn ensure_safe_uninstall(app_id: &str, version: &str) -> Result<(), String> {
+    let check = check_uninstall(app_id.to_string(), version.to_string()).await?;
+    if check.safe {
+        return Ok(());
+    }
+    info!("[Dependencies] Refusing to uninstall {}/{}: {}", app_id, version, check.message);
+    Err(check.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(app_id: &str, version: &str, kind: &str, module: &str) -> InstalledApp {
+        InstalledApp::test(app_id, version).with_kind(kind).with_module(module)
+    }
+
+    fn fixture() -> (Vec<InstalledApp>, HashMap<String, Vec<String>>) {
+        let list = vec![
+            installed("org.example.app", "1.0.0.0", "app", "binary"),
+            installed("org.example.app", "1.0.0.0", "app", "develop"),
+            installed("org.deepin.runtime.dtk", "23.1.0.0", "runtime", "binary"),
+            installed("org.deepin.base", "23.1.0.1", "base", "binary"),
+            installed("org.deepin.base", "23.1.0.2", "base", "binary"),
+        ];
+        let mut references = HashMap::new();
+        references.insert(
+            "org.example.app/1.0.0.0".to_string(),
+            vec![
+                "main:org.deepin.runtime.dtk/23.1.0/x86_64".to_string(),
+                "main:org.deepin.base/23.1.0/x86_64".to_string(),
+                "main:org.example.missing/1.0/x86_64".to_string(),
+            ],
+        );
+        references.insert(
+            "org.deepin.runtime.dtk/23.1.0.0".to_string(),
+            vec!["main:org.deepin.base/23.1.0/x86_64".to_string()],
+        );
+        (list, references)
+    }
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches("23.1.0", "23.1.0.2"));
+        assert!(version_matches("23.1.0.2", "23.1.0.2"));
+        assert!(!version_matches("23.1.1", "23.1.0.2"));
+        assert!(!version_matches("23.1.0.2.1", "23.1.0.2"));
+    }
+
+    #[test]
+    fn test_build_graph() {
+        let (list, references) = fixture();
+        let mut reasons = BTreeMap::new();
+        reasons.insert("org.deepin.runtime.dtk".to_string(), REASON_EXPLICIT.to_string());
+        let graph = build_graph(&list, &references, &reasons);
+
+        assert_eq!(graph.nodes.len(), 4);
+        let app = graph.node("org.example.app/1.0.0.0").unwrap();
+        assert_eq!(app.modules, vec!["binary", "develop"]);
+        assert_eq!(app.reason, REASON_EXPLICIT);
+        assert_eq!(
+            app.dependencies,
+            vec!["org.deepin.runtime.dtk/23.1.0.0", "org.deepin.base/23.1.0.2"]
+        );
+        assert_eq!(app.missing, vec!["main:org.example.missing/1.0/x86_64"]);
+        assert_eq!(graph.node("org.deepin.runtime.dtk/23.1.0.0").unwrap().reason, REASON_EXPLICIT);
+        assert_eq!(graph.node("org.deepin.base/23.1.0.1").unwrap().reason, REASON_DEPENDENCY);
+        assert!(graph.node("org.deepin.base/23.1.0.1").unwrap().dependents.is_empty());
+        assert_eq!(
+            graph.node("org.deepin.base/23.1.0.2").unwrap().dependents,
+            vec!["org.example.app/1.0.0.0", "org.deepin.runtime.dtk/23.1.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_transitive_queries() {
+        let (list, references) = fixture();
+        let graph = build_graph(&list, &references, &BTreeMap::new());
+        let keys = |nodes: Vec<DependencyNode>| -> Vec<String> {
+            nodes.iter().map(|n| node_key(&n.app_id, &n.version)).collect()
+        };
+        assert_eq!(
+            keys(graph.dependencies_of("org.example.app", None)),
+            vec!["org.deepin.runtime.dtk/23.1.0.0", "org.deepin.base/23.1.0.2"]
+        );
+        assert_eq!(
+            keys(graph.dependents_of("org.deepin.runtime.dtk", Some("23.1.0.0"))),
+            vec!["org.example.app/1.0.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_breaking_dependents() {
+        let (list, references) = fixture();
+        let graph = build_graph(&list, &references, &BTreeMap::new());
+
+        // 23.1.0.2 卸载后引用回落到 23.1.0.1，不会破坏
+        assert!(breaking_dependents(&list, &references, &graph, "org.deepin.base", "23.1.0.2").is_empty());
+
+        // 运行时没有其他版本，应用会受影响
+        let broken = breaking_dependents(&list, &references, &graph, "org.deepin.runtime.dtk", "23.1.0.0");
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].app_id, "org.example.app");
+
+        // 只剩一个 base 版本时，运行时与间接依赖它的应用都会受影响
+        let single: Vec<InstalledApp> = list.into_iter().filter(|app| app.version != "23.1.0.1").collect();
+        let graph = build_graph(&single, &references, &BTreeMap::new());
+        let broken = breaking_dependents(&single, &references, &graph, "org.deepin.base", "23.1.0.2");
+        let ids: Vec<&str> = broken.iter().map(|n| n.app_id.as_str()).collect();
+        assert_eq!(ids, vec!["org.example.app", "org.deepin.runtime.dtk"]);
+    }
+
+    #[test]
+    fn test_uninstall_check_with_missing_info() {
+        let (list, references) = fixture();
+        let graph = build_graph(&list, &references, &BTreeMap::new());
+        let missing = vec!["org.example.app/1.0.0.0".to_string()];
+
+        // 应用的 base 未知，卸载 base 不能判定为安全
+        let check = uninstall_check(
+            &list,
+            &references,
+            &missing,
+            &graph,
+            "org.deepin.base".to_string(),
+            "23.1.0.2".to_string(),
+        );
+        assert!(!check.safe);
+        assert!(check.dependents.is_empty());
+        assert_eq!(check.missing_info, missing);
+        assert!(check.message.contains("org.example.app/1.0.0.0"));
+
+        // 卸载普通应用不受依赖信息缺失影响
+        let check = uninstall_check(
+            &list,
+            &references,
+            &missing,
+            &graph,
+            "org.example.app".to_string(),
+            "1.0.0.0".to_string(),
+        );
+        assert!(check.safe);
+        assert!(check.missing_info.is_empty());
+        assert!(check.message.is_empty());
+    }
+}
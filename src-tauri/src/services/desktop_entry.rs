@@ -0,0 +1,557 @@
+//! 桌面入口集成
+//!
+//! 通过 `ll-cli content` 找到应用导出的 `.desktop` 文件，检查其 Exec 是否指向
+//! `ll-cli run <appId>`、图标能否解析、菜单中是否存在；并支持在用户桌面创建/删除
+//! 快捷方式，以及把缺失的菜单项补到用户级应用目录（`~/.local/share/applications`）。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::services::app_info::AppRef;
+use crate::services::install::get_installed_apps;
+use crate::services::ll_cli_command;
+use crate::utils::paths::{desktop_dir, home_dir, user_applications_dir};
+
+/// 玲珑导出文件的系统目录
+const LINGLONG_ENTRIES_DIR: &str = "/var/lib/linglong/entries/share";
+/// 同时执行 `ll-cli content` 的最大数量
+const MAX_CONCURRENT_QUERIES: usize = 4;
+const ICON_EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+
+/// 应用导出的一个桌面入口
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopEntryInfo {
+    pub app_id: String,
+    pub file_name: String,
+    /// 应用层中的原始文件
+    pub source_path: String,
+    pub name: String,
+    pub exec: String,
+    pub icon: String,
+    /// 应用菜单中的文件，未导出时为 None
+    pub menu_path: Option<String>,
+    pub has_shortcut: bool,
+    /// 校验发现的问题，为空表示正常
+    pub problems: Vec<String>,
+}
+
+/// 菜单项修复结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopRepairReport {
+    pub app_id: String,
+    /// 新写入的菜单文件
+    pub repaired: Vec<String>,
+    /// 已在菜单中、无需修复的文件名
+    pub unchanged: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// 解析 `[Desktop Entry]` 分组中的键值，忽略本地化键
+pub(crate) fn parse_desktop_entry(content: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut in_main_group = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_main_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            if !key.contains('[') {
+                fields.insert(key.to_string(), value.trim().to_string());
+            }
+        }
+    }
+    fields
+}
+
+/// 从 Exec 中取出 `ll-cli run` 启动的 appId
+pub(crate) fn exec_app_id(exec: &str) -> Option<String> {
+    let tokens = split_exec(exec);
+    let index = tokens
+        .iter()
+        .position(|t| Path::new(t).file_name().is_some_and(|n| n == "ll-cli"))?;
+    if tokens.get(index + 1).map(String::as_str) != Some("run") {
+        return None;
+    }
+    tokens[index + 2..]
+        .iter()
+        .find(|t| !t.starts_with('-'))
+        .map(|t| AppRef::parse(t).id)
+}
+
+/// Exec 是否为 `ll-cli run <appId> ...`
+fn exec_runs_app(exec: &str, app_id: &str) -> bool {
+    exec_app_id(exec).is_some_and(|id| id == app_id)
+}
+
+/// 还原键值中的字符串转义（`\\`、`\s`、`\n`、`\t`、`\r`）
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 按桌面入口规范拆分 Exec 键的值，处理双引号与转义
+pub(crate) fn split_exec(exec: &str) -> Vec<String> {
+    let exec = unescape_value(exec);
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            '\\' if quoted => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// 按桌面入口规范引用单个 Exec 参数，结果可直接写入键值
+pub(crate) fn quote_exec_arg(arg: &str) -> String {
+    const RESERVED: &str = " \t\n\"'\\><~|&;$*?#()`";
+    let arg = arg.replace('%', "%%");
+    if !arg.is_empty() && !arg.chars().any(|c| RESERVED.contains(c)) {
+        return arg;
+    }
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            // 引号内的转义，再经字符串转义后反斜杠需写两次
+            '"' | '`' | '$' => {
+                quoted.push_str("\\\\");
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str("\\\\\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// 图标查找目录：玲珑导出目录、用户目录与 XDG_DATA_DIRS
+fn icon_bases() -> Vec<PathBuf> {
+    let mut bases = vec![PathBuf::from(LINGLONG_ENTRIES_DIR)];
+    if let Some(home) = home_dir() {
+        bases.push(home.join(".local/share"));
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS").unwrap_or_default();
+    bases.extend(data_dirs.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    bases.push(PathBuf::from("/usr/share"));
+    bases
+}
+
+/// 图标是否能被解析：绝对路径需存在，图标名需在 hicolor 主题或 pixmaps 中找到
+fn resolve_icon(icon: &str, bases: &[PathBuf]) -> bool {
+    if icon.starts_with('/') {
+        return Path::new(icon).exists();
+    }
+    let has_icon = |dir: &Path| {
+        ICON_EXTENSIONS
+            .iter()
+            .any(|ext| dir.join(format!("{}.{}", icon, ext)).exists())
+    };
+    bases.iter().any(|base| {
+        if has_icon(&base.join("pixmaps")) {
+            return true;
+        }
+        let Ok(sizes) = fs::read_dir(base.join("icons/hicolor")) else {
+            return false;
+        };
+        sizes.flatten().any(|size| has_icon(&size.path().join("apps")))
+    })
+}
+
+/// 校验桌面入口，返回发现的问题
+fn validate(fields: &HashMap<String, String>, app_id: &str, icon_ok: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut problems = Vec::new();
+    let field = |key: &str| fields.get(key).map(String::as_str).unwrap_or("");
+    if field("Type") != "Application" {
+        problems.push("Type 不是 Application".to_string());
+    }
+    if field("Name").is_empty() {
+        problems.push("缺少 Name".to_string());
+    }
+    if !exec_runs_app(field("Exec"), app_id) {
+        problems.push(format!("Exec 未指向 ll-cli run {}", app_id));
+    }
+    let icon = field("Icon");
+    if icon.is_empty() {
+        problems.push("缺少 Icon".to_string());
+    } else if !icon_ok(icon) {
+        problems.push(format!("找不到图标 {}", icon));
+    }
+    problems
+}
+
+/// 通过 `ll-cli content` 获取应用导出的 .desktop 文件
+fn app_desktop_files(app_id: &str) -> Result<Vec<PathBuf>, String> {
+    let output = ll_cli_command()
+        .arg("content")
+        .arg(app_id)
+        .output()
+        .map_err(|e| format!("Failed to execute 'll-cli content {}': {}", app_id, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ll-cli content {} failed: {}",
+            app_id,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('/') && line.ends_with(".desktop") && line.contains("/applications/"))
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// 应用菜单中对应的文件：玲珑导出目录或用户级应用目录
+fn menu_file(file_name: &str) -> Option<PathBuf> {
+    let system = Path::new(LINGLONG_ENTRIES_DIR).join("applications").join(file_name);
+    if system.exists() {
+        return Some(system);
+    }
+    user_applications_dir()
+        .map(|dir| dir.join(file_name))
+        .filter(|path| path.exists())
+}
+
+fn inspect(app_id: &str, source: &Path, bases: &[PathBuf]) -> DesktopEntryInfo {
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut info = DesktopEntryInfo {
+        app_id: app_id.to_string(),
+        source_path: source.to_string_lossy().to_string(),
+        menu_path: menu_file(&file_name).map(|p| p.to_string_lossy().to_string()),
+        has_shortcut: desktop_dir().is_some_and(|dir| dir.join(&file_name).exists()),
+        file_name,
+        ..Default::default()
+    };
+
+    // 优先检查菜单中实际生效的文件
+    let path = info.menu_path.clone().unwrap_or_else(|| info.source_path.clone());
+    match fs::read_to_string(&path) {
+        Ok(content) => {
+            let fields = parse_desktop_entry(&content);
+            info.problems = validate(&fields, app_id, |icon| resolve_icon(icon, bases));
+            let field = |key: &str| fields.get(key).cloned().unwrap_or_default();
+            info.name = field("Name");
+            info.exec = field("Exec");
+            info.icon = field("Icon");
+        }
+        Err(e) => info.problems.push(format!("无法读取 {}: {}", path, e)),
+    }
+    if info.menu_path.is_none() {
+        info.problems.push("应用菜单中缺少此入口".to_string());
+    }
+    info
+}
+
+fn inspect_app(app_id: &str) -> Result<Vec<DesktopEntryInfo>, String> {
+    let bases = icon_bases();
+    Ok(app_desktop_files(app_id)?
+        .iter()
+        .map(|source| inspect(app_id, source, &bases))
+        .collect())
+}
+
+async fn inspect_app_async(app_id: String) -> Result<Vec<DesktopEntryInfo>, String> {
+    tokio::task::spawn_blocking(move || inspect_app(&app_id))
+        .await
+        .map_err(|e| format!("桌面入口检查任务执行失败: {}", e))?
+}
+
+/// 应用的主桌面入口：优先 `<appId>.desktop`，优先使用菜单中的文件
+pub(crate) fn primary_desktop_file(app_id: &str) -> Result<PathBuf, String> {
+    let files = app_desktop_files(app_id)?;
+    let expected = format!("{}.desktop", app_id);
+    let source = files
+        .iter()
+        .find(|p| p.file_name().is_some_and(|n| n.to_string_lossy() == expected))
+        .or_else(|| files.first())
+        .ok_or_else(|| format!("{} 没有导出桌面入口", app_id))?;
+    let file_name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    Ok(menu_file(&file_name).unwrap_or_else(|| source.clone()))
+}
+
+/// 获取应用的桌面入口及校验结果
+///
+/// # Arguments
+/// * `app_ids` - 要检查的应用，为空时检查所有已安装应用
+pub async fn list_desktop_entries(app_ids: Option<Vec<String>>) -> Result<Vec<DesktopEntryInfo>, String> {
+    let mut app_ids = match app_ids {
+        Some(ids) => ids,
+        None => get_installed_apps(false)
+            .await?
+            .into_iter()
+            .map(|app| app.app_id)
+            .collect(),
+    };
+    app_ids.sort();
+    app_ids.dedup();
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_QUERIES));
+    let mut tasks = JoinSet::new();
+    for app_id in app_ids {
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (app_id.clone(), inspect_app_async(app_id).await)
+        });
+    }
+
+    let mut entries = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((_, Ok(list))) => entries.extend(list),
+            Ok((app_id, Err(e))) => warn!("[DesktopEntry] Failed to inspect {}: {}", app_id, e),
+            Err(e) => warn!("[DesktopEntry] Inspect task failed: {}", e),
+        }
+    }
+    entries.sort_by(|a, b| (&a.app_id, &a.file_name).cmp(&(&b.app_id, &b.file_name)));
+    Ok(entries)
+}
+
+/// 复制桌面文件并设置权限，返回目标路径
+fn copy_entry(source: &Path, dir: &Path, mode: u32) -> Result<PathBuf, String> {
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| format!("无效的桌面文件路径: {:?}", source))?;
+    fs::create_dir_all(dir).map_err(|e| format!("创建目录 {:?} 失败: {}", dir, e))?;
+    let target = dir.join(file_name);
+    fs::copy(source, &target).map_err(|e| format!("复制 {:?} 到 {:?} 失败: {}", source, target, e))?;
+    fs::set_permissions(&target, fs::Permissions::from_mode(mode))
+        .map_err(|e| format!("设置 {:?} 权限失败: {}", target, e))?;
+    Ok(target)
+}
+
+/// 删除目录中属于该应用的桌面文件（Exec 需指向该应用），返回已删除的路径
+fn remove_entries(app_id: &str, file_names: &[String], dir: &Path) -> Vec<PathBuf> {
+    file_names
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| {
+            fs::read_to_string(path)
+                .map(|content| {
+                    let fields = parse_desktop_entry(&content);
+                    exec_runs_app(fields.get("Exec").map(String::as_str).unwrap_or(""), app_id)
+                })
+                .unwrap_or(false)
+        })
+        .filter(|path| fs::remove_file(path).is_ok())
+        .collect()
+}
+
+/// 在用户桌面创建应用快捷方式
+pub async fn create_desktop_shortcut(app_id: String) -> Result<Vec<String>, String> {
+    let dir = desktop_dir().ok_or_else(|| "无法确定桌面目录".to_string())?;
+    let entries = inspect_app_async(app_id.clone()).await?;
+    if entries.is_empty() {
+        return Err(format!("{} 没有导出桌面入口", app_id));
+    }
+
+    let mut created = Vec::new();
+    for entry in entries {
+        let source = entry.menu_path.unwrap_or(entry.source_path);
+        let target = copy_entry(Path::new(&source), &dir, 0o755)?;
+        // GNOME 需标记为可信才能直接启动，其他桌面环境忽略失败
+        let _ = Command::new("gio")
+            .arg("set")
+            .arg(&target)
+            .arg("metadata::trusted")
+            .arg("true")
+            .output();
+        info!("[DesktopEntry] Shortcut created: {:?}", target);
+        created.push(target.to_string_lossy().to_string());
+    }
+    Ok(created)
+}
+
+/// 删除用户桌面上的应用快捷方式
+pub async fn remove_desktop_shortcut(app_id: String) -> Result<Vec<String>, String> {
+    let dir = desktop_dir().ok_or_else(|| "无法确定桌面目录".to_string())?;
+    let file_names: Vec<String> = inspect_app_async(app_id.clone())
+        .await?
+        .into_iter()
+        .map(|entry| entry.file_name)
+        .collect();
+    let removed = remove_entries(&app_id, &file_names, &dir);
+    info!("[DesktopEntry] Shortcuts removed for {}: {:?}", app_id, removed);
+    Ok(removed.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+fn repair_app(app_id: &str) -> Result<DesktopRepairReport, String> {
+    let dir = user_applications_dir().ok_or_else(|| "无法确定用户应用目录".to_string())?;
+    let mut report = DesktopRepairReport {
+        app_id: app_id.to_string(),
+        ..Default::default()
+    };
+    for entry in inspect_app(app_id)? {
+        if entry.menu_path.is_some() {
+            report.unchanged.push(entry.file_name);
+            continue;
+        }
+        match copy_entry(Path::new(&entry.source_path), &dir, 0o644) {
+            Ok(target) => report.repaired.push(target.to_string_lossy().to_string()),
+            Err(e) => report.failed.push(e),
+        }
+    }
+    if !report.repaired.is_empty() {
+        // 刷新菜单缓存，命令不存在时忽略
+        let _ = Command::new("update-desktop-database").arg(&dir).output();
+        info!("[DesktopEntry] Repaired menu entries for {}: {:?}", app_id, report.repaired);
+    }
+    Ok(report)
+}
+
+/// 为应用补全缺失的菜单项
+pub async fn repair_desktop_entries(app_id: String) -> Result<DesktopRepairReport, String> {
+    tokio::task::spawn_blocking(move || repair_app(&app_id))
+        .await
+        .map_err(|e| format!("菜单修复任务执行失败: {}", e))?
+}
+
+/// 安装完成后检查并补全菜单项，失败只记录日志
+pub(crate) fn repair_after_install(app_id: &str) {
+    let app_id = app_id.to_string();
+    std::thread::spawn(move || match repair_app(&app_id) {
+        Ok(report) if !report.failed.is_empty() => {
+            warn!("[DesktopEntry] Repair after install of {} failed: {:?}", app_id, report.failed)
+        }
+        Ok(_) => {}
+        Err(e) => warn!("[DesktopEntry] Repair after install of {} failed: {}", app_id, e),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRY: &str = "[Desktop Entry]\n\
+        Type=Application\n\
+        Name=Calculator\n\
+        Name[zh_CN]=计算器\n\
+        Exec=/usr/bin/ll-cli run org.deepin.calculator -- deepin-calculator %U\n\
+        Icon=deepin-calculator\n\
+        \n\
+        [Desktop Action new]\n\
+        Exec=other\n";
+
+    #[test]
+    fn test_parse_desktop_entry() {
+        let fields = parse_desktop_entry(ENTRY);
+        assert_eq!(fields.get("Name").map(String::as_str), Some("Calculator"));
+        assert!(fields.get("Exec").unwrap().starts_with("/usr/bin/ll-cli"));
+        assert!(!fields.contains_key("Name[zh_CN]"));
+    }
+
+    #[test]
+    fn test_exec_runs_app() {
+        assert!(exec_runs_app("ll-cli run org.a -- a %F", "org.a"));
+        assert!(exec_runs_app("/usr/bin/ll-cli run main:org.a/1.0.0.0/x86_64", "org.a"));
+        assert!(!exec_runs_app("ll-cli run org.b", "org.a"));
+        assert!(!exec_runs_app("/opt/apps/org.a/files/bin/a", "org.a"));
+    }
+
+    #[test]
+    fn test_split_and_quote_exec() {
+        assert_eq!(
+            split_exec(r#"ll-cli run org.a -- "/opt/my app/bin" --name="a \\"b\\"" %U"#),
+            vec!["ll-cli", "run", "org.a", "--", "/opt/my app/bin", "--name=a \"b\"", "%U"]
+        );
+        for arg in ["plain", "with space", "quote\"s", "back\\slash", "100%", "$HOME", ""] {
+            let quoted = quote_exec_arg(arg);
+            let round_trip = split_exec(&format!("cmd {}", quoted));
+            let expected = arg.replace('%', "%%");
+            assert_eq!(round_trip, vec!["cmd".to_string(), expected], "{}", quoted);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let fields = parse_desktop_entry(ENTRY);
+        assert!(validate(&fields, "org.deepin.calculator", |_| true).is_empty());
+        let problems = validate(&fields, "org.other", |_| false);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("org.other"));
+        assert!(problems[1].contains("deepin-calculator"));
+    }
+
+    #[test]
+    fn test_copy_and_remove_entries() {
+        let root = std::env::temp_dir().join(format!("ll-desktop-test-{}", std::process::id()));
+        let source_dir = root.join("source");
+        let desktop = root.join("desktop");
+        fs::create_dir_all(&source_dir).unwrap();
+        let source = source_dir.join("org.deepin.calculator.desktop");
+        fs::write(&source, ENTRY).unwrap();
+        fs::create_dir_all(&desktop).unwrap();
+        fs::write(desktop.join("other.desktop"), "[Desktop Entry]\nExec=other\n").unwrap();
+
+        let target = copy_entry(&source, &desktop, 0o755).unwrap();
+        assert_eq!(fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o755);
+
+        let names = vec!["org.deepin.calculator.desktop".to_string(), "other.desktop".to_string()];
+        let removed = remove_entries("org.deepin.calculator", &names, &desktop);
+        assert_eq!(removed, vec![target.clone()]);
+        assert!(!target.exists());
+        // Exec 不指向该应用的文件不会被删除
+        assert!(desktop.join("other.desktop").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
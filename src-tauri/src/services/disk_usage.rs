@@ -0,0 +1,275 @@
+//! 磁盘占用分析
+//!
+//! 按应用、运行时、基础环境及模块统计玲珑层目录的实际占用。层目录之间通过硬链接共享文件，
+//! 同一 inode 只计一次：仅被一个层引用的部分计入该层的独占空间，被多个层引用的部分计为共享空间。
+//! 同时统计用户主目录下各应用的数据目录，方便用户决定清理哪些内容。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::services::install::{get_installed_apps, InstalledApp};
+use crate::utils::paths::{config_dir, home_dir};
+
+/// 玲珑层目录
+const LINGLONG_LAYERS_DIR: &str = "/var/lib/linglong/layers";
+
+/// 单个层（应用、运行时或基础环境的某个模块）的占用
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerUsage {
+    pub app_id: String,
+    pub name: String,
+    pub version: String,
+    pub arch: String,
+    pub channel: String,
+    /// app、runtime 或 base
+    pub kind: String,
+    pub module: String,
+    /// ll-cli 报告的大小
+    pub size_bytes: u64,
+    /// 层目录，未找到时为 None
+    pub path: Option<String>,
+    /// 层目录中文件的总大小（同一 inode 只计一次），未找到层目录时为 None
+    pub disk_bytes: Option<u64>,
+    /// 仅由该层引用的文件大小
+    pub exclusive_bytes: Option<u64>,
+}
+
+/// 应用在用户主目录下的数据目录
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDataUsage {
+    pub app_id: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// 磁盘占用报告
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageReport {
+    pub layers: Vec<LayerUsage>,
+    /// 按类型（app/runtime/base）汇总的独占空间
+    pub by_kind: BTreeMap<String, u64>,
+    /// 按模块（binary/develop 等）汇总的独占空间
+    pub by_module: BTreeMap<String, u64>,
+    /// 被多个层共享的空间
+    pub shared_bytes: u64,
+    /// 所有层的总占用，等于各类型独占空间之和加共享空间
+    pub total_bytes: u64,
+    pub user_data: Vec<UserDataUsage>,
+    pub user_data_bytes: u64,
+}
+
+/// 文件标识 (dev, inode) 到文件大小的映射
+type InodeSizes = HashMap<(u64, u64), u64>;
+
+/// 递归统计目录中的普通文件，不跟随符号链接
+fn scan_tree(root: &Path) -> InodeSizes {
+    let mut files = InodeSizes::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("[DiskUsage] Failed to read {:?}: {}", dir, e);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                stack.push(entry.path());
+            } else if meta.is_file() {
+                files.insert((meta.dev(), meta.ino()), meta.len());
+            }
+        }
+    }
+    files
+}
+
+/// 目录中文件的总大小，同一 inode 只计一次
+fn tree_size(root: &Path) -> u64 {
+    scan_tree(root).values().sum()
+}
+
+/// 统计各层的总占用、独占空间，以及所有层共享的空间与总空间
+///
+/// 返回 (每层的 (总占用, 独占空间), 共享空间, 总空间)
+fn summarize(layers: &[InodeSizes]) -> (Vec<(u64, u64)>, u64, u64) {
+    let mut refs: HashMap<(u64, u64), (u64, usize)> = HashMap::new();
+    for files in layers {
+        for (inode, size) in files {
+            refs.entry(*inode).or_insert((*size, 0)).1 += 1;
+        }
+    }
+    let per_layer = layers
+        .iter()
+        .map(|files| {
+            let disk = files.values().sum();
+            let exclusive = files
+                .keys()
+                .filter(|inode| refs.get(*inode).is_some_and(|(_, count)| *count == 1))
+                .map(|inode| files[inode])
+                .sum();
+            (disk, exclusive)
+        })
+        .collect();
+    let shared = refs.values().filter(|(_, count)| *count > 1).map(|(size, _)| size).sum();
+    let total = refs.values().map(|(size, _)| size).sum();
+    (per_layer, shared, total)
+}
+
+/// 查找层目录，兼容带频道和不带频道的目录布局
+fn layer_dir(root: &Path, app: &InstalledApp) -> Option<PathBuf> {
+    let module = if app.module.is_empty() { "binary" } else { &app.module };
+    [
+        root.join(&app.channel).join(&app.app_id),
+        root.join(&app.app_id),
+    ]
+    .into_iter()
+    .map(|base| base.join(&app.version).join(&app.arch).join(module))
+    .find(|path| path.is_dir())
+}
+
+/// 应用在用户主目录下可能的数据目录
+fn user_data_dirs(app_id: &str) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = home_dir() {
+        dirs.push(home.join(".linglong").join(app_id));
+        dirs.push(home.join(".cache").join(app_id));
+        dirs.push(home.join(".local/share").join(app_id));
+    }
+    if let Some(config) = config_dir() {
+        dirs.push(config.join(app_id));
+    }
+    dirs
+}
+
+fn analyze(installed: &[InstalledApp], layers_root: &Path) -> DiskUsageReport {
+    let dirs: Vec<Option<PathBuf>> = installed.iter().map(|app| layer_dir(layers_root, app)).collect();
+    let scans: Vec<InodeSizes> = dirs
+        .iter()
+        .map(|dir| dir.as_deref().map(scan_tree).unwrap_or_default())
+        .collect();
+    let (per_layer, shared_bytes, scanned_total) = summarize(&scans);
+
+    let mut report = DiskUsageReport {
+        shared_bytes,
+        total_bytes: scanned_total,
+        ..Default::default()
+    };
+    for ((app, dir), (disk, exclusive)) in installed.iter().zip(dirs).zip(per_layer) {
+        // 未找到层目录时退回 ll-cli 报告的大小
+        let attributed = if dir.is_some() { exclusive } else { app.size_bytes };
+        if dir.is_none() {
+            report.total_bytes += app.size_bytes;
+        }
+        let kind = app.kind.clone().unwrap_or_else(|| "app".to_string());
+        *report.by_kind.entry(kind.clone()).or_default() += attributed;
+        *report.by_module.entry(app.module.clone()).or_default() += attributed;
+        report.layers.push(LayerUsage {
+            app_id: app.app_id.clone(),
+            name: app.name.clone(),
+            version: app.version.clone(),
+            arch: app.arch.clone(),
+            channel: app.channel.clone(),
+            kind,
+            module: app.module.clone(),
+            size_bytes: app.size_bytes,
+            path: dir.as_ref().map(|p| p.to_string_lossy().to_string()),
+            disk_bytes: dir.as_ref().map(|_| disk),
+            exclusive_bytes: dir.as_ref().map(|_| exclusive),
+        });
+    }
+    report
+        .layers
+        .sort_by_key(|layer| std::cmp::Reverse(layer.exclusive_bytes.unwrap_or(layer.size_bytes)));
+
+    let mut app_ids: Vec<&str> = installed
+        .iter()
+        .filter(|app| app.kind.as_deref().unwrap_or("app") == "app")
+        .map(|app| app.app_id.as_str())
+        .collect();
+    app_ids.sort();
+    app_ids.dedup();
+    for app_id in app_ids {
+        for dir in user_data_dirs(app_id) {
+            // 不跟随指向其他位置的符号链接
+            if !dir.symlink_metadata().is_ok_and(|meta| meta.is_dir()) {
+                continue;
+            }
+            let size_bytes = tree_size(&dir);
+            report.user_data_bytes += size_bytes;
+            report.user_data.push(UserDataUsage {
+                app_id: app_id.to_string(),
+                path: dir.to_string_lossy().to_string(),
+                size_bytes,
+            });
+        }
+    }
+    report.user_data.sort_by_key(|data| std::cmp::Reverse(data.size_bytes));
+    report
+}
+
+/// 分析已安装应用、运行时与基础环境的磁盘占用
+pub async fn get_disk_usage() -> Result<DiskUsageReport, String> {
+    let installed = get_installed_apps(true).await?;
+    let report = tokio::task::spawn_blocking(move || analyze(&installed, Path::new(LINGLONG_LAYERS_DIR)))
+        .await
+        .map_err(|e| format!("磁盘占用分析失败: {}", e))?;
+    info!(
+        "[DiskUsage] {} layer(s), total {} bytes, shared {} bytes, user data {} bytes",
+        report.layers.len(),
+        report.total_bytes,
+        report.shared_bytes,
+        report.user_data_bytes
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_counts_shared_inodes_once() {
+        let a: InodeSizes = [((1, 1), 100), ((1, 2), 50)].into_iter().collect();
+        let b: InodeSizes = [((1, 2), 50), ((1, 3), 30)].into_iter().collect();
+        let (per_layer, shared, total) = summarize(&[a, b, InodeSizes::new()]);
+        assert_eq!(per_layer, vec![(150, 100), (80, 30), (0, 0)]);
+        assert_eq!(shared, 50);
+        assert_eq!(total, 180);
+    }
+
+    #[test]
+    fn test_scan_tree_and_layer_dir() {
+        let root = std::env::temp_dir().join(format!("ll-disk-usage-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let layer_a = root.join("main/org.a/1.0/x86_64/binary");
+        let layer_b = root.join("org.b/2.0/x86_64/binary");
+        std::fs::create_dir_all(layer_a.join("files")).unwrap();
+        std::fs::create_dir_all(&layer_b).unwrap();
+        std::fs::write(layer_a.join("files/data"), vec![0u8; 64]).unwrap();
+        std::fs::write(layer_a.join("own"), vec![0u8; 16]).unwrap();
+        std::fs::hard_link(layer_a.join("files/data"), layer_b.join("data")).unwrap();
+
+        let app = |id: &str, version: &str| InstalledApp::test(id, version).with_kind("runtime");
+        assert_eq!(layer_dir(&root, &app("org.a", "1.0")), Some(layer_a.clone()));
+        assert_eq!(layer_dir(&root, &app("org.b", "2.0")), Some(layer_b.clone()));
+        assert_eq!(layer_dir(&root, &app("org.c", "1.0")), None);
+
+        let report = analyze(&[app("org.a", "1.0"), app("org.b", "2.0")], &root);
+        assert_eq!(report.shared_bytes, 64);
+        assert_eq!(report.total_bytes, 80);
+        assert_eq!(report.by_kind.get("runtime"), Some(&16));
+        assert_eq!(report.layers[0].app_id, "org.a");
+        assert_eq!(report.layers[0].disk_bytes, Some(80));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
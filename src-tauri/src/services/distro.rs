@@ -0,0 +1,359 @@
+//! 发行版识别与玲珑软件包版本查询
+//!
+//! 根据 /etc/os-release 的 ID / ID_LIKE 判断发行版家族，
+//! 再只调用该发行版的包管理器查询 linglong-bin 等软件包的已安装版本与候选版本。
+//! 查询结果缓存一段时间，环境检查无需每次都启动包管理器。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::services::apply_english_locale_env_to_command;
+
+/// 软件包查询结果的缓存有效期
+const PACKAGE_CACHE_TTL: Duration = Duration::from_secs(600);
+
+static PACKAGE_CACHE: Lazy<Mutex<Option<(Instant, LinglongPackageReport)>>> = Lazy::new(|| Mutex::new(None));
+
+/// 发行版家族
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DistroFamily {
+    /// Debian / Ubuntu / deepin / UOS 等（apt）
+    Debian,
+    /// Fedora / RHEL / openEuler 等（dnf）
+    Fedora,
+    /// Arch / Manjaro 等（pacman）
+    Arch,
+    /// openSUSE / SLES（zypper）
+    Suse,
+    Unknown,
+}
+
+impl DistroFamily {
+    /// 对应的包管理器名称
+    pub fn package_manager(self) -> &'static str {
+        match self {
+            DistroFamily::Debian => "apt",
+            DistroFamily::Fedora => "dnf",
+            DistroFamily::Arch => "pacman",
+            DistroFamily::Suse => "zypper",
+            DistroFamily::Unknown => "unknown",
+        }
+    }
+
+    /// 需要查询的玲珑相关软件包，第一个为主程序包（Arch 系打包名为 linyaps）
+    pub fn linglong_packages(self) -> &'static [&'static str] {
+        match self {
+            DistroFamily::Arch => &["linyaps", "linyaps-box"],
+            DistroFamily::Debian | DistroFamily::Fedora | DistroFamily::Suse => {
+                &["linglong-bin", "linglong-box", "linglong-builder"]
+            }
+            DistroFamily::Unknown => &["linglong-bin"],
+        }
+    }
+}
+
+/// /etc/os-release 中与识别相关的字段
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OsRelease {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub pretty_name: Option<String>,
+}
+
+/// 单个软件包的版本信息
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVersionInfo {
+    pub name: String,
+    /// 已安装版本
+    pub installed: Option<String>,
+    /// 软件源中的候选版本
+    pub candidate: Option<String>,
+}
+
+/// 玲珑相关软件包的查询结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LinglongPackageReport {
+    pub distro_id: String,
+    pub family: DistroFamily,
+    pub package_manager: String,
+    /// 已安装或软件源中存在的软件包
+    pub packages: Vec<PackageVersionInfo>,
+}
+
+impl LinglongPackageReport {
+    /// linglong-bin（Arch 系为 linyaps）的已安装版本
+    pub fn ll_bin_version(&self) -> Option<String> {
+        ["linglong-bin", "linyaps"].iter().find_map(|name| {
+            self.packages
+                .iter()
+                .find(|p| p.name == *name)
+                .and_then(|p| p.installed.clone())
+        })
+    }
+
+    /// 用于日志展示的摘要文本
+    pub fn summary(&self) -> String {
+        self.packages
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} installed: {} candidate: {}",
+                    p.name,
+                    p.installed.as_deref().unwrap_or("-"),
+                    p.candidate.as_deref().unwrap_or("-")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+pub fn parse_os_release(content: &str) -> OsRelease {
+    let mut release = OsRelease::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').trim_matches('\'');
+        match key.trim() {
+            "ID" => release.id = value.to_lowercase(),
+            "ID_LIKE" => {
+                release.id_like = value.split_whitespace().map(|s| s.to_lowercase()).collect()
+            }
+            "PRETTY_NAME" => release.pretty_name = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    release
+}
+
+fn family_of(id: &str) -> DistroFamily {
+    match id {
+        "debian" | "ubuntu" | "deepin" | "uos" | "linuxmint" | "kylin" | "openkylin" | "pop"
+        | "elementary" | "zorin" => DistroFamily::Debian,
+        "fedora" | "rhel" | "centos" | "openeuler" | "anolis" | "rocky" | "almalinux" | "ol"
+        | "kylin-server" => DistroFamily::Fedora,
+        "arch" | "manjaro" | "endeavouros" | "garuda" | "cachyos" => DistroFamily::Arch,
+        "suse" | "sles" | "opensuse" | "opensuse-leap" | "opensuse-tumbleweed" => DistroFamily::Suse,
+        _ => DistroFamily::Unknown,
+    }
+}
+
+/// 先按 ID 判断，再依次按 ID_LIKE 判断
+pub fn detect_family(release: &OsRelease) -> DistroFamily {
+    std::iter::once(&release.id)
+        .chain(release.id_like.iter())
+        .map(|id| family_of(id))
+        .find(|family| *family != DistroFamily::Unknown)
+        .unwrap_or(DistroFamily::Unknown)
+}
+
+/// 以英文环境执行命令，成功时返回 stdout
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    apply_english_locale_env_to_command(&mut cmd);
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value == "(none)" {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// 解析 `Key : Value` 形式输出中指定字段（apt-cache policy / pacman -Si / zypper info）
+fn parse_field(output: &str, key: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        if k.trim() == key {
+            non_empty(v)
+        } else {
+            None
+        }
+    })
+}
+
+/// 解析 dpkg-query -W -f='${db:Status-Abbrev}|${Version}' 的输出，仅 ii 状态视为已安装
+fn parse_dpkg_query(output: &str) -> Option<String> {
+    let (status, version) = output.trim().split_once('|')?;
+    if status.trim() == "ii" {
+        non_empty(version)
+    } else {
+        None
+    }
+}
+
+/// 解析 pacman -Q 的输出（"linyaps 1.9.9-1"）
+fn parse_pacman_query(output: &str) -> Option<String> {
+    output.split_whitespace().nth(1).and_then(non_empty)
+}
+
+fn query_package(family: DistroFamily, name: &str) -> PackageVersionInfo {
+    let (installed, candidate) = match family {
+        DistroFamily::Debian => {
+            let installed = run("dpkg-query", &["-W", "-f=${db:Status-Abbrev}|${Version}", name])
+                .and_then(|o| parse_dpkg_query(&o));
+            let candidate = run("apt-cache", &["policy", name]).and_then(|o| parse_field(&o, "Candidate"));
+            (installed, candidate)
+        }
+        DistroFamily::Fedora => {
+            let installed = run("rpm", &["-q", "--qf", "%{VERSION}-%{RELEASE}", name]).and_then(|o| non_empty(&o));
+            // -C 仅使用本地缓存，避免检查时刷新软件源
+            let candidate = run(
+                "dnf",
+                &["repoquery", "-C", "-q", "--latest-limit=1", "--qf", "%{version}-%{release}\n", name],
+            )
+            .and_then(|o| o.lines().next().and_then(non_empty));
+            (installed, candidate)
+        }
+        DistroFamily::Arch => {
+            let installed = run("pacman", &["-Q", name]).and_then(|o| parse_pacman_query(&o));
+            let candidate = run("pacman", &["-Si", name]).and_then(|o| parse_field(&o, "Version"));
+            (installed, candidate)
+        }
+        DistroFamily::Suse => {
+            let installed = run("rpm", &["-q", "--qf", "%{VERSION}-%{RELEASE}", name]).and_then(|o| non_empty(&o));
+            let candidate = run("zypper", &["--non-interactive", "--no-refresh", "info", name])
+                .and_then(|o| parse_field(&o, "Version"));
+            (installed, candidate)
+        }
+        DistroFamily::Unknown => (None, None),
+    };
+
+    PackageVersionInfo {
+        name: name.to_string(),
+        installed,
+        candidate,
+    }
+}
+
+/// 查询当前系统中玲珑相关软件包的版本，有效期内直接返回缓存结果
+pub fn query_linglong_packages() -> LinglongPackageReport {
+    if let Ok(cache) = PACKAGE_CACHE.lock() {
+        if let Some((at, report)) = cache.as_ref() {
+            if at.elapsed() < PACKAGE_CACHE_TTL {
+                return report.clone();
+            }
+        }
+    }
+
+    let release = fs::read_to_string("/etc/os-release")
+        .map(|c| parse_os_release(&c))
+        .unwrap_or_default();
+    let family = detect_family(&release);
+    let names = family.linglong_packages();
+
+    let packages = names
+        .iter()
+        .map(|name| query_package(family, name))
+        // 主程序包始终保留，其余只保留已安装或软件源中存在的
+        .filter(|p| p.name == names[0] || p.installed.is_some() || p.candidate.is_some())
+        .collect();
+
+    let report = LinglongPackageReport {
+        distro_id: release.id,
+        family,
+        package_manager: family.package_manager().to_string(),
+        packages,
+    };
+    if let Ok(mut cache) = PACKAGE_CACHE.lock() {
+        *cache = Some((Instant::now(), report.clone()));
+    }
+    report
+}
+
+/// 清除软件包查询缓存，安装或升级玲珑环境后调用
+pub fn invalidate_package_cache() {
+    if let Ok(mut cache) = PACKAGE_CACHE.lock() {
+        *cache = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_family() {
+        let deepin = parse_os_release("PRETTY_NAME=\"Deepin 23\"\nID=deepin\n");
+        assert_eq!(detect_family(&deepin), DistroFamily::Debian);
+        assert_eq!(deepin.pretty_name.as_deref(), Some("Deepin 23"));
+
+        let euler = parse_os_release("NAME=\"openEuler\"\nID=\"openEuler\"\n");
+        assert_eq!(detect_family(&euler), DistroFamily::Fedora);
+
+        let derived = parse_os_release("ID=somelinux\nID_LIKE=\"rhel centos fedora\"\n");
+        assert_eq!(detect_family(&derived), DistroFamily::Fedora);
+
+        let tumbleweed = parse_os_release("ID=\"opensuse-tumbleweed\"\nID_LIKE=\"opensuse suse\"\n");
+        assert_eq!(detect_family(&tumbleweed), DistroFamily::Suse);
+
+        let manjaro = parse_os_release("ID=manjaro\nID_LIKE=arch\n");
+        assert_eq!(detect_family(&manjaro), DistroFamily::Arch);
+
+        assert_eq!(detect_family(&parse_os_release("ID=nixos\n")), DistroFamily::Unknown);
+    }
+
+    #[test]
+    fn test_linglong_packages_per_family() {
+        assert_eq!(DistroFamily::Arch.linglong_packages()[0], "linyaps");
+        assert_eq!(DistroFamily::Debian.linglong_packages()[0], "linglong-bin");
+        assert!(!DistroFamily::Fedora.linglong_packages().contains(&"linyaps"));
+        assert_eq!(DistroFamily::Unknown.linglong_packages(), &["linglong-bin"]);
+    }
+
+    #[test]
+    fn test_parse_apt_policy() {
+        let output = "linglong-bin:\n  Installed: 1.9.9-1\n  Candidate: 1.9.10-1\n  Version table:\n";
+        assert_eq!(parse_field(output, "Installed").as_deref(), Some("1.9.9-1"));
+        assert_eq!(parse_field(output, "Candidate").as_deref(), Some("1.9.10-1"));
+        assert_eq!(parse_field("  Installed: (none)\n", "Installed"), None);
+    }
+
+    #[test]
+    fn test_parse_dpkg_and_pacman() {
+        assert_eq!(parse_dpkg_query("ii |1.9.9-1").as_deref(), Some("1.9.9-1"));
+        assert_eq!(parse_dpkg_query("rc |1.9.9-1"), None);
+        assert_eq!(parse_pacman_query("linyaps 1.9.9-1\n").as_deref(), Some("1.9.9-1"));
+        let si = "Repository      : extra\nName            : linyaps\nVersion         : 1.9.10-1\n";
+        assert_eq!(parse_field(si, "Version").as_deref(), Some("1.9.10-1"));
+    }
+
+    #[test]
+    fn test_report_ll_bin_version() {
+        let report = LinglongPackageReport {
+            distro_id: "arch".to_string(),
+            family: DistroFamily::Arch,
+            package_manager: "pacman".to_string(),
+            packages: vec![
+                PackageVersionInfo {
+                    name: "linglong-bin".to_string(),
+                    installed: None,
+                    candidate: None,
+                },
+                PackageVersionInfo {
+                    name: "linyaps".to_string(),
+                    installed: Some("1.9.9-1".to_string()),
+                    candidate: Some("1.9.10-1".to_string()),
+                },
+            ],
+        };
+        assert_eq!(report.ll_bin_version().as_deref(), Some("1.9.9-1"));
+        assert!(report.summary().contains("linyaps installed: 1.9.9-1 candidate: 1.9.10-1"));
+    }
+}
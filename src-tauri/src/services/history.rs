@@ -0,0 +1,93 @@
+//! 操作历史
+//!
+//! 记录后台执行的批量操作（如自动更新）的摘要，以 JSONL 格式追加保存在数据目录中。
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::jsonl;
+use crate::utils::paths::app_data_file;
+
+/// 操作历史文件
+const HISTORY_FILE: &str = "operation-history.jsonl";
+/// 最多保留的记录条数
+const HISTORY_LIMIT: usize = 500;
+
+/// 单个应用的操作结果
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationItem {
+    pub app_id: String,
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+    /// "success" | "failed" | "skipped"
+    pub status: String,
+    pub message: Option<String>,
+}
+
+/// 一次操作的摘要
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationRecord {
+    /// 操作类型，如 "auto-update"
+    pub kind: String,
+    /// 开始时间（Unix 毫秒）
+    pub started_at: u64,
+    /// 结束时间（Unix 毫秒）
+    pub finished_at: u64,
+    pub summary: String,
+    pub items: Vec<OperationItem>,
+}
+
+/// 追加一条操作记录，失败仅记录日志
+pub fn record_operation(record: &OperationRecord) {
+    let result = app_data_file(HISTORY_FILE).and_then(|path| jsonl::append(&path, record, HISTORY_LIMIT));
+    if let Err(e) = result {
+        warn!("[OperationHistory] {}", e);
+    }
+}
+
+/// 获取操作历史，最新的在前
+///
+/// # Arguments
+/// * `kind` - 仅返回指定类型的记录，为空时返回全部
+pub async fn get_operation_history(kind: Option<String>) -> Result<Vec<OperationRecord>, String> {
+    let mut records: Vec<OperationRecord> = jsonl::read(&app_data_file(HISTORY_FILE)?)?;
+    if let Some(kind) = kind.filter(|k| !k.is_empty()) {
+        records.retain(|r| r.kind == kind);
+    }
+    records.reverse();
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_history_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("operation-history-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(HISTORY_FILE);
+        let record = OperationRecord {
+            kind: "auto-update".to_string(),
+            started_at: 1,
+            finished_at: 2,
+            summary: "更新 1 个应用".to_string(),
+            items: vec![OperationItem {
+                app_id: "org.example.app".to_string(),
+                from_version: Some("1.0.0.0".to_string()),
+                to_version: Some("1.1.0.0".to_string()),
+                status: "success".to_string(),
+                message: None,
+            }],
+        };
+        jsonl::append(&path, &record, HISTORY_LIMIT).unwrap();
+        jsonl::append(&path, &record, HISTORY_LIMIT).unwrap();
+        let records: Vec<OperationRecord> = jsonl::read(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].items, record.items);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
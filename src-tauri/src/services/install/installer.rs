@@ -17,7 +17,10 @@ use std::process::Stdio;
 use std::sync::{Arc, Mutex};
 use tauri::AppHandle;
 
+use crate::services::dependencies::record_explicit_install;
+use crate::services::desktop_entry::repair_after_install;
 use crate::services::ll_cli_command;
+use crate::services::repo_health::ensure_online;
 
 use super::json_parser::{JsonEventType, JsonLineParser};
 use super::progress_emitter::{ProgressEmitter, ThreadSafeProgressEmitter};
@@ -52,6 +55,17 @@ pub async fn install_linglong_app(
     // 创建进度发送器
     let emitter = ProgressEmitter::new(&app_handle, app_id.clone());
 
+    // 检查仓库连通性，所有仓库均不可达时直接失败，避免等待 ll-cli 超时
+    if let Some(report) = ensure_online().await {
+        if !report.online {
+            let err_msg = "网络不可用：所有已配置的仓库均无法访问".to_string();
+            error!("[Installer] ERROR: {}", err_msg);
+            emitter.emit_error(3001, &err_msg);
+            InstallSlot::release();
+            return Err(err_msg);
+        }
+    }
+
     // 2. 构建应用引用
     let app_ref = if let Some(ref ver) = version {
         format!("{}/{}", app_id, ver)
@@ -286,6 +300,11 @@ pub async fn install_linglong_app(
         info!("[Installer] SUCCESS: {}", success_msg);
         emitter.emit_success();
 
+        // 补全可能缺失的菜单项
+        repair_after_install(&app_id);
+        // 由商店安装的视为用户主动安装，其依赖不记录
+        record_explicit_install(&app_id);
+
         info!("========== [Installer] END ==========");
         Ok(success_msg)
     } else if was_cancelled {
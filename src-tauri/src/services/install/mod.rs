@@ -10,7 +10,7 @@
 //! - `slot`: 安装槽位管理（含取消标志）
 //! - `progress_emitter`: 进度事件发送器
 //! - `installer`: 安装器核心逻辑
-//! - `operations`: 其他操作（list, search, run, uninstall）
+//! - `operations`: 其他操作（list, search, run, uninstall, upgrade）
 
 mod models;
 mod error_codes;
@@ -22,11 +22,13 @@ mod installer;
 mod operations;
 
 // 重新导出公共 API
-pub use models::InstalledApp;
+pub use models::{InstalledApp, RunAppOptions};
 pub use installer::{install_linglong_app, cancel_linglong_install};
 pub use operations::{
     get_installed_apps,
     uninstall_linglong_app,
+    upgrade_linglong_app,
+    UpgradeError,
     search_app_versions,
     run_linglong_app,
 };
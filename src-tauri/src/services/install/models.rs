@@ -3,6 +3,7 @@
 //! 本模块定义了安装相关的核心数据结构。
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// 已安装的玲珑应用信息
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,12 +29,73 @@ pub struct InstalledApp {
     pub module: String,
     /// 运行时依赖
     pub runtime: String,
-    /// 应用大小
+    /// 应用大小（ll-cli 原始输出）
     pub size: String,
+    /// 应用大小（字节），由 size 解析得到，无法解析时为 0
+    #[serde(default)]
+    pub size_bytes: u64,
     /// 仓库名称
     pub repo_name: String,
 }
 
+/// 测试用的已安装应用构造器
+#[cfg(test)]
+impl InstalledApp {
+    /// x86_64、main 频道、binary 模块的应用
+    pub(crate) fn test(app_id: &str, version: &str) -> Self {
+        InstalledApp {
+            app_id: app_id.to_string(),
+            name: app_id.to_string(),
+            version: version.to_string(),
+            arch: "x86_64".to_string(),
+            channel: "main".to_string(),
+            description: String::new(),
+            icon: String::new(),
+            kind: Some("app".to_string()),
+            module: "binary".to_string(),
+            runtime: String::new(),
+            size: "0".to_string(),
+            size_bytes: 0,
+            repo_name: "stable".to_string(),
+        }
+    }
+
+    pub(crate) fn with_kind(mut self, kind: &str) -> Self {
+        self.kind = Some(kind.to_string());
+        self
+    }
+
+    pub(crate) fn with_module(mut self, module: &str) -> Self {
+        self.module = module.to_string();
+        self
+    }
+
+    pub(crate) fn with_channel(mut self, channel: &str) -> Self {
+        self.channel = channel.to_string();
+        self
+    }
+
+    pub(crate) fn with_size(mut self, size: &str) -> Self {
+        self.size = size.to_string();
+        self.size_bytes = parse_size_bytes(size);
+        self
+    }
+}
+
+/// 启动应用的选项
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunAppOptions {
+    /// 指定已安装的版本，为空时由 ll-cli 选择
+    pub version: Option<String>,
+    /// 传递给应用的参数，放在 `--` 之后
+    pub args: Vec<String>,
+    /// 用应用打开的文件路径或 URL
+    pub files: Vec<String>,
+    /// 额外的环境变量
+    pub env: BTreeMap<String, String>,
+}
+
 /// 安装进度事件数据结构
 ///
 /// 统一的 install-progress 事件，根据 eventType 区分不同类型：
@@ -100,6 +162,8 @@ impl LLCliListItem {
             _ => "0".to_string(),
         };
 
+        let size_bytes = parse_size_bytes(&size);
+
         InstalledApp {
             app_id: self.app_id.unwrap_or_else(|| self.name.clone()),
             name: self.name,
@@ -112,7 +176,48 @@ impl LLCliListItem {
             module: self.module.unwrap_or_default(),
             runtime: self.runtime.unwrap_or_default(),
             size,
+            size_bytes,
             repo_name: "stable".to_string(), // 默认仓库
         }
     }
 }
+
+/// 将 ll-cli 输出的大小解析为字节数
+///
+/// 支持纯数字（字节）以及 `12.5 MB`、`3G`、`800KiB` 等带单位的写法，单位按 1024 换算。
+pub(crate) fn parse_size_bytes(size: &str) -> u64 {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let Ok(number) = number.parse::<f64>() else {
+        return 0;
+    };
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return 0,
+    };
+    (number * multiplier as f64).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size_bytes() {
+        assert_eq!(parse_size_bytes("0"), 0);
+        assert_eq!(parse_size_bytes("123456"), 123456);
+        assert_eq!(parse_size_bytes(" 2 KB "), 2048);
+        assert_eq!(parse_size_bytes("1.5M"), 1572864);
+        assert_eq!(parse_size_bytes("1GiB"), 1 << 30);
+        assert_eq!(parse_size_bytes(""), 0);
+        assert_eq!(parse_size_bytes("unknown"), 0);
+        assert_eq!(parse_size_bytes("12 parsecs"), 0);
+    }
+}
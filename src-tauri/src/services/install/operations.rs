@@ -4,14 +4,19 @@
 //! - 获取已安装应用列表
 //! - 卸载应用
 //! - 搜索应用版本
-//! - 运行应用
+//! - 运行应用（启动日志见 `launch_log`）
 
 use log::{error, info, warn};
+use std::fmt;
+use tauri::AppHandle;
 
+use crate::services::launch_log::launch_with_log;
 use crate::services::ll_cli_command;
 use crate::services::process::kill_linglong_app;
+use crate::services::version::compare_version_str;
 
-use super::models::{InstalledApp, LLCliListItem};
+use super::models::{InstalledApp, LLCliListItem, RunAppOptions};
+use super::slot::{AcquireError, InstallSlot};
 
 /// 获取已安装的玲珑应用列表
 ///
@@ -111,6 +116,61 @@ pub async fn uninstall_linglong_app(app_id: String, version: String) -> Result<S
     ))
 }
 
+/// 升级失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpgradeError {
+    /// 安装槽位被其他任务占用，未执行升级
+    Busy(String),
+    /// 升级执行失败
+    Failed(String),
+}
+
+impl fmt::Display for UpgradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpgradeError::Busy(msg) | UpgradeError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<AcquireError> for UpgradeError {
+    fn from(e: AcquireError) -> Self {
+        match e {
+            AcquireError::Busy(_) => UpgradeError::Busy(e.to_string()),
+            AcquireError::Lock(_) => UpgradeError::Failed(e.to_string()),
+        }
+    }
+}
+
+impl From<String> for UpgradeError {
+    fn from(e: String) -> Self {
+        UpgradeError::Failed(e)
+    }
+}
+
+/// 通过 `ll-cli upgrade` 将应用升级到最新版本，供后台无人值守更新使用
+///
+/// 与安装共用安装槽位，但不发送 install-progress 事件，避免在前端弹出安装界面。
+pub async fn upgrade_linglong_app(app_id: String) -> Result<String, UpgradeError> {
+    InstallSlot::acquire(&app_id)?;
+    info!("[Upgrade] Upgrading {}", app_id);
+    let target = app_id.clone();
+    let output = tokio::task::spawn_blocking(move || ll_cli_command().arg("upgrade").arg(&target).output()).await;
+    InstallSlot::release();
+
+    let output = output
+        .map_err(|e| format!("升级任务执行失败: {}", e))?
+        .map_err(|e| format!("Failed to execute 'll-cli upgrade': {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let detail = if stderr.is_empty() { stdout } else { stderr };
+        error!("[Upgrade] Failed to upgrade {}: {}", app_id, detail);
+        return Err(format!("ll-cli upgrade command failed: {}", detail).into());
+    }
+    Ok(format!("Successfully upgraded {}", app_id))
+}
+
 /// 搜索指定 appId 的所有已安装版本
 ///
 /// # Arguments
@@ -164,7 +224,7 @@ pub async fn search_app_versions(app_id: String) -> Result<Vec<InstalledApp>, St
     info!("[SearchVersions] Found {} installed items", list_items.len());
 
     // 过滤出指定 app_id 的所有版本
-    let apps: Vec<InstalledApp> = list_items
+    let mut apps: Vec<InstalledApp> = list_items
         .into_iter()
         .filter(|item| {
             // 匹配 app_id 或 name
@@ -182,6 +242,9 @@ pub async fn search_app_versions(app_id: String) -> Result<Vec<InstalledApp>, St
         .map(|item| item.into_installed_app())
         .collect();
 
+    // 按版本从新到旧排序
+    apps.sort_by(|a, b| compare_version_str(&b.version, &a.version));
+
     info!(
         "[SearchVersions] Found {} installed versions for: {}",
         apps.len(),
@@ -198,48 +261,171 @@ pub async fn search_app_versions(app_id: String) -> Result<Vec<InstalledApp>, St
     Ok(apps)
 }
 
+/// 拒绝以 `-` 开头的值，避免被 ll-cli 当作选项解析
+fn ensure_not_option(kind: &str, value: &str) -> Result<(), String> {
+    if value.starts_with('-') {
+        return Err(format!("{}不能以 '-' 开头: {}", kind, value));
+    }
+    Ok(())
+}
+
+/// 环境变量名只允许字母、数字和下划线，且不以数字开头
+fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 带协议头的视为 URL（如 `https://`、`file://`），其余视为文件路径
+fn is_url(value: &str) -> bool {
+    match value.split_once("://") {
+        Some((scheme, _)) => {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// 校验启动选项并构建 `ll-cli run` 的参数
+///
+/// 应用参数放在 `--` 之后，允许以 `-` 开头；其余输入都会被校验。
+pub(super) fn build_run_args(app_id: &str, options: &RunAppOptions) -> Result<Vec<String>, String> {
+    let app_id = app_id.trim();
+    if app_id.is_empty() {
+        return Err("应用 ID 不能为空".to_string());
+    }
+    ensure_not_option("应用 ID", app_id)?;
+    if app_id.chars().any(|c| c.is_whitespace() || c == '/') {
+        return Err(format!("应用 ID 不合法: {}", app_id));
+    }
+
+    let app_ref = match options.version.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(version) => {
+            ensure_not_option("版本号", version)?;
+            if version.chars().any(|c| c.is_whitespace() || c == '/') {
+                return Err(format!("版本号不合法: {}", version));
+            }
+            format!("{}/{}", app_id, version)
+        }
+        None => app_id.to_string(),
+    };
+
+    let mut args = vec!["run".to_string()];
+    for file in &options.files {
+        if file.is_empty() {
+            continue;
+        }
+        ensure_not_option("文件路径", file)?;
+        if is_url(file) {
+            args.push(format!("--url={}", file));
+        } else {
+            if !file.starts_with('/') {
+                return Err(format!("文件路径必须为绝对路径: {}", file));
+            }
+            args.push(format!("--file={}", file));
+        }
+    }
+    for (key, value) in &options.env {
+        if !is_valid_env_key(key) {
+            return Err(format!("环境变量名不合法: {}", key));
+        }
+        args.push(format!("--env={}={}", key, value));
+    }
+
+    args.push(app_ref);
+    if !options.args.is_empty() {
+        args.push("--".to_string());
+        args.extend(options.args.iter().cloned());
+    }
+    Ok(args)
+}
+
 /// 运行指定的玲珑应用
 ///
-/// 根据 ll-cli 文档，run 命令只需要应用名，不需要版本号。
+/// 可指定已安装的版本、打开的文件或 URL、环境变量以及传给应用的参数。
 ///
 /// # Arguments
+/// * `app_handle` - 用于发送启动失败事件
 /// * `app_id` - 应用 ID
+/// * `options` - 启动选项
 ///
 /// # Returns
 /// * `Ok(String)` - 启动成功消息
-/// * `Err(String)` - 启动失败原因
-pub async fn run_linglong_app(app_id: String) -> Result<String, String> {
+/// * `Err(String)` - 启动失败原因或选项校验失败原因
+pub async fn run_linglong_app(
+    app_handle: AppHandle,
+    app_id: String,
+    options: RunAppOptions,
+) -> Result<String, String> {
+    let args = build_run_args(&app_id, &options)?;
     info!("[Run] Starting app: {}", app_id);
-    info!("[Run] Command: ll-cli run {}", app_id);
-
-    // 在后台线程中启动命令，不等待退出
-    let app_id_bg = app_id.clone();
-    std::thread::spawn(move || {
-        info!("[Run:bg] Spawning ll-cli run {}", app_id_bg);
-
-        let mut cmd = ll_cli_command();
-        let spawn_result = cmd
-            .arg("run")
-            .arg(&app_id_bg)
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .spawn();
-
-        match spawn_result {
-            Ok(child) => {
-                info!("[Run:bg] Process spawned with PID: {:?}", child.id());
-                // 不 wait，让子进程自行运行
-            }
-            Err(e) => {
-                error!(
-                    "[Run:bg] Failed to execute 'll-cli run' for {}: {}",
-                    app_id_bg, e
-                );
-            }
-        }
-    });
+    info!("[Run] Command: ll-cli {}", args.join(" "));
+
+    // 不等待退出，启动失败通过 app-launch-failed 事件通知
+    let mut cmd = ll_cli_command();
+    cmd.args(&args);
+    launch_with_log(app_handle, app_id.trim(), cmd)?;
 
-    // 立即返回
     Ok(format!("Successfully launched {}", app_id))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_run_args() {
+        let options = RunAppOptions {
+            version: Some("1.0.0.0".to_string()),
+            args: vec!["--verbose".to_string()],
+            files: vec!["/home/user/a b.txt".to_string(), "https://example.com".to_string()],
+            env: [("LANG".to_string(), "zh_CN.UTF-8".to_string())].into(),
+        };
+        let args = build_run_args("org.example.app", &options).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "--file=/home/user/a b.txt",
+                "--url=https://example.com",
+                "--env=LANG=zh_CN.UTF-8",
+                "org.example.app/1.0.0.0",
+                "--",
+                "--verbose",
+            ]
+        );
+        assert_eq!(
+            build_run_args("org.example.app", &RunAppOptions::default()).unwrap(),
+            vec!["run", "org.example.app"]
+        );
+    }
+
+    #[test]
+    fn test_build_run_args_rejects_options() {
+        let run = |app_id: &str, options: RunAppOptions| build_run_args(app_id, &options);
+        assert!(run("--help", RunAppOptions::default()).is_err());
+        assert!(run("org.example.app", RunAppOptions {
+            version: Some("--base=x".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(run("org.example.app", RunAppOptions {
+            files: vec!["-rf".to_string()],
+            ..Default::default()
+        })
+        .is_err());
+        assert!(run("org.example.app", RunAppOptions {
+            files: vec!["relative.txt".to_string()],
+            ..Default::default()
+        })
+        .is_err());
+        assert!(run("org.example.app", RunAppOptions {
+            env: [("-X".to_string(), "1".to_string())].into(),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
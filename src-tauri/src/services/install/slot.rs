@@ -9,6 +9,7 @@
 
 use log::info;
 use once_cell::sync::Lazy;
+use std::fmt;
 use std::sync::{Arc, Mutex, MutexGuard};
 
 /// 安装槽位状态
@@ -20,6 +21,30 @@ pub struct InstallSlotState {
     pub is_cancelled: bool,
 }
 
+/// 占用槽位失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum AcquireError {
+    /// 槽位已被占用，携带正在安装的应用 ID
+    Busy(String),
+    /// 获取锁失败
+    Lock(String),
+}
+
+impl fmt::Display for AcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcquireError::Busy(app_id) => write!(f, "已有应用正在安装中: {}，请等待完成后再试", app_id),
+            AcquireError::Lock(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<AcquireError> for String {
+    fn from(e: AcquireError) -> Self {
+        e.to_string()
+    }
+}
+
 /// 全局安装槽位（单槽位模式）
 static INSTALL_SLOT: Lazy<Arc<Mutex<Option<InstallSlotState>>>> =
     Lazy::new(|| Arc::new(Mutex::new(None)));
@@ -39,15 +64,12 @@ impl InstallSlot {
     ///
     /// # Returns
     /// * `Ok(())` - 成功占用槽位
-    /// * `Err(String)` - 槽位已被占用，返回正在安装的应用 ID
-    pub fn acquire(app_id: &str) -> Result<(), String> {
-        let mut slot = Self::lock()?;
+    /// * `Err(AcquireError::Busy)` - 槽位已被占用，携带正在安装的应用 ID
+    pub fn acquire(app_id: &str) -> Result<(), AcquireError> {
+        let mut slot = Self::lock().map_err(AcquireError::Lock)?;
 
         if let Some(ref state) = *slot {
-            return Err(format!(
-                "已有应用正在安装中: {}，请等待完成后再试",
-                state.app_id
-            ));
+            return Err(AcquireError::Busy(state.app_id.clone()));
         }
 
         *slot = Some(InstallSlotState {
@@ -148,7 +170,10 @@ mod tests {
         assert_eq!(InstallSlot::current_app_id(), Some("test.app".to_string()));
 
         // 再次占用应失败
-        assert!(InstallSlot::acquire("another.app").is_err());
+        assert_eq!(
+            InstallSlot::acquire("another.app"),
+            Err(AcquireError::Busy("test.app".to_string()))
+        );
 
         // 释放槽位
         InstallSlot::release();
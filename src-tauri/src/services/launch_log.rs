@@ -0,0 +1,292 @@
+//! 应用启动日志
+//!
+//! 每次 `ll-cli run` 的 stdout/stderr 都写入独立的启动日志文件，
+//! 并保留子进程句柄等待其退出。若进程在启动后的短时间内以非零状态退出
+//! （如缺少运行时、沙箱创建失败），发送 `app-launch-failed` 事件并附带输出。
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::services::app_watcher::{record_exit_status, wake_app_watcher};
+use crate::utils::paths::app_private_dir;
+use crate::utils::time::now_millis;
+
+/// 启动失败事件名称
+const LAUNCH_FAILED_EVENT: &str = "app-launch-failed";
+/// 启动日志目录
+const LOG_DIR: &str = "launch-logs";
+/// 在此时间内非零退出视为启动失败
+const FAILURE_WINDOW: Duration = Duration::from_secs(5);
+/// 每个应用保留的启动日志数量
+const LOGS_PER_APP: usize = 20;
+/// 事件与查看器返回的最大输出长度
+const MAX_OUTPUT_BYTES: u64 = 64 * 1024;
+
+/// 启动失败事件数据
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLaunchFailed {
+    pub app_id: String,
+    /// 被信号终止时为 None
+    pub exit_code: Option<i32>,
+    /// 启动日志末尾的输出
+    pub output: String,
+    pub log_name: Option<String>,
+}
+
+/// 一条启动日志
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchLogEntry {
+    /// 日志文件名，查看日志时使用
+    pub name: String,
+    pub app_id: String,
+    /// 启动时间（Unix 毫秒）
+    pub started_at: u64,
+    pub size_bytes: u64,
+}
+
+/// 由日志文件名 `<appId>-<启动时间>.log` 解析出日志信息
+fn parse_log_name(name: &str) -> Option<(String, u64)> {
+    let stem = name.strip_suffix(".log")?;
+    let (app_id, started_at) = stem.rsplit_once('-')?;
+    if app_id.is_empty() {
+        return None;
+    }
+    Some((app_id.to_string(), started_at.parse().ok()?))
+}
+
+fn list_entries(dir: &Path) -> Vec<LaunchLogEntry> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<LaunchLogEntry> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let (app_id, started_at) = parse_log_name(&name)?;
+            let size_bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            Some(LaunchLogEntry {
+                name,
+                app_id,
+                started_at,
+                size_bytes,
+            })
+        })
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.started_at));
+    entries
+}
+
+/// 选出超出保留数量的旧日志（entries 需按时间倒序）
+fn logs_to_prune(entries: &[LaunchLogEntry], app_id: &str, keep: usize) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|entry| entry.app_id == app_id)
+        .skip(keep)
+        .map(|entry| entry.name.clone())
+        .collect()
+}
+
+/// 读取文件末尾最多 MAX_OUTPUT_BYTES 字节
+fn read_tail(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("打开日志 {:?} 失败: {}", path, e))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len > MAX_OUTPUT_BYTES {
+        let _ = file.seek(SeekFrom::Start(len - MAX_OUTPUT_BYTES));
+    }
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)
+        .map_err(|e| format!("读取日志 {:?} 失败: {}", path, e))?;
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+/// 启动进程并把输出写入日志，在后台线程中等待其退出
+///
+/// 进程在 FAILURE_WINDOW 内非零退出时调用 on_failure。
+fn spawn_logged<F>(mut cmd: Command, app_id: &str, log_path: Option<PathBuf>, on_failure: F) -> Result<u32, String>
+where
+    F: FnOnce(AppLaunchFailed) + Send + 'static,
+{
+    let log_file = log_path.as_ref().and_then(|path| {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| warn!("[Launch] Cannot open launch log {:?}: {}", path, e))
+            .ok()
+    });
+    let stdio = |file: &Option<File>| match file.as_ref().and_then(|f| f.try_clone().ok()) {
+        Some(f) => Stdio::from(f),
+        None => Stdio::null(),
+    };
+    cmd.stdin(Stdio::null())
+        .stdout(stdio(&log_file))
+        .stderr(stdio(&log_file));
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to execute 'll-cli run' for {}: {}", app_id, e))?;
+    let pid = child.id();
+    info!("[Launch] {} spawned with PID: {}", app_id, pid);
+
+    let app_id = app_id.to_string();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if started.elapsed() < FAILURE_WINDOW => {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                // 已过启动窗口，继续持有句柄直到退出，避免留下僵尸进程
+                Ok(None) => break child.wait().ok(),
+                Err(e) => {
+                    error!("[Launch] Failed to wait for {}: {}", app_id, e);
+                    break None;
+                }
+            }
+        };
+        let elapsed = started.elapsed();
+
+        let Some(status) = status else {
+            return;
+        };
+        info!("[Launch] {} exited with {} after {:?}", app_id, status, elapsed);
+        record_exit_status(&app_id, status.code());
+        if let Some(mut file) = log_file {
+            let _ = writeln!(file, "\n[linglong-store] ll-cli run exited: {}", status);
+        }
+
+        if !status.success() && elapsed < FAILURE_WINDOW {
+            let output = log_path
+                .as_deref()
+                .and_then(|path| read_tail(path).ok())
+                .unwrap_or_default();
+            on_failure(AppLaunchFailed {
+                app_id,
+                exit_code: status.code(),
+                output,
+                log_name: log_path
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string()),
+            });
+        }
+    });
+    Ok(pid)
+}
+
+/// 启动应用并记录启动日志
+///
+/// # Arguments
+/// * `app_id` - 应用 ID，用于日志文件名与事件
+/// * `cmd` - 已构建好参数的 `ll-cli run` 命令
+pub fn launch_with_log(app_handle: AppHandle, app_id: &str, cmd: Command) -> Result<u32, String> {
+    let dir = app_private_dir(LOG_DIR)
+        .map_err(|e| warn!("[Launch] {}", e))
+        .ok();
+    let log_path = dir
+        .as_ref()
+        .map(|dir| dir.join(format!("{}-{}.log", app_id, now_millis())));
+
+    if let Some(dir) = &dir {
+        for name in logs_to_prune(&list_entries(dir), app_id, LOGS_PER_APP.saturating_sub(1)) {
+            let _ = fs::remove_file(dir.join(name));
+        }
+    }
+
+    let pid = spawn_logged(cmd, app_id, log_path, move |failed| {
+        warn!(
+            "[Launch] {} failed to start (exit code {:?})",
+            failed.app_id, failed.exit_code
+        );
+        let _ = app_handle.emit(LAUNCH_FAILED_EVENT, &failed);
+    })?;
+    wake_app_watcher();
+    Ok(pid)
+}
+
+/// 获取启动日志列表，按启动时间倒序
+///
+/// # Arguments
+/// * `app_id` - 仅返回该应用的日志，为空时返回全部
+pub async fn get_launch_logs(app_id: Option<String>) -> Result<Vec<LaunchLogEntry>, String> {
+    let dir = app_private_dir(LOG_DIR)?;
+    Ok(list_entries(&dir)
+        .into_iter()
+        .filter(|entry| app_id.as_ref().is_none_or(|id| &entry.app_id == id))
+        .collect())
+}
+
+/// 读取启动日志内容（超过 64 KB 时只返回末尾部分）
+///
+/// # Arguments
+/// * `name` - get_launch_logs 返回的日志文件名
+pub async fn read_launch_log(name: String) -> Result<String, String> {
+    if name.contains('/') || parse_log_name(&name).is_none() {
+        return Err(format!("无效的启动日志名称: {}", name));
+    }
+    let path = app_private_dir(LOG_DIR)?.join(&name);
+    read_tail(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_parse_log_name() {
+        assert_eq!(
+            parse_log_name("org.example.app-1700000000000.log"),
+            Some(("org.example.app".to_string(), 1700000000000))
+        );
+        assert_eq!(
+            parse_log_name("org.example-app-12.log"),
+            Some(("org.example-app".to_string(), 12))
+        );
+        assert_eq!(parse_log_name("org.example.app.log"), None);
+        assert_eq!(parse_log_name("-12.log"), None);
+    }
+
+    #[test]
+    fn test_logs_to_prune() {
+        let entry = |app_id: &str, started_at: u64| LaunchLogEntry {
+            name: format!("{}-{}.log", app_id, started_at),
+            app_id: app_id.to_string(),
+            started_at,
+            size_bytes: 0,
+        };
+        let entries = vec![entry("a", 3), entry("b", 3), entry("a", 2), entry("a", 1)];
+        assert_eq!(logs_to_prune(&entries, "a", 1), vec!["a-2.log", "a-1.log"]);
+        assert!(logs_to_prune(&entries, "b", 1).is_empty());
+    }
+
+    #[test]
+    fn test_early_failure_reports_output() {
+        let dir = std::env::temp_dir().join(format!("ll-launch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("org.example.app-1.log");
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo missing runtime >&2; exit 3");
+        let (tx, rx) = mpsc::channel();
+        spawn_logged(cmd, "org.example.app", Some(log_path.clone()), move |failed| {
+            let _ = tx.send(failed);
+        })
+        .unwrap();
+
+        let failed = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(failed.exit_code, Some(3));
+        assert!(failed.output.contains("missing runtime"));
+        assert_eq!(failed.log_name.as_deref(), Some("org.example.app-1.log"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,11 @@
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use crate::services::install::get_installed_apps;
+use crate::services::version::compare_version_str;
 use crate::services::ll_cli_command;
+use crate::services::repo::{read_repo_config, LinglongRepoConfig};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -13,26 +18,34 @@ pub struct SearchResultItem {
     pub description: Option<String>,
     pub module: Option<String>,
     pub icon: Option<String>,
+    pub channel: Option<String>,
+    /// 来源仓库名称
+    pub repo_name: Option<String>,
 }
 
-pub async fn search_remote_app(app_id: String) -> Result<Vec<SearchResultItem>, String> {
-    let output = ll_cli_command()
-        .arg("search")
-        .arg(&app_id)
-        .arg("--json")
-        .output()
-        .map_err(|e| format!("Failed to execute 'll-cli search': {}", e))?;
-
-    if !output.status.success() {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        // If it's just not found or network error, we might want to return empty or error.
-        // For now, return error so frontend knows.
-        return Err(format!("ll-cli search command failed: {}", error_msg));
-    }
+/// 远程仓库中某个应用的单个可用版本
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteAppVersion {
+    pub app_id: String,
+    pub name: String,
+    pub version: String,
+    /// 来源仓库名称（ll-cli search 输出中的分组 key）
+    pub repo_name: String,
+    pub channel: String,
+    pub arch: Vec<String>,
+    pub module: String,
+    pub description: Option<String>,
+    /// 本地是否已安装该版本
+    pub installed: bool,
+}
 
-    let output_string = String::from_utf8_lossy(&output.stdout);
-    let trimmed = output_string.trim();
-    
+/// 解析 ll-cli search --json 的输出，保留每一项的来源仓库
+///
+/// 新版 ll-cli 输出按仓库分组的对象（如 {"stable": [...]}），旧版直接输出数组，
+/// 此时来源仓库为 None。
+fn parse_search_output(raw: &str) -> Result<Vec<(Option<String>, SearchResultItem)>, String> {
+    let trimmed = raw.trim();
     if trimmed.is_empty() {
         return Ok(Vec::new());
     }
@@ -40,8 +53,8 @@ pub async fn search_remote_app(app_id: String) -> Result<Vec<SearchResultItem>,
     // Try parsing as a map first (e.g. {"stable": [...]})
     if let Ok(map) = serde_json::from_str::<HashMap<String, Vec<SearchResultItem>>>(trimmed) {
         let mut all_results = Vec::new();
-        for (_, items) in map {
-            all_results.extend(items);
+        for (repo, items) in map {
+            all_results.extend(items.into_iter().map(|item| (Some(repo.clone()), item)));
         }
         return Ok(all_results);
     }
@@ -50,9 +63,432 @@ pub async fn search_remote_app(app_id: String) -> Result<Vec<SearchResultItem>,
     let search_results: Vec<SearchResultItem> = serde_json::from_str(trimmed)
         .map_err(|e| format!("Failed to parse search result: {}", e))?;
 
-    Ok(search_results)
+    Ok(search_results.into_iter().map(|item| (None, item)).collect())
+}
+
+/// 将 arch 字段（字符串或数组）统一为字符串列表
+pub(crate) fn arch_list(arch: &Option<serde_json::Value>) -> Vec<String> {
+    match arch {
+        Some(serde_json::Value::String(s)) if !s.is_empty() => vec![s.clone()],
+        Some(serde_json::Value::Array(arr)) => arr
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 执行一次 ll-cli search，并为每一项填充来源仓库
+///
+/// # Arguments
+/// * `query` - 搜索关键字
+/// * `repo` - 指定搜索的仓库，为 None 时搜索所有已配置仓库
+/// * `show_all_version` - 是否列出所有版本
+pub(crate) fn run_search(
+    query: &str,
+    repo: Option<&str>,
+    show_all_version: bool,
+) -> Result<Vec<SearchResultItem>, String> {
+    let mut cmd = ll_cli_command();
+    cmd.arg("search").arg(query).arg("--json");
+    if let Some(repo) = repo {
+        cmd.arg("--repo").arg(repo);
+    }
+    if show_all_version {
+        cmd.arg("--show-all-version");
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to execute 'll-cli search': {}", e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        // If it's just not found or network error, we might want to return empty or error.
+        // For now, return error so frontend knows.
+        return Err(format!("ll-cli search command failed: {}", error_msg));
+    }
+
+    parse_search_items(&String::from_utf8_lossy(&output.stdout), repo)
+}
+
+/// 解析 ll-cli search 的 JSON 输出，并为每一项填充来源仓库
+pub(crate) fn parse_search_items(raw: &str, repo: Option<&str>) -> Result<Vec<SearchResultItem>, String> {
+    Ok(parse_search_output(raw)?
+        .into_iter()
+        .map(|(key, mut item)| {
+            if item.repo_name.is_none() {
+                item.repo_name = key.or_else(|| repo.map(|r| r.to_string()));
+            }
+            item
+        })
+        .collect())
+}
+
+/// 搜索远程应用
+///
+/// # Arguments
+/// * `app_id` - 搜索关键字
+/// * `repos` - 指定搜索的仓库列表，为空时搜索所有已配置仓库
+///
+/// 相同 appId/版本/架构/模块 的条目只保留优先级最高的仓库来源，
+/// 结果按匹配程度排序，同一应用的 binary 与 develop 模块相邻排列。
+pub async fn search_remote_app(
+    app_id: String,
+    repos: Option<Vec<String>>,
+) -> Result<Vec<SearchResultItem>, String> {
+    let config = read_repo_config().unwrap_or_else(|e| {
+        warn!("[Search] Failed to read repo config: {}", e);
+        LinglongRepoConfig::default()
+    });
+
+    let selected: Vec<String> = repos
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| !r.trim().is_empty())
+        .collect();
+
+    let items = if selected.is_empty() {
+        let query = app_id.clone();
+        tokio::task::spawn_blocking(move || run_search(&query, None, false))
+            .await
+            .map_err(|e| format!("搜索任务执行失败: {}", e))??
+    } else {
+        // 各仓库并发搜索，部分仓库失败时仍返回其他仓库的结果
+        let handles: Vec<_> = selected
+            .into_iter()
+            .map(|repo| {
+                let query = app_id.clone();
+                tokio::task::spawn_blocking(move || {
+                    run_search(&query, Some(&repo), false).map_err(|e| format!("{}: {}", repo, e))
+                })
+            })
+            .collect();
+
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+        for handle in handles {
+            match handle.await {
+                Ok(Ok(result)) => items.extend(result),
+                Ok(Err(e)) => {
+                    warn!("[Search] Repo search failed: {}", e);
+                    errors.push(e);
+                }
+                Err(e) => errors.push(format!("搜索任务执行失败: {}", e)),
+            }
+        }
+        if items.is_empty() && !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        items
+    };
+
+    let mut items = dedup_by_repo_priority(items, &config);
+    rank_search_results(&app_id, &mut items, &config);
+    Ok(items)
+}
+
+pub(crate) fn module_of(item: &SearchResultItem) -> &str {
+    item.module.as_deref().unwrap_or("binary")
+}
+
+pub(crate) fn app_id_of(item: &SearchResultItem) -> &str {
+    item.app_id.as_deref().unwrap_or(&item.name)
+}
+
+/// 按 appId/版本/架构/模块 去重，保留仓库优先级最高的条目
+fn dedup_by_repo_priority(
+    items: Vec<SearchResultItem>,
+    config: &LinglongRepoConfig,
+) -> Vec<SearchResultItem> {
+    let mut kept: Vec<SearchResultItem> = Vec::new();
+    let mut index: HashMap<(String, String, String, String), usize> = HashMap::new();
+
+    for item in items {
+        let mut arch = arch_list(&item.arch);
+        arch.sort();
+        let key = (
+            app_id_of(&item).to_string(),
+            item.version.clone(),
+            arch.join(","),
+            module_of(&item).to_string(),
+        );
+        match index.get(&key) {
+            Some(&pos) => {
+                let existing_rank = config.rank_of(kept[pos].repo_name.as_deref().unwrap_or(""));
+                let rank = config.rank_of(item.repo_name.as_deref().unwrap_or(""));
+                if rank > existing_rank {
+                    kept[pos] = item;
+                }
+            }
+            None => {
+                index.insert(key, kept.len());
+                kept.push(item);
+            }
+        }
+    }
+
+    kept
+}
+
+/// 计算条目与搜索关键字的匹配程度，数值越小匹配越好
+///
+/// 0: appId 完全匹配；1: appId 前缀匹配；2: 名称完全匹配；
+/// 3: appId 或名称包含关键字；4: 描述包含关键字；5: 其他
+fn match_rank(query: &str, item: &SearchResultItem) -> u8 {
+    let query = query.trim().to_lowercase();
+    let id = app_id_of(item).to_lowercase();
+    let name = item.name.to_lowercase();
+
+    if id == query {
+        0
+    } else if id.starts_with(&query) {
+        1
+    } else if name == query {
+        2
+    } else if id.contains(&query) || name.contains(&query) {
+        3
+    } else if item
+        .description
+        .as_deref()
+        .is_some_and(|d| d.to_lowercase().contains(&query))
+    {
+        4
+    } else {
+        5
+    }
+}
+
+fn module_order(module: &str) -> u8 {
+    match module {
+        "binary" => 0,
+        "develop" | "devel" => 1,
+        _ => 2,
+    }
+}
+
+/// 按匹配程度排序，并将同一应用的各模块归为一组
+fn rank_search_results(query: &str, items: &mut [SearchResultItem], config: &LinglongRepoConfig) {
+    let mut group_rank: HashMap<String, u8> = HashMap::new();
+    for item in items.iter() {
+        let rank = match_rank(query, item);
+        group_rank
+            .entry(app_id_of(item).to_string())
+            .and_modify(|r| *r = (*r).min(rank))
+            .or_insert(rank);
+    }
+
+    items.sort_by(|a, b| {
+        let a_id = app_id_of(a);
+        let b_id = app_id_of(b);
+        group_rank[a_id]
+            .cmp(&group_rank[b_id])
+            .then_with(|| a_id.cmp(b_id))
+            .then_with(|| module_order(module_of(a)).cmp(&module_order(module_of(b))))
+            .then_with(|| compare_version_str(&b.version, &a.version))
+            .then_with(|| {
+                Reverse(config.rank_of(a.repo_name.as_deref().unwrap_or("")))
+                    .cmp(&Reverse(config.rank_of(b.repo_name.as_deref().unwrap_or(""))))
+            })
+    });
+}
+
+/// 获取指定应用在远程仓库中的所有可用版本
+///
+/// 调用 `ll-cli search --show-all-version`，为每个版本标注来源仓库、频道和架构，
+/// 并结合本地已安装列表标记是否已安装。结果按版本号从新到旧排序。
+pub async fn get_remote_app_versions(app_id: String) -> Result<Vec<RemoteAppVersion>, String> {
+    info!("[RemoteVersions] Searching all versions of: {}", app_id);
+
+    let query = app_id.clone();
+    let results = tokio::task::spawn_blocking(move || run_search(&query, None, true))
+        .await
+        .map_err(|e| format!("搜索任务执行失败: {}", e))??;
+
+    // 已安装列表获取失败不影响远程版本展示
+    let installed = match get_installed_apps(true).await {
+        Ok(apps) => apps,
+        Err(e) => {
+            warn!("[RemoteVersions] Failed to load installed apps: {}", e);
+            Vec::new()
+        }
+    };
+
+    let mut versions: Vec<RemoteAppVersion> = results
+        .into_iter()
+        // search 是模糊匹配，只保留 appId 完全一致的条目
+        .filter(|item| app_id_of(item) == app_id)
+        .map(|item| {
+            let arch = arch_list(&item.arch);
+            let module = item.module.clone().unwrap_or_else(|| "binary".to_string());
+            let installed = installed.iter().any(|app| {
+                let installed_module = if app.module.is_empty() { "binary" } else { app.module.as_str() };
+                app.app_id == app_id
+                    && app.version == item.version
+                    && installed_module == module
+                    && (arch.is_empty() || app.arch.is_empty() || arch.contains(&app.arch))
+            });
+            RemoteAppVersion {
+                app_id: app_id.clone(),
+                name: item.name,
+                version: item.version,
+                repo_name: item.repo_name.unwrap_or_default(),
+                channel: item.channel.unwrap_or_else(|| "main".to_string()),
+                arch,
+                module,
+                description: item.description,
+                installed,
+            }
+        })
+        .collect();
+
+    sort_remote_versions(&mut versions);
+
+    info!(
+        "[RemoteVersions] Found {} remote versions for: {}",
+        versions.len(),
+        app_id
+    );
+    Ok(versions)
+}
+
+/// 按版本号从新到旧排序，同版本按仓库、频道、模块排列
+fn sort_remote_versions(versions: &mut [RemoteAppVersion]) {
+    versions.sort_by(|a, b| {
+        compare_version_str(&b.version, &a.version)
+            .then_with(|| a.repo_name.cmp(&b.repo_name))
+            .then_with(|| a.channel.cmp(&b.channel))
+            .then_with(|| a.module.cmp(&b.module))
+    });
 }
 
 pub async fn get_ll_cli_version() -> Result<String, String> {
     crate::services::linglong_env::get_ll_cli_version().await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_output_keeps_repo() {
+        let raw = r#"{"stable":[{"id":"org.deepin.calculator","name":"calculator","version":"5.7.21.1","arch":["x86_64"],"channel":"main","module":"binary"}]}"#;
+        let results = parse_search_output(raw).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.as_deref(), Some("stable"));
+        assert_eq!(results[0].1.app_id.as_deref(), Some("org.deepin.calculator"));
+        assert_eq!(results[0].1.channel.as_deref(), Some("main"));
+        assert_eq!(arch_list(&results[0].1.arch), vec!["x86_64".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_search_output_array_fallback() {
+        let raw = r#"[{"appId":"org.deepin.calculator","name":"calculator","version":"5.7.21.1","arch":"arm64"}]"#;
+        let results = parse_search_output(raw).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].0.is_none());
+        assert_eq!(arch_list(&results[0].1.arch), vec!["arm64".to_string()]);
+        assert!(parse_search_output("  ").unwrap().is_empty());
+    }
+
+    fn search_item(id: &str, version: &str, module: &str, repo: &str) -> SearchResultItem {
+        SearchResultItem {
+            app_id: Some(id.to_string()),
+            name: id.rsplit('.').next().unwrap_or(id).to_string(),
+            version: version.to_string(),
+            arch: Some(serde_json::json!(["x86_64"])),
+            description: Some("a calculator app".to_string()),
+            module: Some(module.to_string()),
+            icon: None,
+            channel: Some("main".to_string()),
+            repo_name: Some(repo.to_string()),
+        }
+    }
+
+    fn test_repo_config() -> LinglongRepoConfig {
+        serde_json::from_str(
+            r#"{"defaultRepo":"stable","repos":[
+                {"name":"stable","url":"https://a","priority":"0"},
+                {"name":"mirror","url":"https://b","priority":"10"}]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dedup_prefers_higher_priority_repo() {
+        let config = test_repo_config();
+        let items = vec![
+            search_item("org.deepin.calculator", "5.7.21.1", "binary", "stable"),
+            search_item("org.deepin.calculator", "5.7.21.1", "binary", "mirror"),
+            search_item("org.deepin.calculator", "5.7.21.1", "develop", "stable"),
+        ];
+        let kept = dedup_by_repo_priority(items, &config);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].repo_name.as_deref(), Some("mirror"));
+        assert_eq!(kept[1].module.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn test_rank_search_results() {
+        let config = test_repo_config();
+        let mut items = vec![
+            search_item("com.other.tool", "1.0.0.0", "binary", "stable"),
+            search_item("org.deepin.calculator.plugin", "1.0.0.0", "binary", "stable"),
+            search_item("org.deepin.calculator", "5.7.21.1", "develop", "stable"),
+            search_item("org.deepin.calculator", "5.7.21.1", "binary", "stable"),
+        ];
+        items[0].description = Some("works with org.deepin.calculator".to_string());
+        rank_search_results("org.deepin.calculator", &mut items, &config);
+        let order: Vec<(&str, &str)> = items
+            .iter()
+            .map(|i| (app_id_of(i), module_of(i)))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("org.deepin.calculator", "binary"),
+                ("org.deepin.calculator", "develop"),
+                ("org.deepin.calculator.plugin", "binary"),
+                ("com.other.tool", "binary"),
+            ]
+        );
+        assert_eq!(match_rank("calculator", &items[0]), 2);
+        assert_eq!(match_rank("calc", &items[0]), 3);
+    }
+
+    #[test]
+    fn test_sort_remote_versions() {
+        let make = |version: &str, repo: &str| RemoteAppVersion {
+            app_id: "org.test.app".to_string(),
+            name: "app".to_string(),
+            version: version.to_string(),
+            repo_name: repo.to_string(),
+            channel: "main".to_string(),
+            arch: vec!["x86_64".to_string()],
+            module: "binary".to_string(),
+            description: None,
+            installed: false,
+        };
+        let mut versions = vec![
+            make("1.2.0.0", "stable"),
+            make("1.10.0.0", "stable"),
+            make("1.2.0.0", "mirror"),
+            make("1.9.3.1", "stable"),
+        ];
+        sort_remote_versions(&mut versions);
+        let order: Vec<(&str, &str)> = versions
+            .iter()
+            .map(|v| (v.version.as_str(), v.repo_name.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("1.10.0.0", "stable"),
+                ("1.9.3.1", "stable"),
+                ("1.2.0.0", "mirror"),
+                ("1.2.0.0", "stable"),
+            ]
+        );
+    }
+}
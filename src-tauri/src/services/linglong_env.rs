@@ -1,21 +1,65 @@
-use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::fs;
-use std::io::Write;
-use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
 use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::services::distro::{parse_os_release, query_linglong_packages, DistroFamily, LinglongPackageReport};
 use crate::services::ll_cli_command;
+use crate::services::repo::{read_repo_config, LinglongRepo, LinglongRepoConfig};
+use crate::services::version::{compare_version_str, LinglongVersion};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+/// 环境检查项的严重程度
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EnvCheckSeverity {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// 单个环境检查项
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct LinglongRepo {
-    pub name: String,
-    pub url: String,
-    pub alias: Option<String>,
-    pub priority: Option<String>,
+pub struct EnvCheckItem {
+    /// 检查项 ID（如 ll-cli-installed）
+    pub id: String,
+    pub severity: EnvCheckSeverity,
+    /// 实际检测到的值
+    pub observed: Option<String>,
+    /// 期望的值
+    pub expected: Option<String>,
+    /// 修复建议，未通过时提供
+    pub remediation: Option<String>,
+}
+
+impl EnvCheckItem {
+    fn new(id: &str, severity: EnvCheckSeverity) -> Self {
+        Self {
+            id: id.to_string(),
+            severity,
+            observed: None,
+            expected: None,
+            remediation: None,
+        }
+    }
+
+    fn severity(mut self, value: EnvCheckSeverity) -> Self {
+        self.severity = value;
+        self
+    }
+
+    fn observed(mut self, value: impl Into<String>) -> Self {
+        self.observed = Some(value.into());
+        self
+    }
+
+    fn expected(mut self, value: impl Into<String>) -> Self {
+        self.expected = Some(value.into());
+        self
+    }
+
+    fn remediation(mut self, value: impl Into<String>) -> Self {
+        self.remediation = Some(value.into());
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -33,83 +77,15 @@ pub struct LinglongEnvCheckResult {
     pub repo_name: Option<String>,
     pub repos: Vec<LinglongRepo>,
     pub is_container: bool,
+    /// 玲珑相关软件包的版本信息
+    pub packages: Option<LinglongPackageReport>,
+    /// 所有检查项，ok 与 reason 由此推导
+    pub checks: Vec<EnvCheckItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct InstallLinglongResult {
-    pub stdout: String,
-    pub stderr: String,
-}
-
-fn parse_repo_output(output: &str) -> LinglongEnvCheckResult {
-    let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
-    if lines.is_empty() {
-        return LinglongEnvCheckResult {
-            ok: false,
-            reason: Some("未检测到仓库信息".to_string()),
-            ..Default::default()
-        };
-    }
-
-    let default_repo = lines
-        .get(0)
-        .and_then(|l| l.split(':').nth(1))
-        .map(|s| s.trim().to_string());
-    let repo_lines = if lines.len() > 2 { &lines[2..] } else { &[] };
-    let mut repos = Vec::new();
-    for line in repo_lines {
-        let parts: Vec<&str> = line.trim().split_whitespace().collect();
-        if parts.is_empty() {
-            continue;
-        }
-        let name = parts.get(0).unwrap_or(&"").to_string();
-        let url = parts.get(1).unwrap_or(&"").to_string();
-        let alias = parts.get(2).map(|s| s.to_string());
-        let priority = parts.get(3).map(|s| s.to_string());
-        repos.push(LinglongRepo {
-            name,
-            url,
-            alias,
-            priority,
-        });
-    }
-
-    LinglongEnvCheckResult {
-        ok: repos.len() > 0,
-        reason: None,
-        repo_name: default_repo,
-        repos,
-        ..Default::default()
-    }
-}
-
+/// 从 `ll-cli --json --version` 的输出中提取版本号，兼容纯文本输出及带修订号的发行版本
 fn parse_ll_version(raw: &str) -> Option<String> {
-    if raw.trim().is_empty() {
-        return None;
-    }
-    if let Ok(json) = serde_json::from_str::<HashMap<String, String>>(raw) {
-        if let Some(v) = json.get("version") {
-            return Some(v.trim().to_string());
-        }
-    }
-    let cleaned = raw.trim();
-    let mut version = None;
-    // 提取连续的版本号片段（仅数字和 .）
-    if let Some(seg) = cleaned
-        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
-        .find(|seg| seg.contains('.'))
-    {
-        if !seg.is_empty() {
-            version = Some(seg.to_string());
-        }
-    }
-
-    if version.is_none() {
-        None
-    } else {
-        version
-    }
+    LinglongVersion::extract(raw).map(|version| version.to_string())
 }
 
 fn get_ll_cli_version_inner() -> Result<String, String> {
@@ -157,26 +133,6 @@ fn parse_glibc_version(raw: &str) -> Option<String> {
     Some(first_line.to_string())
 }
 
-fn compare_versions(v1: &str, v2: &str) -> std::cmp::Ordering {
-    let to_parts = |v: &str| -> Vec<i32> {
-        v.split(|c| c == '.' || c == '-' || c == '_')
-            .filter_map(|p| p.parse::<i32>().ok())
-            .collect()
-    };
-    let a = to_parts(v1);
-    let b = to_parts(v2);
-    let len = a.len().max(b.len());
-    for i in 0..len {
-        let av = *a.get(i).unwrap_or(&0);
-        let bv = *b.get(i).unwrap_or(&0);
-        match av.cmp(&bv) {
-            std::cmp::Ordering::Equal => continue,
-            ord => return ord,
-        }
-    }
-    std::cmp::Ordering::Equal
-}
-
 pub async fn check_linglong_env(min_version: &str) -> Result<LinglongEnvCheckResult, String> {
     let mut result = LinglongEnvCheckResult::default();
 
@@ -190,13 +146,7 @@ pub async fn check_linglong_env(min_version: &str) -> Result<LinglongEnvCheckRes
     // 获取 OS 信息
     let os_release = fs::read_to_string("/etc/os-release")
         .ok()
-        .and_then(|content| {
-            content
-                .lines()
-                .find(|line| line.starts_with("PRETTY_NAME"))
-                .and_then(|line| line.split('=').nth(1))
-                .map(|v| v.trim_matches('"').to_string())
-        });
+        .and_then(|content| parse_os_release(&content).pretty_name);
     if let Some(name) = os_release {
         result.os_version = Some(name);
     } else if let Ok(output) = Command::new("uname").arg("-a").output() {
@@ -239,123 +189,30 @@ pub async fn check_linglong_env(min_version: &str) -> Result<LinglongEnvCheckRes
         result.os_version = Some(kernel_info);
     }
 
-    // dpkg 信息（仅作为日志展示）
-    if let Ok(output) = Command::new("bash")
-        .arg("-c")
-        .arg("dpkg -l | grep linglong")
-        .output()
-    {
-        if output.status.success() {
-            result.detail_msg = Some(String::from_utf8_lossy(&output.stdout).to_string());
-        }
-    }
+    // 通过发行版对应的包管理器查询玲珑软件包（摘要仅作为日志展示）
+    let package_report = query_linglong_packages();
+    result.detail_msg = Some(package_report.summary());
+    result.ll_bin_version = package_report.ll_bin_version();
 
     // 检查 ll-cli 是否存在
-    let exists = ll_cli_command().arg("--help").output();
-    if exists.is_err() {
-        result.ok = false;
-        result.reason = Some("检测到系统未安装玲珑环境，请先安装".to_string());
-        return Ok(result);
-    }
-    let exists_output = exists.unwrap();
-    if !exists_output.status.success() {
-        result.ok = false;
-        result.reason = Some("检测到系统未安装玲珑环境，请先安装".to_string());
-        return Ok(result);
-    }
+    let ll_cli_installed = ll_cli_command()
+        .arg("--help")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
 
     // 仓库信息
-    let repo_output = ll_cli_command()
-        .arg("--json")
-        .arg("repo")
-        .arg("show")
-        .output();
-    let mut repo_info = LinglongEnvCheckResult::default();
-    if let Ok(output) = repo_output {
-        if output.status.success() {
-            let clean = String::from_utf8_lossy(&output.stdout);
-            if let Ok(json) = serde_json::from_str::<HashMap<String, serde_json::Value>>(&clean) {
-                let repo_name = json
-                    .get("defaultRepo")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-                let repos = json
-                    .get("repos")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|item| {
-                                Some(LinglongRepo {
-                                    name: item.get("name")?.as_str().unwrap_or("").to_string(),
-                                    url: item.get("url")?.as_str().unwrap_or("").to_string(),
-                                    alias: item.get("alias").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                    priority: item.get("priority").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                })
-                            })
-                            .collect::<Vec<_>>()
-                    })
-                    .unwrap_or_default();
-                repo_info.repo_name = repo_name;
-                repo_info.repos = repos;
-            } else {
-                repo_info = parse_repo_output(&clean);
-            }
-        } else {
-            // 尝试旧命令
-            let fallback = ll_cli_command().arg("repo").arg("show").output();
-            if let Ok(out) = fallback {
-                if out.status.success() {
-                    let clean = String::from_utf8_lossy(&out.stdout);
-                    repo_info = parse_repo_output(&clean);
-                }
-            }
-        }
-    }
-    if repo_info.repos.is_empty() {
-        result.ok = false;
-        result.reason = Some("未检测到玲珑仓库配置，请检查环境".to_string());
-        return Ok(result);
-    }
-    result.repo_name = repo_info.repo_name.clone();
+    let repo_info = if ll_cli_installed {
+        read_repo_config().unwrap_or_default()
+    } else {
+        LinglongRepoConfig::default()
+    };
+    result.repo_name = repo_info.default_repo.clone();
     result.repos = repo_info.repos.clone();
 
     // 获取 ll-cli 版本
-    let version = get_ll_cli_version_inner().ok();
-    result.ll_version = version.clone();
-
-    // 获取 linglong-bin 版本（APT 系）
-    if let Ok(output) = Command::new("apt-cache")
-        .arg("policy")
-        .arg("linglong-bin")
-        .output()
-    {
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                if line.contains("Installed:") || line.contains("已安装：") {
-                    let parts: Vec<&str> = line.split(':').collect();
-                    if let Some(v) = parts.get(1) {
-                        result.ll_bin_version = Some(v.trim().to_string());
-                    }
-                }
-            }
-        }
-    }
-
-    // 版本校验
-    if let Some(ref v) = result.ll_version {
-        if compare_versions(v, min_version) == std::cmp::Ordering::Less {
-            result.ok = false;
-            result.reason = Some(format!(
-                "当前玲珑基础环境版本({})过低，需升级至 >= {}",
-                v, min_version
-            ));
-            return Ok(result);
-        }
-    } else {
-        result.ok = false;
-        result.reason = Some("无法检测到玲珑环境版本，请确认已安装".to_string());
-        return Ok(result);
+    if ll_cli_installed {
+        result.ll_version = get_ll_cli_version_inner().ok();
     }
 
     // 检测容器环境变量
@@ -363,64 +220,157 @@ pub async fn check_linglong_env(min_version: &str) -> Result<LinglongEnvCheckRes
         .map(|v| v == "yes")
         .unwrap_or(false);
 
-    result.ok = true;
+    let family = package_report.family;
+    result.packages = Some(package_report);
+    result.checks = vec![
+        check_ll_cli_installed(ll_cli_installed),
+        check_repo_configured(ll_cli_installed, &repo_info),
+        check_ll_version(result.ll_version.as_deref(), min_version),
+        check_ll_bin_package(result.ll_bin_version.as_deref(), family),
+        check_arch(result.arch.as_deref()),
+    ];
+    let (ok, reason) = summarize_checks(&result.checks);
+    result.ok = ok;
+    result.reason = reason;
     Ok(result)
 }
 
-pub async fn install_linglong_env(script_content: String) -> Result<InstallLinglongResult, String> {
-    if script_content.trim().is_empty() {
-        return Err("安装脚本内容为空".to_string());
+fn check_ll_cli_installed(installed: bool) -> EnvCheckItem {
+    let item = EnvCheckItem::new(
+        "ll-cli-installed",
+        if installed { EnvCheckSeverity::Pass } else { EnvCheckSeverity::Fail },
+    )
+    .observed(if installed { "已安装" } else { "未找到 ll-cli" })
+    .expected("已安装 ll-cli");
+    if installed {
+        item
+    } else {
+        item.remediation("检测到系统未安装玲珑环境，请先安装")
     }
-    let script = script_content.clone();
-    let handle = tokio::task::spawn_blocking(move || -> Result<InstallLinglongResult, String> {
-        let file_name = format!(
-            "install-linglong-{}.sh",
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .map_err(|e| format!("获取时间失败: {}", e))?
-                .as_millis()
-        );
-        let mut path = PathBuf::from(std::env::temp_dir());
-        path.push(file_name);
-
-        {
-            let mut file = fs::File::create(&path)
-                .map_err(|e| format!("创建安装脚本失败: {}", e))?;
-            file.write_all(script.as_bytes())
-                .map_err(|e| format!("写入安装脚本失败: {}", e))?;
-        }
-        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
-            .map_err(|e| format!("设置脚本权限失败: {}", e))?;
-
-        info!("[install_linglong_env] executing script at {:?}", path);
-        let output = Command::new("pkexec")
-            .arg("bash")
-            .arg(&path)
-            .output()
-            .map_err(|e| format!("执行安装脚本失败: {}", e))?;
-
-        if !output.status.success() {
-            warn!(
-                "[install_linglong_env] script failed with code {:?}",
-                output.status.code()
-            );
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            return Err(format!(
-                "安装失败(code {:?}): {}",
-                output.status.code(),
-                stderr
-            ));
-        }
+}
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+fn check_repo_configured(ll_cli_installed: bool, config: &LinglongRepoConfig) -> EnvCheckItem {
+    let expected = "至少配置一个玲珑仓库";
+    if !config.repos.is_empty() {
+        let names: Vec<&str> = config.repos.iter().map(|r| r.name.as_str()).collect();
+        return EnvCheckItem::new("repo-configured", EnvCheckSeverity::Pass)
+            .observed(names.join(", "))
+            .expected(expected);
+    }
+    EnvCheckItem::new("repo-configured", EnvCheckSeverity::Fail)
+        .observed(if ll_cli_installed { "未配置仓库" } else { "ll-cli 不可用，无法读取仓库" })
+        .expected(expected)
+        .remediation("未检测到玲珑仓库配置，请检查环境")
+}
 
-        // 清理脚本文件
-        let _ = fs::remove_file(&path);
-        Ok(InstallLinglongResult { stdout, stderr })
-    });
+fn check_ll_version(version: Option<&str>, min_version: &str) -> EnvCheckItem {
+    let item = EnvCheckItem::new("ll-version", EnvCheckSeverity::Pass).expected(format!(">= {}", min_version));
+    match version {
+        Some(v) if compare_version_str(v, min_version) == std::cmp::Ordering::Less => item
+            .severity(EnvCheckSeverity::Fail)
+            .observed(v)
+            .remediation(format!(
+                "当前玲珑基础环境版本({})过低，需升级至 >= {}",
+                v, min_version
+            )),
+        Some(v) => item.observed(v),
+        None => item
+            .severity(EnvCheckSeverity::Fail)
+            .observed("未检测到")
+            .remediation("无法检测到玲珑环境版本，请确认已安装"),
+    }
+}
+
+fn check_ll_bin_package(version: Option<&str>, family: DistroFamily) -> EnvCheckItem {
+    let item = EnvCheckItem::new("ll-bin-package", EnvCheckSeverity::Pass)
+        .expected(format!("已通过 {} 安装 linglong-bin", family.package_manager()));
+    match version.filter(|v| !v.is_empty() && *v != "(none)") {
+        Some(v) => item.observed(v),
+        None if family == DistroFamily::Unknown => item
+            .severity(EnvCheckSeverity::Warn)
+            .observed("未识别的发行版，无法查询软件包")
+            .remediation("无法通过系统包管理器检测 linglong-bin，如已通过其他方式安装可忽略"),
+        None => item
+            .severity(EnvCheckSeverity::Warn)
+            .observed("未检测到")
+            .remediation("未通过系统包管理器检测到 linglong-bin，如使用其他方式安装可忽略"),
+    }
+}
 
-    handle
-        .await
-        .map_err(|e| format!("安装任务执行失败: {}", e))?
+fn check_arch(arch: Option<&str>) -> EnvCheckItem {
+    const SUPPORTED_ARCHS: [&str; 3] = ["x86_64", "aarch64", "loongarch64"];
+    let item = EnvCheckItem::new("arch", EnvCheckSeverity::Pass).expected(SUPPORTED_ARCHS.join(" / "));
+    match arch {
+        Some(a) if SUPPORTED_ARCHS.contains(&a) => item.observed(a),
+        other => item
+            .severity(EnvCheckSeverity::Warn)
+            .observed(other.unwrap_or("未知"))
+            .remediation("当前架构可用的玲珑应用较少，部分应用可能无法安装"),
+    }
+}
+
+/// 根据检查项推导整体结果：无失败项即通过，reason 取第一个失败项的修复建议
+fn summarize_checks(checks: &[EnvCheckItem]) -> (bool, Option<String>) {
+    let first_fail = checks
+        .iter()
+        .find(|item| item.severity == EnvCheckSeverity::Fail);
+    match first_fail {
+        Some(item) => (
+            false,
+            Some(item.remediation.clone().unwrap_or_else(|| format!("环境检查未通过: {}", item.id))),
+        ),
+        None => (true, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ll_version() {
+        assert_eq!(parse_ll_version("{\"version\":\"1.9.9\"}").as_deref(), Some("1.9.9"));
+        assert_eq!(parse_ll_version("linyaps CLI version 1.10.2-1deepin1\n").as_deref(), Some("1.10.2-1deepin1"));
+        assert_eq!(parse_ll_version("  "), None);
+    }
+
+    #[test]
+    fn test_check_ll_version() {
+        assert_eq!(check_ll_version(Some("1.9.9"), "1.9.0").severity, EnvCheckSeverity::Pass);
+        assert_eq!(check_ll_version(Some("1.10.0"), "1.9.0").severity, EnvCheckSeverity::Pass);
+        let low = check_ll_version(Some("1.8.3"), "1.9.0");
+        assert_eq!(low.severity, EnvCheckSeverity::Fail);
+        assert_eq!(low.observed.as_deref(), Some("1.8.3"));
+        assert_eq!(low.expected.as_deref(), Some(">= 1.9.0"));
+        assert!(low.remediation.unwrap().contains("过低"));
+        assert_eq!(check_ll_version(None, "1.9.0").severity, EnvCheckSeverity::Fail);
+    }
+
+    #[test]
+    fn test_summarize_checks_reports_all_failures() {
+        let checks = vec![
+            check_ll_cli_installed(true),
+            check_repo_configured(true, &LinglongRepoConfig::default()),
+            check_ll_version(Some("1.8.3"), "1.9.0"),
+            check_ll_bin_package(None, DistroFamily::Debian),
+            check_arch(Some("x86_64")),
+        ];
+        let failures: Vec<&str> = checks
+            .iter()
+            .filter(|c| c.severity == EnvCheckSeverity::Fail)
+            .map(|c| c.id.as_str())
+            .collect();
+        assert_eq!(failures, vec!["repo-configured", "ll-version"]);
+        assert_eq!(checks[3].severity, EnvCheckSeverity::Warn);
+
+        let (ok, reason) = summarize_checks(&checks);
+        assert!(!ok);
+        assert_eq!(reason.as_deref(), Some("未检测到玲珑仓库配置，请检查环境"));
+    }
+
+    #[test]
+    fn test_summarize_checks_warn_only_is_ok() {
+        let checks = vec![check_ll_cli_installed(true), check_arch(Some("riscv64"))];
+        assert_eq!(summarize_checks(&checks), (true, None));
+    }
 }
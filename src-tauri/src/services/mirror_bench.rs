@@ -0,0 +1,375 @@
+//! 镜像测速
+//!
+//! 从每个候选仓库下载一个已知的小文件（仓库 summary，限制最大字节数），
+//! 计算吞吐量并按速度排序。每次测速结果追加记录到数据目录，便于长期对比；
+//! 用户确认后可按测速结果调整仓库优先级或切换默认仓库。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::services::repo::{read_repo_config, run_privileged_repo_commands, LinglongRepo, LinglongRepoConfig};
+use crate::utils::jsonl;
+use crate::utils::paths::app_data_file;
+use crate::utils::time::now_millis;
+
+/// 单个镜像的最大下载字节数
+const BENCH_MAX_BYTES: u64 = 2 * 1024 * 1024;
+/// 单个镜像的测速超时
+const BENCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// 测速历史记录文件
+const HISTORY_FILE: &str = "mirror-benchmark.jsonl";
+/// 最多保留的历史记录条数
+const HISTORY_LIMIT: usize = 200;
+
+/// 单个镜像的测速结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorBenchmarkEntry {
+    pub name: String,
+    pub url: String,
+    /// 实际下载字节数
+    pub bytes: u64,
+    /// 下载耗时（毫秒）
+    pub elapsed_ms: u64,
+    /// 首字节耗时（毫秒）
+    pub ttfb_ms: Option<u64>,
+    /// 吞吐量（字节/秒）
+    pub throughput_bps: u64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// 一次测速的完整结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorBenchmarkRun {
+    /// 测速时间（Unix 毫秒）
+    pub started_at: u64,
+    /// 按吞吐量从高到低排序的结果
+    pub entries: Vec<MirrorBenchmarkEntry>,
+    /// 最快的仓库名称
+    pub fastest: Option<String>,
+}
+
+fn throughput(bytes: u64, elapsed: Duration) -> u64 {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return 0;
+    }
+    (bytes as f64 / secs) as u64
+}
+
+/// 测速对象地址：<url>/repos/<name>/summary
+fn benchmark_object_url(repo: &LinglongRepo) -> String {
+    format!("{}/repos/{}/summary", repo.url.trim_end_matches('/'), repo.name)
+}
+
+/// 对单个镜像测速
+pub async fn benchmark_mirror(repo: &LinglongRepo) -> MirrorBenchmarkEntry {
+    let mut entry = MirrorBenchmarkEntry {
+        name: repo.name.clone(),
+        url: repo.url.clone(),
+        bytes: 0,
+        elapsed_ms: 0,
+        ttfb_ms: None,
+        throughput_bps: 0,
+        success: false,
+        error: None,
+    };
+
+    if !(repo.url.starts_with("http://") || repo.url.starts_with("https://")) {
+        entry.error = Some("仅支持对 http(s) 仓库测速".to_string());
+        return entry;
+    }
+
+    let client = match reqwest::Client::builder().timeout(BENCH_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            entry.error = Some(format!("创建 HTTP 客户端失败: {}", e));
+            return entry;
+        }
+    };
+
+    let start = Instant::now();
+    let mut resp = match client.get(benchmark_object_url(repo)).send().await {
+        Ok(resp) if resp.status().is_success() => resp,
+        Ok(resp) => {
+            entry.error = Some(format!("测速文件返回状态码 {}", resp.status().as_u16()));
+            return entry;
+        }
+        Err(e) => {
+            entry.error = Some(format!("请求失败: {}", e));
+            return entry;
+        }
+    };
+    entry.ttfb_ms = Some(start.elapsed().as_millis() as u64);
+
+    loop {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => {
+                entry.bytes += chunk.len() as u64;
+                if entry.bytes >= BENCH_MAX_BYTES {
+                    break;
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                // 已下载部分仍可用于估算速度
+                if entry.bytes == 0 {
+                    entry.error = Some(format!("下载失败: {}", e));
+                    return entry;
+                }
+                warn!("[MirrorBench] {} interrupted after {} bytes: {}", repo.name, entry.bytes, e);
+                break;
+            }
+        }
+    }
+
+    let elapsed = start.elapsed();
+    entry.elapsed_ms = elapsed.as_millis() as u64;
+    entry.throughput_bps = throughput(entry.bytes, elapsed);
+    entry.success = entry.bytes > 0;
+    if !entry.success {
+        entry.error = Some("测速文件为空".to_string());
+    }
+    entry
+}
+
+/// 依次对给定镜像测速并按吞吐量排序
+///
+/// 逐个测速而非并发，避免多个下载互相抢占带宽影响结果。
+pub async fn benchmark_mirrors(repos: &[LinglongRepo]) -> MirrorBenchmarkRun {
+    let started_at = now_millis();
+    let mut entries = Vec::with_capacity(repos.len());
+    for repo in repos {
+        let entry = benchmark_mirror(repo).await;
+        info!(
+            "[MirrorBench] {} -> {} bytes in {}ms ({} B/s), error: {:?}",
+            entry.name, entry.bytes, entry.elapsed_ms, entry.throughput_bps, entry.error
+        );
+        entries.push(entry);
+    }
+
+    entries.sort_by(|a, b| {
+        b.success
+            .cmp(&a.success)
+            .then_with(|| b.throughput_bps.cmp(&a.throughput_bps))
+    });
+    let fastest = entries
+        .first()
+        .filter(|e| e.success)
+        .map(|e| e.name.clone());
+
+    MirrorBenchmarkRun {
+        started_at,
+        entries,
+        fastest,
+    }
+}
+
+/// 对已配置的仓库测速并记录结果
+///
+/// # Arguments
+/// * `repos` - 可选，只对指定名称的仓库测速
+pub async fn run_mirror_benchmark(repos: Option<Vec<String>>) -> Result<MirrorBenchmarkRun, String> {
+    let config = read_repo_config()?;
+    let candidates: Vec<LinglongRepo> = match repos {
+        Some(names) if !names.is_empty() => config
+            .repos
+            .into_iter()
+            .filter(|r| names.iter().any(|n| r.matches(n)))
+            .collect(),
+        _ => config.repos,
+    };
+    if candidates.is_empty() {
+        return Err("没有可测速的仓库".to_string());
+    }
+
+    let run = benchmark_mirrors(&candidates).await;
+    match app_data_file(HISTORY_FILE) {
+        Ok(path) => {
+            if let Err(e) = jsonl::append(&path, &run, HISTORY_LIMIT) {
+                warn!("[MirrorBench] {}", e);
+            }
+        }
+        Err(e) => warn!("[MirrorBench] {}", e),
+    }
+    Ok(run)
+}
+
+/// 获取测速历史记录（从旧到新）
+pub async fn get_mirror_benchmark_history() -> Result<Vec<MirrorBenchmarkRun>, String> {
+    jsonl::read(&app_data_file(HISTORY_FILE)?)
+}
+
+/// 根据确认后的顺序计算各仓库的新优先级
+///
+/// 排在前面的仓库优先级更高；未参与排序的仓库保持原优先级，
+/// 参与排序的仓库整体排在它们之前。
+fn plan_priorities(config: &LinglongRepoConfig, order: &[String]) -> Result<Vec<(String, i64)>, String> {
+    let mut names = Vec::with_capacity(order.len());
+    for name in order {
+        let repo = config
+            .find(name)
+            .ok_or_else(|| format!("未找到仓库: {}", name))?;
+        if names.contains(&repo.name) {
+            return Err(format!("仓库重复: {}", name));
+        }
+        names.push(repo.name.clone());
+    }
+
+    let base = config
+        .repos
+        .iter()
+        .filter(|r| !names.contains(&r.name))
+        .map(|r| r.priority_value() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(0);
+    let count = names.len() as i64;
+
+    Ok(names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (name, base + count - 1 - i as i64))
+        .collect())
+}
+
+/// 按用户确认的测速排序调整仓库优先级，可选地将第一个设为默认仓库
+///
+/// # Arguments
+/// * `order` - 从快到慢的仓库名称列表
+/// * `set_default` - 是否将最快的仓库设为默认仓库
+pub async fn apply_mirror_ranking(order: Vec<String>, set_default: bool) -> Result<LinglongRepoConfig, String> {
+    let config = read_repo_config()?;
+    let plan = plan_priorities(&config, &order)?;
+
+    // 所有修改合并为一次授权执行，只修改优先级实际变化的仓库
+    let mut commands: Vec<Vec<String>> = Vec::new();
+    for (name, priority) in &plan {
+        let unchanged = config
+            .find(name)
+            .is_some_and(|r| r.priority.is_some() && r.priority_value() == *priority);
+        if unchanged {
+            continue;
+        }
+        info!("[MirrorBench] Set priority of {} to {}", name, priority);
+        commands.push(vec!["set-priority".to_string(), name.clone(), priority.to_string()]);
+    }
+
+    if set_default {
+        if let Some((fastest, _)) = plan.first() {
+            let is_default = config
+                .default_repo
+                .as_deref()
+                .and_then(|d| config.find(d))
+                .is_some_and(|r| &r.name == fastest);
+            if !is_default {
+                info!("[MirrorBench] Set default repo to {}", fastest);
+                commands.push(vec!["set-default".to_string(), fastest.clone()]);
+            }
+        }
+    }
+
+    run_privileged_repo_commands(&commands)?;
+    read_repo_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    async fn spawn_summary_server(body_len: usize) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let _ = socket.read(&mut buf).await;
+                    let header = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body_len
+                    );
+                    let _ = socket.write_all(header.as_bytes()).await;
+                    let _ = socket.write_all(&vec![b'x'; body_len]).await;
+                });
+            }
+        });
+        addr
+    }
+
+    fn repo(name: &str, url: &str, priority: Option<&str>) -> LinglongRepo {
+        LinglongRepo {
+            name: name.to_string(),
+            url: url.to_string(),
+            alias: None,
+            priority: priority.map(|p| p.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_mirrors() {
+        let addr = spawn_summary_server(64 * 1024).await;
+        let run = benchmark_mirrors(&[
+            repo("bad", "ftp://example.com", None),
+            repo("stable", &format!("http://{}", addr), None),
+        ])
+        .await;
+        assert_eq!(run.fastest.as_deref(), Some("stable"));
+        assert_eq!(run.entries[0].bytes, 64 * 1024);
+        assert!(run.entries[0].success);
+        assert!(!run.entries[1].success);
+    }
+
+    #[test]
+    fn test_plan_priorities() {
+        let config = LinglongRepoConfig {
+            default_repo: Some("stable".to_string()),
+            repos: vec![
+                repo("stable", "https://a", Some("0")),
+                repo("mirror", "https://b", Some("0")),
+                repo("local", "file:///opt/repo", Some("5")),
+            ],
+        };
+        let plan = plan_priorities(&config, &["mirror".to_string(), "stable".to_string()]).unwrap();
+        assert_eq!(plan, vec![("mirror".to_string(), 7), ("stable".to_string(), 6)]);
+
+        let all = plan_priorities(
+            &config,
+            &["local".to_string(), "mirror".to_string(), "stable".to_string()],
+        )
+        .unwrap();
+        assert_eq!(all[0], ("local".to_string(), 2));
+        assert_eq!(all[2], ("stable".to_string(), 0));
+
+        assert!(plan_priorities(&config, &["unknown".to_string()]).is_err());
+        assert!(plan_priorities(&config, &["stable".to_string(), "stable".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_history_roundtrip() {
+        let path = std::env::temp_dir().join(format!("mirror-bench-test-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let run = MirrorBenchmarkRun {
+            started_at: 1,
+            entries: Vec::new(),
+            fastest: None,
+        };
+        jsonl::append(&path, &run, HISTORY_LIMIT).unwrap();
+        jsonl::append(&path, &run, HISTORY_LIMIT).unwrap();
+        assert_eq!(jsonl::read::<MirrorBenchmarkRun>(&path).unwrap().len(), 2);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_throughput() {
+        assert_eq!(throughput(1000, Duration::from_millis(500)), 2000);
+        assert_eq!(throughput(1000, Duration::ZERO), 0);
+    }
+}
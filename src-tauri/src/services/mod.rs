@@ -1,15 +1,35 @@
 use std::process::Command;
 
+pub mod app_info;
+pub mod app_watcher;
+pub mod auto_update;
+pub mod autostart;
+pub mod dependencies;
+pub mod desktop_entry;
+pub mod disk_usage;
+pub mod distro;
+pub mod env_install;
+pub mod history;
 pub mod network;
 pub mod process;
 pub mod install;
+pub mod launch_log;
 pub mod linglong;
 pub mod linglong_env;
+pub mod mirror_bench;
 pub mod prune;
+pub mod repo;
+pub mod repo_health;
+pub mod resource_monitor;
+pub mod terminal;
+pub mod update_scheduler;
+pub mod updates;
+pub mod version;
 
 // 重新导出 install 模块的公共 API，保持向后兼容
 pub use install::{
     InstalledApp,
+    RunAppOptions,
     install_linglong_app,
     cancel_linglong_install,
     get_installed_apps,
@@ -25,7 +45,7 @@ const ENGLISH_LOCALE_ENV: [(&str, &str); 4] = [
     ("LC_MESSAGES", "C.UTF-8"),
 ];
 
-fn apply_english_locale_env_to_command(cmd: &mut Command) {
+pub(crate) fn apply_english_locale_env_to_command(cmd: &mut Command) {
     for (key, value) in ENGLISH_LOCALE_ENV {
         cmd.env(key, value);
     }
@@ -37,3 +57,28 @@ pub fn ll_cli_command() -> Command {
     apply_english_locale_env_to_command(&mut cmd);
     cmd
 }
+
+/// pkexec clears the environment, so the English locale is passed through `env`.
+fn privileged_env_command() -> Command {
+    let mut cmd = Command::new("pkexec");
+    cmd.arg("env");
+    for (key, value) in ENGLISH_LOCALE_ENV {
+        cmd.arg(format!("{}={}", key, value));
+    }
+    cmd
+}
+
+/// Create an ll-cli Command that runs as root through pkexec.
+pub fn privileged_ll_cli_command() -> Command {
+    let mut cmd = privileged_env_command();
+    cmd.arg("ll-cli");
+    cmd
+}
+
+/// Create a `sh -c <script>` Command that runs as root through pkexec,
+/// so several privileged steps need a single authorization.
+pub fn privileged_shell_command(script: &str) -> Command {
+    let mut cmd = privileged_env_command();
+    cmd.arg("sh").arg("-c").arg(script);
+    cmd
+}
@@ -1,7 +1,10 @@
+use crate::services::app_info::{get_app_infos, AppInfo, AppRef};
+use crate::services::app_watcher::wake_app_watcher;
 use crate::services::ll_cli_command;
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use tokio::task::JoinSet;
 use tokio::time::sleep;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,18 +16,75 @@ pub struct LinglongAppInfo {
     pub source: String,
     pub pid: String,
     pub container_id: String,
+    /// 未能获取 ll-cli info 时为 true，此时部分字段来自 ll-cli ps 的应用引用
+    #[serde(default)]
+    pub partial: bool,
 }
 
+/// `ll-cli ps` 输出中的一个容器
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PsEntry {
+    /// 应用引用，可能是 appId 或 `channel:appId/version/arch`
+    pub app: String,
+    pub container_id: String,
+    pub pid: String,
+}
+
+/// ll-cli 的 CliContainer：`{"id": 容器 ID, "package": 应用引用, "pid": 进程号}`
 #[derive(Debug, Deserialize)]
-struct AppInfoJson {
-    arch: Vec<String>,
-    channel: String,
-    id: String,
-    version: String,
-    base: String,
+struct PsEntryJson {
+    #[serde(alias = "appId", alias = "package")]
+    app: String,
+    #[serde(default, alias = "id", alias = "containerId", alias = "container_id")]
+    container_id: String,
+    #[serde(default)]
+    pid: serde_json::Value,
 }
 
-pub async fn get_running_linglong_apps() -> Result<Vec<LinglongAppInfo>, String> {
+/// 解析 `ll-cli --json ps` 的输出，格式不符时返回 None
+fn parse_ps_json(raw: &str) -> Option<Vec<PsEntry>> {
+    let items: Vec<PsEntryJson> = serde_json::from_str(raw.trim()).ok()?;
+    Some(
+        items
+            .into_iter()
+            .map(|item| PsEntry {
+                app: item.app,
+                container_id: item.container_id,
+                pid: match item.pid {
+                    serde_json::Value::String(s) => s,
+                    serde_json::Value::Null => String::new(),
+                    other => other.to_string(),
+                },
+            })
+            .collect(),
+    )
+}
+
+/// 解析 `ll-cli ps` 的表格输出（首行为表头）
+fn parse_ps_text(raw: &str) -> Vec<PsEntry> {
+    raw.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            (parts.len() >= 3).then(|| PsEntry {
+                app: parts[0].to_string(),
+                container_id: parts[1].to_string(),
+                pid: parts[2].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 列出运行中的容器，优先使用 JSON 输出，旧版 ll-cli 不支持时回退到文本解析
+pub(crate) fn list_ps() -> Result<Vec<PsEntry>, String> {
+    if let Ok(output) = ll_cli_command().arg("--json").arg("ps").output() {
+        if output.status.success() {
+            if let Some(entries) = parse_ps_json(&String::from_utf8_lossy(&output.stdout)) {
+                return Ok(entries);
+            }
+        }
+    }
+
     let ps_output = ll_cli_command()
         .arg("ps")
         .output()
@@ -37,111 +97,364 @@ pub async fn get_running_linglong_apps() -> Result<Vec<LinglongAppInfo>, String>
         ));
     }
 
-    let ps_string = String::from_utf8_lossy(&ps_output.stdout);
-    let mut apps = Vec::new();
-
-    // Skip header line
-    for line in ps_string.lines().skip(1) {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 3 {
-            let app_name = parts[0];
-            let container_id = parts[1];
-            let pid = parts[2];
-
-            let info_output = ll_cli_command()
-                .arg("info")
-                .arg(app_name)
-                .output()
-                .map_err(|e| format!("Failed to execute 'll-cli info {}': {}", app_name, e))?;
-
-            if info_output.status.success() {
-                let info_string = String::from_utf8_lossy(&info_output.stdout);
-                if let Ok(info_json) = serde_json::from_str::<AppInfoJson>(&info_string) {
-                    let source = info_json.base.split(':').next().unwrap_or("").to_string();
-                    apps.push(LinglongAppInfo {
-                        name: info_json.id,
-                        version: info_json.version,
-                        arch: info_json.arch.join(", "),
-                        channel: info_json.channel,
-                        source,
-                        pid: pid.to_string(),
-                        container_id: container_id.to_string(),
-                    });
-                }
+    Ok(parse_ps_text(&String::from_utf8_lossy(&ps_output.stdout)))
+}
+
+async fn ps_entries() -> Result<Vec<PsEntry>, String> {
+    tokio::task::spawn_blocking(list_ps)
+        .await
+        .map_err(|e| format!("ll-cli ps task failed: {}", e))?
+}
+
+/// 由容器与元信息组装运行中的应用信息，元信息缺失时以应用引用中的字段填充
+fn build_app_info(entry: PsEntry, info: Option<AppInfo>) -> LinglongAppInfo {
+    match info {
+        Some(info) => LinglongAppInfo {
+            name: info.id.clone(),
+            version: info.version.clone(),
+            arch: info.arch.join(", "),
+            channel: info.channel.clone(),
+            source: info.source(),
+            pid: entry.pid,
+            container_id: entry.container_id,
+            partial: false,
+        },
+        None => {
+            let app_ref = AppRef::parse(&entry.app);
+            LinglongAppInfo {
+                name: app_ref.id,
+                version: app_ref.version.unwrap_or_default(),
+                arch: app_ref.arch.unwrap_or_default(),
+                channel: app_ref.channel.unwrap_or_default(),
+                source: String::new(),
+                pid: entry.pid,
+                container_id: entry.container_id,
+                partial: true,
             }
         }
     }
+}
+
+pub async fn get_running_linglong_apps() -> Result<Vec<LinglongAppInfo>, String> {
+    let entries = ps_entries().await?;
+    if !entries.is_empty() {
+        // 可能有在商店外启动的应用，让监视器开始跟踪
+        wake_app_watcher();
+    }
 
-    Ok(apps)
+    let references: Vec<String> = entries.iter().map(|entry| entry.app.clone()).collect();
+    let infos = get_app_infos(&references).await;
+
+    Ok(entries
+        .into_iter()
+        .zip(infos)
+        .map(|(entry, info)| build_app_info(entry, info))
+        .collect())
+}
+
+/// 默认的 SIGTERM 宽限期
+const DEFAULT_GRACE_MS: u64 = 5000;
+/// 宽限期上限
+const MAX_GRACE_MS: u64 = 60_000;
+/// 宽限期内检查应用是否退出的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// SIGKILL 的最大尝试次数
+const KILL_ATTEMPTS: u32 = 3;
+
+/// 停止应用的结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StopAppResult {
+    /// 调用方传入的 appId 或容器 ID
+    pub target: String,
+    pub app_id: String,
+    /// 最终使应用退出的信号：SIGTERM / SIGKILL；调用时已不在运行则为 None
+    pub signal: Option<String>,
+    pub stopped: bool,
+    pub message: String,
+}
+
+/// 筛选与目标匹配的容器，目标可以是容器 ID、appId 或完整的应用引用
+fn matching_entries<'a>(entries: &'a [PsEntry], target: &str) -> Vec<&'a PsEntry> {
+    entries
+        .iter()
+        .filter(|entry| {
+            entry.container_id == target || entry.app == target || AppRef::parse(&entry.app).id == target
+        })
+        .collect()
 }
 
 async fn is_app_running(app_id: &str) -> Result<bool, String> {
-    let ps_output = ll_cli_command()
-        .arg("ps")
-        .output()
-        .map_err(|e| format!("Failed to execute 'll-cli ps': {}", e))?;
+    let entries = ps_entries().await?;
+    Ok(!matching_entries(&entries, app_id).is_empty())
+}
 
-    if !ps_output.status.success() {
-        return Err(format!(
-            "ll-cli ps command failed with status: {}",
-            ps_output.status
-        ));
-    }
+/// 通过 `ll-cli kill -s <signal>` 向应用发送信号
+async fn send_signal(app_id: &str, signal: i32) -> Result<(), String> {
+    let app_id = app_id.to_string();
+    let output = tokio::task::spawn_blocking(move || {
+        ll_cli_command()
+            .arg("kill")
+            .arg("-s")
+            .arg(signal.to_string())
+            .arg(&app_id)
+            .output()
+    })
+    .await
+    .map_err(|e| format!("ll-cli kill task failed: {}", e))?
+    .map_err(|e| format!("Failed to execute 'll-cli kill': {}", e))?;
 
-    let ps_string = String::from_utf8_lossy(&ps_output.stdout);
-    // Skip header line
-    for line in ps_string.lines().skip(1) {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.first() == Some(&app_id) {
-            return Ok(true);
-        }
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
     }
-    Ok(false)
 }
 
-pub async fn kill_linglong_app(app_name: String) -> Result<String, String> {
-    // 尝试停止运行中的应用，最多 5 次，间隔 1 秒
-    for attempt in 1..=5 {
-        let running = is_app_running(&app_name).await?;
-        if !running {
-            info!("[kill_linglong_app] App not running, proceed: {}", app_name);
-            return Ok(format!("Successfully stopped {}", app_name));
-        }
+/// 向指定容器的进程发送信号；容器进程不属于当前用户时通过 pkexec 提权
+async fn signal_container(pid: u32, signal: i32) -> Result<(), String> {
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+    if err.raw_os_error() != Some(libc::EPERM) {
+        return Err(format!("向进程 {} 发送信号失败: {}", pid, err));
+    }
 
-        info!(
-            "[kill_linglong_app] App is running, attempt {} to kill: {}",
-            attempt, app_name
-        );
-        let output = ll_cli_command()
+    let output = tokio::task::spawn_blocking(move || {
+        std::process::Command::new("pkexec")
             .arg("kill")
             .arg("-s")
-            .arg("9")
-            .arg(&app_name)
+            .arg(signal.to_string())
+            .arg(pid.to_string())
             .output()
-            .map_err(|e| format!("Failed to execute 'll-cli kill': {}", e))?;
-        let mut error_msg = String::from_utf8_lossy(&output.stderr).to_string();
-        if !output.status.success() {
-            warn!(
-                "[kill_linglong_app] kill attempt {} failed for {}: {}",
-                attempt, app_name, error_msg
-            );
+    })
+    .await
+    .map_err(|e| format!("pkexec kill task failed: {}", e))?
+    .map_err(|e| format!("Failed to execute 'pkexec kill': {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// 信号的发送对象：指定容器时只向该容器的进程发送，否则通过 `ll-cli kill` 发送给整个应用
+#[derive(Debug, PartialEq)]
+enum SignalTarget {
+    App(String),
+    Container(u32),
+}
+
+impl SignalTarget {
+    fn resolve(entry: &PsEntry, target: &str) -> Result<Self, String> {
+        if entry.container_id.is_empty() || entry.container_id != target {
+            return Ok(SignalTarget::App(AppRef::parse(&entry.app).id));
+        }
+        entry
+            .pid
+            .trim()
+            .parse::<u32>()
+            .map(SignalTarget::Container)
+            .map_err(|_| format!("无法获取容器 {} 的进程号", target))
+    }
+
+    async fn send(&self, signal: i32) -> Result<(), String> {
+        match self {
+            SignalTarget::App(app_id) => send_signal(app_id, signal).await,
+            SignalTarget::Container(pid) => signal_container(*pid, signal).await,
         }
+    }
+}
 
-        if attempt == 5 {
-            // 最后一轮后再检查一次，仍在运行则返回错误
-            let still_running = is_app_running(&app_name).await.unwrap_or(true);
-            if still_running {
-                warn!("[kill_linglong_app] error_msg: {}", error_msg);
-                if error_msg.is_empty() {
-                    error_msg = "未知错误".to_string();
-                }
-                return Err(error_msg.to_string());
-            }
-            break;
+/// 在 timeout 内轮询，应用退出时返回 true
+async fn wait_for_exit(target: &str, timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Ok(false) = is_app_running(target).await {
+            return true;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return false;
         }
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// 停止应用：先发送 SIGTERM，宽限期内未退出再发送 SIGKILL
+///
+/// # Arguments
+/// * `target` - appId 或 `ll-cli ps` 中的容器 ID，指定容器时只停止该容器
+/// * `grace_ms` - SIGTERM 后等待应用自行退出的时间（毫秒），默认 5000
+pub async fn stop_linglong_app(target: String, grace_ms: Option<u64>) -> Result<StopAppResult, String> {
+    let target = target.trim().to_string();
+    if target.is_empty() {
+        return Err("应用 ID 不能为空".to_string());
+    }
+    let grace = Duration::from_millis(grace_ms.unwrap_or(DEFAULT_GRACE_MS).min(MAX_GRACE_MS));
+
+    let entries = ps_entries().await?;
+    let Some(entry) = matching_entries(&entries, &target).first().cloned() else {
+        info!("[stop_linglong_app] App not running: {}", target);
+        return Ok(StopAppResult {
+            app_id: target.clone(),
+            target,
+            stopped: true,
+            message: "应用未在运行".to_string(),
+            ..Default::default()
+        });
+    };
+    let app_id = AppRef::parse(&entry.app).id;
+    let signal_target = SignalTarget::resolve(entry, &target)?;
+    let mut result = StopAppResult {
+        target: target.clone(),
+        app_id: app_id.clone(),
+        ..Default::default()
+    };
+
+    info!("[stop_linglong_app] Sending SIGTERM to {} (grace {:?})", target, grace);
+    if let Err(e) = signal_target.send(libc::SIGTERM).await {
+        warn!("[stop_linglong_app] SIGTERM failed for {}: {}", app_id, e);
+    }
+    if wait_for_exit(&target, grace).await {
+        result.signal = Some("SIGTERM".to_string());
+        result.stopped = true;
+        result.message = format!("{} 已正常退出", app_id);
+        return Ok(result);
+    }
 
-        sleep(Duration::from_secs(1)).await;
+    let mut error_msg = String::new();
+    for attempt in 1..=KILL_ATTEMPTS {
+        info!("[stop_linglong_app] Attempt {} to send SIGKILL to {}", attempt, app_id);
+        if let Err(e) = signal_target.send(libc::SIGKILL).await {
+            warn!("[stop_linglong_app] SIGKILL attempt {} failed for {}: {}", attempt, app_id, e);
+            error_msg = e;
+        }
+        if wait_for_exit(&target, Duration::from_secs(1)).await {
+            result.signal = Some("SIGKILL".to_string());
+            result.stopped = true;
+            result.message = format!("{} 未在宽限期内退出，已强制结束", app_id);
+            return Ok(result);
+        }
     }
 
+    if error_msg.is_empty() {
+        error_msg = "未知错误".to_string();
+    }
+    Err(format!("无法停止 {}: {}", app_id, error_msg))
+}
+
+/// 停止所有运行中的玲珑应用，各应用并发执行
+pub async fn stop_all_linglong_apps(grace_ms: Option<u64>) -> Result<Vec<StopAppResult>, String> {
+    let entries = ps_entries().await?;
+    let mut app_ids: Vec<String> = entries.iter().map(|entry| AppRef::parse(&entry.app).id).collect();
+    app_ids.sort();
+    app_ids.dedup();
+    info!("[stop_all_linglong_apps] Stopping {} app(s)", app_ids.len());
+
+    let mut tasks = JoinSet::new();
+    for app_id in app_ids {
+        tasks.spawn(async move {
+            let result = stop_linglong_app(app_id.clone(), grace_ms).await;
+            result.unwrap_or_else(|e| StopAppResult {
+                target: app_id.clone(),
+                app_id,
+                stopped: false,
+                message: e,
+                ..Default::default()
+            })
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => warn!("[stop_all_linglong_apps] Stop task failed: {}", e),
+        }
+    }
+    results.sort_by(|a, b| a.app_id.cmp(&b.app_id));
+    Ok(results)
+}
+
+pub async fn kill_linglong_app(app_name: String) -> Result<String, String> {
+    let result = stop_linglong_app(app_name.clone(), None).await?;
+    info!(
+        "[kill_linglong_app] {} stopped with {:?}",
+        app_name, result.signal
+    );
     Ok(format!("Successfully stopped {}", app_name))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ps_json() {
+        let raw = r#"[{"id":"5b0c5f2a9e1d","package":"main:org.example.app/1.0.0.0/x86_64","pid":4242}]"#;
+        let entries = parse_ps_json(raw).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].app, "main:org.example.app/1.0.0.0/x86_64");
+        assert_eq!(entries[0].container_id, "5b0c5f2a9e1d");
+        assert_eq!(entries[0].pid, "4242");
+        // 文本输出不是合法 JSON，需回退
+        assert!(parse_ps_json("App  ContainerID  Pid\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_ps_text() {
+        let raw = "App                         ContainerID      Pid\n\
+                   main:org.example.app/1.0.0.0/x86_64  0f1e2d3c   1234\n\
+                   org.example.other           aabbccdd         5678\n";
+        let entries = parse_ps_text(raw);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].app, "org.example.other");
+        assert_eq!(entries[1].pid, "5678");
+    }
+
+    #[test]
+    fn test_signal_target_by_container() {
+        let entry = PsEntry {
+            app: "main:org.example.app/1.0.0.0/x86_64".to_string(),
+            container_id: "c1".to_string(),
+            pid: "4242".to_string(),
+        };
+        assert_eq!(SignalTarget::resolve(&entry, "c1"), Ok(SignalTarget::Container(4242)));
+        assert_eq!(
+            SignalTarget::resolve(&entry, "org.example.app"),
+            Ok(SignalTarget::App("org.example.app".to_string()))
+        );
+        let no_pid = PsEntry { pid: String::new(), ..entry };
+        assert!(SignalTarget::resolve(&no_pid, "c1").is_err());
+    }
+
+    #[test]
+    fn test_build_partial_app_info() {
+        let entry = PsEntry {
+            app: "main:org.example.app/1.0.0.0/x86_64".to_string(),
+            container_id: "abc".to_string(),
+            pid: "1".to_string(),
+        };
+        let app = build_app_info(entry, None);
+        assert!(app.partial);
+        assert_eq!(app.name, "org.example.app");
+        assert_eq!(app.version, "1.0.0.0");
+        assert_eq!(app.channel, "main");
+    }
+
+    #[test]
+    fn test_matching_entries() {
+        let entries = parse_ps_text(
+            "App  ContainerID  Pid\n\
+             main:org.example.app/1.0.0.0/x86_64  c1  1\n\
+             main:org.example.app/1.0.0.0/x86_64  c2  2\n\
+             org.example.other  c3  3\n",
+        );
+        assert_eq!(matching_entries(&entries, "org.example.app").len(), 2);
+        let by_container = matching_entries(&entries, "c3");
+        assert_eq!(by_container.len(), 1);
+        assert_eq!(by_container[0].app, "org.example.other");
+        assert!(matching_entries(&entries, "org.example").is_empty());
+    }
+}
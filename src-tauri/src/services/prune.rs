@@ -1,39 +1,222 @@
+//! 清理废弃的基础服务
+//!
+//! 预览：根据已安装应用（及运行时）引用的 base/runtime，找出不再被引用的运行时与基础环境，
+//! 列出每项大小与可回收空间总量。有应用无法获取 `ll-cli info` 时其 base 未知，预览中不列出基础环境。
+//! 清理：执行 `ll-cli prune`，过程通过 `prune-progress` 事件推送，完成后比较清理前后的
+//! 安装列表，报告实际删除的项目与释放的空间。
+
 use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter};
+
+use crate::services::dependencies::{collect_references, resolve_reference};
+use crate::services::install::{get_installed_apps, InstalledApp};
 use crate::services::ll_cli_command;
 
-/// 清理废弃的基础服务
-/// 调用 ll-cli prune 命令
-pub async fn prune_linglong_apps() -> Result<String, String> {
-    info!("[prune_linglong_apps] Starting prune operation");
+/// 清理进度事件名称
+const PRUNE_PROGRESS_EVENT: &str = "prune-progress";
+/// 玲珑数据目录，用于统计磁盘可用空间变化
+const LINGLONG_ROOT: &str = "/var/lib/linglong";
+
+/// 可清理（或已清理）的一项
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneItem {
+    pub app_id: String,
+    pub name: String,
+    pub version: String,
+    pub arch: String,
+    pub channel: String,
+    /// runtime 或 base
+    pub kind: String,
+    pub module: String,
+    pub size_bytes: u64,
+}
+
+/// 清理预览
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrunePreview {
+    pub items: Vec<PruneItem>,
+    /// 可回收空间总量
+    pub total_bytes: u64,
+    /// 无法获取依赖信息的应用（appId/version），存在时基础环境不列入预览
+    pub missing_info: Vec<String>,
+}
+
+/// 清理结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    /// 实际被删除的项目
+    pub removed: Vec<PruneItem>,
+    /// 删除项目的大小之和
+    pub freed_bytes: u64,
+    /// 玲珑数据目录所在磁盘可用空间的增加量，无法统计时为 None
+    pub disk_freed_bytes: Option<u64>,
+    /// ll-cli prune 的输出
+    pub message: String,
+}
 
-    let mut cmd = ll_cli_command();
-    cmd.arg("prune");
+/// 清理进度
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneProgress {
+    /// "scanning" | "pruning" | "verifying" | "done"
+    pub stage: String,
+    pub message: String,
+}
 
-    let output = cmd
-        .output()
+fn emit_progress(app_handle: &AppHandle, stage: &str, message: impl Into<String>) {
+    let _ = app_handle.emit(
+        PRUNE_PROGRESS_EVENT,
+        PruneProgress {
+            stage: stage.to_string(),
+            message: message.into(),
+        },
+    );
+}
+
+fn is_base_service(app: &InstalledApp) -> bool {
+    matches!(app.kind.as_deref(), Some("runtime") | Some("base"))
+}
+
+fn to_item(app: &InstalledApp) -> PruneItem {
+    PruneItem {
+        app_id: app.app_id.clone(),
+        name: app.name.clone(),
+        version: app.version.clone(),
+        arch: app.arch.clone(),
+        channel: app.channel.clone(),
+        kind: app.kind.clone().unwrap_or_default(),
+        module: app.module.clone(),
+        size_bytes: app.size_bytes,
+    }
+}
+
+/// 找出未被任何引用命中的运行时与基础环境
+///
+/// `bases_known` 为 false 时有应用的 base 未知，任何基础环境都可能仍在使用，不予列出。
+fn find_unreferenced<'a>(
+    installed: &'a [InstalledApp],
+    references: &[String],
+    bases_known: bool,
+) -> Vec<&'a InstalledApp> {
+    let referenced: HashSet<(&str, &str)> = references
+        .iter()
+        .filter_map(|reference| resolve_reference(installed, reference))
+        .map(|app| (app.app_id.as_str(), app.version.as_str()))
+        .collect();
+    installed
+        .iter()
+        .filter(|app| is_base_service(app))
+        .filter(|app| bases_known || app.kind.as_deref() != Some("base"))
+        .filter(|app| !referenced.contains(&(app.app_id.as_str(), app.version.as_str())))
+        .collect()
+}
+
+async fn preview(installed: &[InstalledApp]) -> Result<PrunePreview, String> {
+    let (references, mut missing_info) = collect_references(installed).await;
+    let references: Vec<String> = references.into_values().flatten().collect();
+    // 有应用信息缺失时无法确认其 base，宁可不列出也不误报
+    if !missing_info.is_empty() {
+        warn!(
+            "[prune_linglong_apps] Base of {} is unknown, leaving bases out of the preview",
+            missing_info.join(", ")
+        );
+    }
+    missing_info.sort();
+
+    let items: Vec<PruneItem> = find_unreferenced(installed, &references, missing_info.is_empty())
+        .into_iter()
+        .map(to_item)
+        .collect();
+    let total_bytes = items.iter().map(|item| item.size_bytes).sum();
+    Ok(PrunePreview {
+        items,
+        total_bytes,
+        missing_info,
+    })
+}
+
+/// 预览清理会删除的运行时与基础环境
+pub async fn preview_prune() -> Result<PrunePreview, String> {
+    let installed = get_installed_apps(true).await?;
+    preview(&installed).await
+}
+
+/// 磁盘可用空间（字节）
+fn available_bytes(path: &str) -> Option<u64> {
+    let c_path = std::ffi::CString::new(path).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// 对比清理前后的安装列表，找出被删除的项目
+fn removed_items(before: &[InstalledApp], after: &[InstalledApp]) -> Vec<PruneItem> {
+    let remaining: HashSet<(&str, &str, &str)> = after
+        .iter()
+        .map(|app| (app.app_id.as_str(), app.version.as_str(), app.module.as_str()))
+        .collect();
+    before
+        .iter()
+        .filter(|app| !remaining.contains(&(app.app_id.as_str(), app.version.as_str(), app.module.as_str())))
+        .map(to_item)
+        .collect()
+}
+
+/// 执行 ll-cli prune，逐行推送输出
+fn run_prune(app_handle: &AppHandle) -> Result<String, String> {
+    let mut child = ll_cli_command()
+        .arg("prune")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| {
             error!("[prune_linglong_apps] Failed to execute ll-cli prune: {}", e);
             format!("执行 ll-cli prune 失败: {}", e)
         })?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let stderr_reader = child.stderr.take().map(|stderr| {
+        std::thread::spawn(move || {
+            let mut content = String::new();
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                content.push_str(&line);
+                content.push('\n');
+            }
+            content
+        })
+    });
 
+    let mut stdout = String::new();
+    if let Some(out) = child.stdout.take() {
+        for line in BufReader::new(out).lines().map_while(Result::ok) {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                emit_progress(app_handle, "pruning", trimmed);
+            }
+            stdout.push_str(&line);
+            stdout.push('\n');
+        }
+    }
+
+    let status = child.wait().map_err(|e| format!("等待 ll-cli prune 退出失败: {}", e))?;
+    let stderr = stderr_reader.and_then(|h| h.join().ok()).unwrap_or_default();
     info!("[prune_linglong_apps] stdout: {}", stdout);
     if !stderr.is_empty() {
         warn!("[prune_linglong_apps] stderr: {}", stderr);
     }
 
-    if output.status.success() {
-        let message = if stdout.trim().is_empty() {
-            "清理完成".to_string()
-        } else {
-            stdout.trim().to_string()
-        };
-        info!("[prune_linglong_apps] Prune completed successfully: {}", message);
-        Ok(message)
+    if status.success() {
+        Ok(stdout.trim().to_string())
     } else {
-        let error_msg = if !stderr.is_empty() {
+        let error_msg = if !stderr.trim().is_empty() {
             stderr.trim().to_string()
         } else {
             "清理失败".to_string()
@@ -42,3 +225,108 @@ pub async fn prune_linglong_apps() -> Result<String, String> {
         Err(error_msg)
     }
 }
+
+/// 清理废弃的基础服务
+/// 调用 ll-cli prune 命令，返回实际删除的项目与释放的空间
+pub async fn prune_linglong_apps(app_handle: AppHandle) -> Result<PruneReport, String> {
+    info!("[prune_linglong_apps] Starting prune operation");
+    emit_progress(&app_handle, "scanning", "正在读取已安装的基础服务");
+    let before = get_installed_apps(true).await?;
+    let available_before = available_bytes(LINGLONG_ROOT);
+
+    emit_progress(&app_handle, "pruning", "正在执行 ll-cli prune");
+    let handle = app_handle.clone();
+    let stdout = tokio::task::spawn_blocking(move || run_prune(&handle))
+        .await
+        .map_err(|e| format!("清理任务执行失败: {}", e))??;
+
+    emit_progress(&app_handle, "verifying", "正在统计释放的空间");
+    let after = get_installed_apps(true).await?;
+    let removed = removed_items(&before, &after);
+    let freed_bytes = removed.iter().map(|item| item.size_bytes).sum();
+    let disk_freed_bytes = match (available_before, available_bytes(LINGLONG_ROOT)) {
+        (Some(before), Some(after)) => Some(after.saturating_sub(before)),
+        _ => None,
+    };
+
+    let message = if stdout.is_empty() {
+        "清理完成".to_string()
+    } else {
+        stdout
+    };
+    info!(
+        "[prune_linglong_apps] Prune completed: {} item(s) removed, {} bytes freed",
+        removed.len(),
+        freed_bytes
+    );
+    emit_progress(&app_handle, "done", format!("已清理 {} 项", removed.len()));
+    Ok(PruneReport {
+        removed,
+        freed_bytes,
+        disk_freed_bytes,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(app_id: &str, version: &str, kind: &str, size: &str) -> InstalledApp {
+        InstalledApp::test(app_id, version).with_kind(kind).with_size(size)
+    }
+
+    #[test]
+    fn test_find_unreferenced() {
+        let list = vec![
+            installed("org.example.app", "1.0.0.0", "app", "100"),
+            installed("org.deepin.base", "23.1.0.1", "base", "1000"),
+            installed("org.deepin.base", "23.1.0.2", "base", "1000"),
+            installed("org.deepin.base", "20.0.0.0", "base", "500"),
+            installed("org.deepin.runtime.dtk", "23.1.0.0", "runtime", "300"),
+        ];
+        let references = vec![
+            "main:org.deepin.base/23.1.0/x86_64".to_string(),
+            "main:org.deepin.runtime.dtk/23.1.0/x86_64".to_string(),
+        ];
+        let unreferenced: Vec<(&str, &str)> = find_unreferenced(&list, &references, true)
+            .into_iter()
+            .map(|app| (app.app_id.as_str(), app.version.as_str()))
+            .collect();
+        // 23.1.0 只命中最新的 23.1.0.2，旧版本和 20.0 都可清理；应用本身不在列表中
+        assert_eq!(
+            unreferenced,
+            vec![("org.deepin.base", "23.1.0.1"), ("org.deepin.base", "20.0.0.0")]
+        );
+    }
+
+    #[test]
+    fn test_find_unreferenced_skips_bases_when_unknown() {
+        let list = vec![
+            installed("org.example.app", "1.0.0.0", "app", "100"),
+            installed("org.deepin.base", "20.0.0.0", "base", "500"),
+            installed("org.deepin.runtime.dtk", "23.1.0.0", "runtime", "300"),
+            installed("org.deepin.runtime.dtk", "20.0.0.0", "runtime", "200"),
+        ];
+        // 应用缺少 info，只知道 list 中的 runtime
+        let references = vec!["main:org.deepin.runtime.dtk/23.1.0/x86_64".to_string()];
+        let unreferenced: Vec<(&str, &str)> = find_unreferenced(&list, &references, false)
+            .into_iter()
+            .map(|app| (app.app_id.as_str(), app.version.as_str()))
+            .collect();
+        assert_eq!(unreferenced, vec![("org.deepin.runtime.dtk", "20.0.0.0")]);
+    }
+
+    #[test]
+    fn test_removed_items() {
+        let before = vec![
+            installed("org.deepin.base", "20.0.0.0", "base", "500"),
+            installed("org.deepin.base", "23.1.0.2", "base", "1000"),
+        ];
+        let after = vec![installed("org.deepin.base", "23.1.0.2", "base", "1000")];
+        let removed = removed_items(&before, &after);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].version, "20.0.0.0");
+        assert_eq!(removed[0].size_bytes, 500);
+    }
+}
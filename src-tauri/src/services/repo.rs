@@ -0,0 +1,466 @@
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Output;
+use crate::services::{ll_cli_command, privileged_ll_cli_command, privileged_shell_command};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LinglongRepo {
+    pub name: String,
+    pub url: String,
+    pub alias: Option<String>,
+    pub priority: Option<String>,
+}
+
+impl LinglongRepo {
+    /// 仓库优先级数值，数值越大优先级越高；未设置或无法解析时视为 0
+    pub fn priority_value(&self) -> i64 {
+        self.priority
+            .as_deref()
+            .and_then(|p| p.trim().parse::<i64>().ok())
+            .unwrap_or(0)
+    }
+
+    /// 判断名称或别名是否指向该仓库
+    pub fn matches(&self, name_or_alias: &str) -> bool {
+        self.name == name_or_alias || self.alias.as_deref() == Some(name_or_alias)
+    }
+}
+
+/// 玲珑仓库配置（ll-cli repo show）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LinglongRepoConfig {
+    pub default_repo: Option<String>,
+    pub repos: Vec<LinglongRepo>,
+}
+
+impl LinglongRepoConfig {
+    /// 按名称或别名查找仓库
+    pub fn find(&self, name_or_alias: &str) -> Option<&LinglongRepo> {
+        self.repos.iter().find(|repo| repo.matches(name_or_alias))
+    }
+
+    /// 仓库排序权重：优先级数值越大越靠前，默认仓库在同优先级中优先
+    pub fn rank_of(&self, name_or_alias: &str) -> (i64, bool) {
+        match self.find(name_or_alias) {
+            Some(repo) => (
+                repo.priority_value(),
+                self.default_repo.as_deref().is_some_and(|d| repo.matches(d)),
+            ),
+            None => (i64::MIN, false),
+        }
+    }
+}
+
+/// 解析 ll-cli repo show 的文本输出（旧版 ll-cli 不支持 --json）
+fn parse_repo_output(output: &str) -> LinglongRepoConfig {
+    let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.is_empty() {
+        return LinglongRepoConfig::default();
+    }
+
+    let default_repo = lines
+        .first()
+        .and_then(|l| l.split(':').nth(1))
+        .map(|s| s.trim().to_string());
+    let repo_lines = if lines.len() > 2 { &lines[2..] } else { &[] };
+    let mut repos = Vec::new();
+    for line in repo_lines {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() {
+            continue;
+        }
+        let name = parts.first().unwrap_or(&"").to_string();
+        let url = parts.get(1).unwrap_or(&"").to_string();
+        let alias = parts.get(2).map(|s| s.to_string());
+        let priority = parts.get(3).map(|s| s.to_string());
+        repos.push(LinglongRepo {
+            name,
+            url,
+            alias,
+            priority,
+        });
+    }
+
+    LinglongRepoConfig {
+        default_repo,
+        repos,
+    }
+}
+
+/// 解析 ll-cli --json repo show 的输出
+fn parse_repo_json(output: &str) -> Option<LinglongRepoConfig> {
+    let json = serde_json::from_str::<HashMap<String, serde_json::Value>>(output).ok()?;
+    let default_repo = json
+        .get("defaultRepo")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let repos = json
+        .get("repos")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|item| {
+                    // priority 在不同版本中可能是字符串或数字
+                    let priority = item.get("priority").and_then(|v| match v {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        serde_json::Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    });
+                    Some(LinglongRepo {
+                        name: item.get("name")?.as_str().unwrap_or("").to_string(),
+                        url: item.get("url")?.as_str().unwrap_or("").to_string(),
+                        alias: item.get("alias").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        priority,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    Some(LinglongRepoConfig {
+        default_repo,
+        repos,
+    })
+}
+
+/// 读取当前的玲珑仓库配置
+///
+/// 优先使用 `ll-cli --json repo show`，失败时回退到文本输出解析。
+pub(crate) fn read_repo_config() -> Result<LinglongRepoConfig, String> {
+    let output = ll_cli_command()
+        .arg("--json")
+        .arg("repo")
+        .arg("show")
+        .output()
+        .map_err(|e| format!("Failed to execute 'll-cli repo show': {}", e))?;
+
+    if output.status.success() {
+        let clean = String::from_utf8_lossy(&output.stdout);
+        if let Some(config) = parse_repo_json(&clean) {
+            return Ok(config);
+        }
+        return Ok(parse_repo_output(&clean));
+    }
+
+    // 尝试旧命令
+    let fallback = ll_cli_command()
+        .arg("repo")
+        .arg("show")
+        .output()
+        .map_err(|e| format!("Failed to execute 'll-cli repo show': {}", e))?;
+    if !fallback.status.success() {
+        let error_msg = String::from_utf8_lossy(&fallback.stderr);
+        return Err(format!("ll-cli repo show command failed: {}", error_msg));
+    }
+    Ok(parse_repo_output(&String::from_utf8_lossy(&fallback.stdout)))
+}
+
+/// 获取当前的玲珑仓库配置
+pub async fn get_repo_config() -> Result<LinglongRepoConfig, String> {
+    read_repo_config()
+}
+
+/// 校验仓库名称/别名：非空，仅允许字母、数字、`.`、`_`、`-`，且不能以 `-` 开头
+fn validate_repo_name(value: &str, field: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Err(format!("{}不能为空", field));
+    }
+    if value.starts_with('-') {
+        return Err(format!("{}不能以 - 开头: {}", field, value));
+    }
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
+    {
+        return Err(format!("{}只能包含字母、数字、.、_ 和 -: {}", field, value));
+    }
+    Ok(())
+}
+
+/// 校验仓库地址
+///
+/// 支持 http(s):// 远程仓库，以及指向本地已存在目录的 file:// 离线镜像。
+fn validate_repo_url(url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Err("仓库地址不能为空".to_string());
+    }
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("仓库地址不能包含空白字符: {}", url));
+    }
+
+    if let Some(path) = url.strip_prefix("file://") {
+        if !path.starts_with('/') {
+            return Err(format!("离线仓库地址必须是绝对路径: {}", url));
+        }
+        if !Path::new(path).is_dir() {
+            return Err(format!("离线仓库目录不存在: {}", path));
+        }
+        return Ok(());
+    }
+
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| format!("仓库地址仅支持 http://、https:// 或 file://: {}", url))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit('@').next().unwrap_or("");
+    if host.is_empty() || host.starts_with(':') {
+        return Err(format!("仓库地址缺少主机名: {}", url));
+    }
+    Ok(())
+}
+
+/// 校验新增仓库的名称与别名在现有配置中唯一
+fn validate_unique(config: &LinglongRepoConfig, name: &str, alias: Option<&str>) -> Result<(), String> {
+    if config.find(name).is_some() {
+        return Err(format!("仓库名称已存在: {}", name));
+    }
+    if let Some(alias) = alias {
+        if config.find(alias).is_some() {
+            return Err(format!("仓库别名已存在: {}", alias));
+        }
+    }
+    Ok(())
+}
+
+fn require_repo<'a>(config: &'a LinglongRepoConfig, name: &str) -> Result<&'a LinglongRepo, String> {
+    config
+        .find(name)
+        .ok_or_else(|| format!("未找到仓库: {}", name))
+}
+
+/// 以 root 权限执行 ll-cli repo 子命令
+pub(crate) fn run_privileged_repo_command(args: &[&str]) -> Result<(), String> {
+    info!("[Repo] Executing: pkexec ll-cli repo {}", args.join(" "));
+
+    let output = privileged_ll_cli_command()
+        .arg("repo")
+        .args(args)
+        .output()
+        .map_err(|e| format!("执行 ll-cli repo 失败: {}", e))?;
+    check_privileged_output(&output, &format!("ll-cli repo {}", args.join(" ")))
+}
+
+/// 单引号转义，用于拼接 sh -c 脚本
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// 将多条 ll-cli repo 子命令拼接为一个 shell 脚本，任一失败即停止
+fn repo_commands_script(commands: &[Vec<String>]) -> String {
+    commands
+        .iter()
+        .map(|args| {
+            let quoted: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+            format!("ll-cli repo {}", quoted.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+/// 以 root 权限依次执行多条 ll-cli repo 子命令，只需授权一次
+pub(crate) fn run_privileged_repo_commands(commands: &[Vec<String>]) -> Result<(), String> {
+    if commands.is_empty() {
+        return Ok(());
+    }
+    let script = repo_commands_script(commands);
+    info!("[Repo] Executing: pkexec sh -c {}", script);
+
+    let output = privileged_shell_command(&script)
+        .output()
+        .map_err(|e| format!("执行 ll-cli repo 失败: {}", e))?;
+    check_privileged_output(&output, &script)
+}
+
+fn check_privileged_output(output: &Output, what: &str) -> Result<(), String> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    // pkexec 在认证失败或用户取消时返回 126/127
+    if matches!(output.status.code(), Some(126) | Some(127)) {
+        error!("[Repo] Authorization failed or cancelled");
+        return Err("授权失败或已取消".to_string());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    error!("[Repo] {} failed: {}", what, stderr);
+    Err(if stderr.is_empty() {
+        format!("仓库操作失败(code {:?})", output.status.code())
+    } else {
+        stderr
+    })
+}
+
+/// 添加仓库
+///
+/// # Arguments
+/// * `name` - 仓库名称
+/// * `url` - 仓库地址（http(s):// 或 file:// 离线镜像）
+/// * `alias` - 可选的仓库别名
+///
+/// # Returns
+/// * `Ok(LinglongRepoConfig)` - 操作后的仓库配置
+pub async fn add_repo(
+    name: String,
+    url: String,
+    alias: Option<String>,
+) -> Result<LinglongRepoConfig, String> {
+    let name = name.trim();
+    let url = url.trim();
+    let alias = alias.as_deref().map(str::trim).filter(|a| !a.is_empty());
+
+    validate_repo_name(name, "仓库名称")?;
+    if let Some(alias) = alias {
+        validate_repo_name(alias, "仓库别名")?;
+    }
+    validate_repo_url(url)?;
+    validate_unique(&read_repo_config()?, name, alias)?;
+
+    let mut args = vec!["add"];
+    if let Some(alias) = alias {
+        args.push("--alias");
+        args.push(alias);
+    }
+    args.push(name);
+    args.push(url);
+    run_privileged_repo_command(&args)?;
+
+    read_repo_config()
+}
+
+/// 删除仓库
+pub async fn remove_repo(name: String) -> Result<LinglongRepoConfig, String> {
+    let name = name.trim();
+    validate_repo_name(name, "仓库名称")?;
+    let config = read_repo_config()?;
+    let repo = require_repo(&config, name)?;
+    if config.default_repo.as_deref().is_some_and(|d| repo.matches(d)) {
+        return Err(format!("不能删除默认仓库: {}，请先切换默认仓库", name));
+    }
+
+    run_privileged_repo_command(&["remove", name])?;
+    read_repo_config()
+}
+
+/// 修改仓库地址
+pub async fn update_repo(name: String, url: String) -> Result<LinglongRepoConfig, String> {
+    let name = name.trim();
+    let url = url.trim();
+    validate_repo_name(name, "仓库名称")?;
+    validate_repo_url(url)?;
+    require_repo(&read_repo_config()?, name)?;
+
+    run_privileged_repo_command(&["update", name, url])?;
+    read_repo_config()
+}
+
+/// 设置默认仓库
+pub async fn set_default_repo(name: String) -> Result<LinglongRepoConfig, String> {
+    let name = name.trim();
+    validate_repo_name(name, "仓库名称")?;
+    require_repo(&read_repo_config()?, name)?;
+
+    run_privileged_repo_command(&["set-default", name])?;
+    read_repo_config()
+}
+
+/// 设置仓库优先级（数值越大优先级越高）
+pub async fn set_repo_priority(name: String, priority: i64) -> Result<LinglongRepoConfig, String> {
+    let name = name.trim();
+    validate_repo_name(name, "仓库名称")?;
+    require_repo(&read_repo_config()?, name)?;
+
+    let priority = priority.to_string();
+    run_privileged_repo_command(&["set-priority", name, &priority])?;
+    read_repo_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_commands_script() {
+        let commands = vec![
+            vec!["set-priority".to_string(), "stable".to_string(), "10".to_string()],
+            vec!["set-default".to_string(), "it's; rm -rf /".to_string()],
+        ];
+        let script = repo_commands_script(&commands);
+        assert_eq!(
+            script,
+            "ll-cli repo 'set-priority' 'stable' '10' && ll-cli repo 'set-default' 'it'\\''s; rm -rf /'"
+        );
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("set -- {}; printf '%s' \"$1\"", shell_quote("it's; rm -rf /")))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "it's; rm -rf /");
+    }
+
+    #[test]
+    fn test_parse_repo_json() {
+        let raw = r#"{"defaultRepo":"stable","repos":[{"name":"stable","url":"https://mirror-repo-linglong.deepin.com","alias":"stable","priority":0},{"name":"mirror","url":"https://mirror.example.com","priority":"10"}]}"#;
+        let config = parse_repo_json(raw).unwrap();
+        assert_eq!(config.default_repo.as_deref(), Some("stable"));
+        assert_eq!(config.repos.len(), 2);
+        assert_eq!(config.repos[0].priority_value(), 0);
+        assert_eq!(config.repos[1].priority_value(), 10);
+        assert_eq!(config.rank_of("stable"), (0, true));
+        assert_eq!(config.rank_of("mirror"), (10, false));
+        assert_eq!(config.rank_of("unknown"), (i64::MIN, false));
+    }
+
+    #[test]
+    fn test_parse_repo_text() {
+        let raw = "Default: stable\nName Url Alias Priority\nstable https://mirror-repo-linglong.deepin.com stable 0\n";
+        let config = parse_repo_output(raw);
+        assert_eq!(config.default_repo.as_deref(), Some("stable"));
+        assert_eq!(config.repos.len(), 1);
+        assert_eq!(config.repos[0].url, "https://mirror-repo-linglong.deepin.com");
+        assert!(config.find("stable").is_some());
+        assert!(parse_repo_output("").repos.is_empty());
+    }
+
+    #[test]
+    fn test_validate_repo_name() {
+        assert!(validate_repo_name("stable", "name").is_ok());
+        assert!(validate_repo_name("my-mirror_1.0", "name").is_ok());
+        assert!(validate_repo_name("", "name").is_err());
+        assert!(validate_repo_name("--force", "name").is_err());
+        assert!(validate_repo_name("a b", "name").is_err());
+        assert!(validate_repo_name("a/b", "name").is_err());
+    }
+
+    #[test]
+    fn test_validate_repo_url() {
+        assert!(validate_repo_url("https://mirror-repo-linglong.deepin.com").is_ok());
+        assert!(validate_repo_url("http://10.0.0.1:8080/repo").is_ok());
+        assert!(validate_repo_url("ftp://example.com").is_err());
+        assert!(validate_repo_url("https://").is_err());
+        assert!(validate_repo_url("https://:8080/").is_err());
+        assert!(validate_repo_url("https://exa mple.com").is_err());
+
+        let dir = std::env::temp_dir();
+        assert!(validate_repo_url(&format!("file://{}", dir.display())).is_ok());
+        assert!(validate_repo_url("file:///definitely/not/a/linglong/mirror").is_err());
+        assert!(validate_repo_url("file://relative/path").is_err());
+    }
+
+    #[test]
+    fn test_validate_unique() {
+        let config = parse_repo_json(
+            r#"{"defaultRepo":"stable","repos":[{"name":"stable","url":"https://a","alias":"main-repo"}]}"#,
+        )
+        .unwrap();
+        assert!(validate_unique(&config, "mirror", Some("mirror-alias")).is_ok());
+        assert!(validate_unique(&config, "stable", None).is_err());
+        assert!(validate_unique(&config, "main-repo", None).is_err());
+        assert!(validate_unique(&config, "mirror", Some("stable")).is_err());
+    }
+}
@@ -0,0 +1,403 @@
+//! 仓库健康检查
+//!
+//! 对每个已配置仓库依次进行 DNS 解析、TCP 连接和 HTTP HEAD（含 TLS 握手）探测，
+//! 返回各阶段耗时与状态，并给出整体在线/离线判断，供安装前快速判断网络问题。
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::timeout;
+
+use crate::services::repo::{read_repo_config, LinglongRepo};
+use crate::utils::time::now_millis;
+
+/// DNS 解析超时
+const DNS_TIMEOUT: Duration = Duration::from_secs(3);
+/// TCP 连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// HTTP 请求超时（含 TLS 握手）
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// 安装前检查复用探测结果的有效期
+const ONLINE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// 单个仓库的探测状态
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RepoHealthStatus {
+    /// 仓库可访问
+    Online,
+    /// 服务器可达，但仓库配置返回异常状态码
+    Degraded,
+    /// DNS 解析失败
+    DnsFailed,
+    /// TCP 连接失败
+    ConnectFailed,
+    /// TLS 握手或 HTTP 请求失败
+    HttpFailed,
+    /// 探测超时
+    Timeout,
+    /// 仓库地址无效
+    Invalid,
+}
+
+impl RepoHealthStatus {
+    /// 服务器是否可达（可达即说明本机网络正常）
+    pub fn is_reachable(self) -> bool {
+        matches!(self, RepoHealthStatus::Online | RepoHealthStatus::Degraded)
+    }
+}
+
+/// 单个仓库的探测结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoHealth {
+    pub name: String,
+    pub url: String,
+    pub status: RepoHealthStatus,
+    /// DNS 解析耗时（毫秒）
+    pub dns_ms: Option<u64>,
+    /// TCP 连接耗时（毫秒）
+    pub connect_ms: Option<u64>,
+    /// HTTP HEAD 耗时（毫秒，含 TLS 握手）
+    pub http_ms: Option<u64>,
+    /// 总耗时（毫秒）
+    pub latency_ms: Option<u64>,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// 所有仓库的探测报告
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoHealthReport {
+    /// 至少有一个仓库可达
+    pub online: bool,
+    pub repos: Vec<RepoHealth>,
+    /// 探测时间（Unix 毫秒）
+    pub checked_at: u64,
+}
+
+/// 最近一次探测结果，用于安装前检查
+static LAST_REPORT: Lazy<Mutex<Option<(Instant, RepoHealthReport)>>> = Lazy::new(|| Mutex::new(None));
+
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// 仓库配置文件地址：<url>/repos/<name>/config
+fn repo_config_url(repo: &LinglongRepo) -> Result<Url, String> {
+    let base = repo.url.trim_end_matches('/');
+    Url::parse(&format!("{}/repos/{}/config", base, repo.name))
+        .map_err(|e| format!("无效的仓库地址 {}: {}", repo.url, e))
+}
+
+/// 依次连接解析到的地址，直到有一个连接成功（如 IPv6 不可用时回退到 IPv4）
+///
+/// 成功时返回连接耗时；全部失败时，只要有一个地址连接超时即视为超时。
+async fn connect_any(addrs: &[SocketAddr]) -> Result<u64, (RepoHealthStatus, String)> {
+    let mut errors = Vec::new();
+    let mut timed_out = false;
+    for addr in addrs {
+        let connect_start = Instant::now();
+        match timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => return Ok(elapsed_ms(connect_start)),
+            Ok(Err(e)) => errors.push(format!("连接 {} 失败: {}", addr, e)),
+            Err(_) => {
+                timed_out = true;
+                errors.push(format!("连接 {} 超时", addr));
+            }
+        }
+    }
+    let status = if timed_out {
+        RepoHealthStatus::Timeout
+    } else {
+        RepoHealthStatus::ConnectFailed
+    };
+    Err((status, errors.join("; ")))
+}
+
+/// 探测单个仓库
+pub async fn probe_repo(repo: &LinglongRepo) -> RepoHealth {
+    let mut health = RepoHealth {
+        name: repo.name.clone(),
+        url: repo.url.clone(),
+        status: RepoHealthStatus::Invalid,
+        dns_ms: None,
+        connect_ms: None,
+        http_ms: None,
+        latency_ms: None,
+        http_status: None,
+        error: None,
+    };
+    let start = Instant::now();
+
+    // 离线镜像只检查目录是否存在
+    if let Some(path) = repo.url.strip_prefix("file://") {
+        if Path::new(path).is_dir() {
+            health.status = RepoHealthStatus::Online;
+            health.latency_ms = Some(elapsed_ms(start));
+        } else {
+            health.status = RepoHealthStatus::ConnectFailed;
+            health.error = Some(format!("离线仓库目录不存在: {}", path));
+        }
+        return health;
+    }
+
+    let url = match repo_config_url(repo) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => url,
+        Ok(url) => {
+            health.error = Some(format!("不支持的仓库地址: {}", url));
+            return health;
+        }
+        Err(e) => {
+            health.error = Some(e);
+            return health;
+        }
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    // 1. DNS 解析
+    let dns_start = Instant::now();
+    let addrs: Vec<SocketAddr> = match timeout(DNS_TIMEOUT, lookup_host((host.as_str(), port))).await {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => {
+            health.status = RepoHealthStatus::DnsFailed;
+            health.error = Some(format!("DNS 解析失败: {}", e));
+            return health;
+        }
+        Err(_) => {
+            health.status = RepoHealthStatus::Timeout;
+            health.error = Some("DNS 解析超时".to_string());
+            return health;
+        }
+    };
+    health.dns_ms = Some(elapsed_ms(dns_start));
+    if addrs.is_empty() {
+        health.status = RepoHealthStatus::DnsFailed;
+        health.error = Some(format!("DNS 未解析到地址: {}", host));
+        return health;
+    }
+
+    // 2. TCP 连接
+    match connect_any(&addrs).await {
+        Ok(ms) => health.connect_ms = Some(ms),
+        Err((status, error)) => {
+            health.status = status;
+            health.error = Some(error);
+            return health;
+        }
+    }
+
+    // 3. HTTP HEAD 仓库配置（https 时包含 TLS 握手）
+    let client = match reqwest::Client::builder().timeout(HTTP_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            health.status = RepoHealthStatus::HttpFailed;
+            health.error = Some(format!("创建 HTTP 客户端失败: {}", e));
+            return health;
+        }
+    };
+    let http_start = Instant::now();
+    match client.head(url).send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            health.http_ms = Some(elapsed_ms(http_start));
+            health.http_status = Some(status.as_u16());
+            if status.is_success() || status.is_redirection() {
+                health.status = RepoHealthStatus::Online;
+            } else {
+                health.status = RepoHealthStatus::Degraded;
+                health.error = Some(format!("仓库配置返回状态码 {}", status.as_u16()));
+            }
+        }
+        Err(e) if e.is_timeout() => {
+            health.status = RepoHealthStatus::Timeout;
+            health.error = Some("HTTP 请求超时".to_string());
+            return health;
+        }
+        Err(e) => {
+            health.status = RepoHealthStatus::HttpFailed;
+            health.error = Some(format!("HTTP 请求失败: {}", e));
+            return health;
+        }
+    }
+
+    health.latency_ms = Some(elapsed_ms(start));
+    health
+}
+
+/// 并发探测所有给定的仓库
+pub async fn probe_repos(repos: &[LinglongRepo]) -> RepoHealthReport {
+    let handles: Vec<_> = repos
+        .iter()
+        .cloned()
+        .map(|repo| tokio::spawn(async move { probe_repo(&repo).await }))
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(health) => results.push(health),
+            Err(e) => warn!("[RepoHealth] Probe task failed: {}", e),
+        }
+    }
+
+    let report = RepoHealthReport {
+        online: results.iter().any(|r| r.status.is_reachable()),
+        repos: results,
+        checked_at: now_millis(),
+    };
+
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some((Instant::now(), report.clone()));
+    }
+    report
+}
+
+/// 探测当前配置的所有仓库
+pub async fn check_repo_health() -> Result<RepoHealthReport, String> {
+    let config = read_repo_config()?;
+    let report = probe_repos(&config.repos).await;
+    for repo in &report.repos {
+        info!(
+            "[RepoHealth] {} ({}) -> {:?}, latency: {:?}ms",
+            repo.name, repo.url, repo.status, repo.latency_ms
+        );
+    }
+    Ok(report)
+}
+
+/// 安装前检查是否在线
+///
+/// 有效期内的在线结果直接复用；读取仓库配置失败时不阻断安装，返回 None。
+pub async fn ensure_online() -> Option<RepoHealthReport> {
+    if let Ok(last) = LAST_REPORT.lock() {
+        if let Some((at, report)) = last.as_ref() {
+            if report.online && at.elapsed() < ONLINE_CACHE_TTL {
+                return Some(report.clone());
+            }
+        }
+    }
+
+    match check_repo_health().await {
+        Ok(report) if !report.repos.is_empty() => Some(report),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("[RepoHealth] Skip online check: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 启动一个本地 HTTP 服务，对所有请求返回指定状态行
+    async fn spawn_stub_server(status_line: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let _ = socket.read(&mut buf).await;
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        status_line
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        addr
+    }
+
+    fn repo(name: &str, url: String) -> LinglongRepo {
+        LinglongRepo {
+            name: name.to_string(),
+            url,
+            alias: None,
+            priority: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_online_repo() {
+        let addr = spawn_stub_server("200 OK").await;
+        let health = probe_repo(&repo("stable", format!("http://{}", addr))).await;
+        assert_eq!(health.status, RepoHealthStatus::Online);
+        assert_eq!(health.http_status, Some(200));
+        assert!(health.dns_ms.is_some());
+        assert!(health.connect_ms.is_some());
+        assert!(health.latency_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_probe_degraded_repo() {
+        let addr = spawn_stub_server("404 Not Found").await;
+        let health = probe_repo(&repo("stable", format!("http://{}/", addr))).await;
+        assert_eq!(health.status, RepoHealthStatus::Degraded);
+        assert_eq!(health.http_status, Some(404));
+        assert!(health.status.is_reachable());
+    }
+
+    #[tokio::test]
+    async fn test_probe_connect_failed() {
+        // 绑定后立即释放端口，确保没有服务监听
+        let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let health = probe_repo(&repo("stable", format!("http://{}", addr))).await;
+        assert_eq!(health.status, RepoHealthStatus::ConnectFailed);
+        assert!(!health.status.is_reachable());
+    }
+
+    #[tokio::test]
+    async fn test_connect_any_falls_back_to_next_address() {
+        let addr = spawn_stub_server("200 OK").await;
+        let dead = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        assert!(connect_any(&[dead, addr]).await.is_ok());
+
+        let (status, error) = connect_any(&[dead]).await.unwrap_err();
+        assert_eq!(status, RepoHealthStatus::ConnectFailed);
+        assert!(error.contains(&dead.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_probe_local_and_invalid_repos() {
+        let dir = std::env::temp_dir();
+        let offline = probe_repo(&repo("local", format!("file://{}", dir.display()))).await;
+        assert_eq!(offline.status, RepoHealthStatus::Online);
+
+        let invalid = probe_repo(&repo("bad", "ftp://example.com".to_string())).await;
+        assert_eq!(invalid.status, RepoHealthStatus::Invalid);
+    }
+
+    #[tokio::test]
+    async fn test_report_online_flag() {
+        let addr = spawn_stub_server("200 OK").await;
+        let dead = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let report = probe_repos(&[
+            repo("dead", format!("http://{}", dead)),
+            repo("stable", format!("http://{}", addr)),
+        ])
+        .await;
+        assert!(report.online);
+        assert_eq!(report.repos.len(), 2);
+
+        let offline = probe_repos(&[repo("dead", format!("http://{}", dead))]).await;
+        assert!(!offline.online);
+    }
+}
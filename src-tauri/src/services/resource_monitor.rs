@@ -0,0 +1,240 @@
+//! 运行中应用的资源占用
+//!
+//! 以 `ll-cli ps` 给出的 pid 为根，从 /proc 遍历容器内的整个进程树，
+//! 汇总采样窗口内的 CPU 占用、RSS/PSS 内存、线程数与磁盘读写字节数。
+//! 流式监控按固定间隔持续采样，通过 `app-resource-usage` 事件推送给前端。
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::services::process::{get_running_linglong_apps, LinglongAppInfo};
+use crate::utils::linux::proc::{clock_ticks_per_second, process_tree, read_usage, ProcUsage};
+use crate::utils::time::now_millis;
+
+/// 资源采样事件名称
+const RESOURCE_USAGE_EVENT: &str = "app-resource-usage";
+/// 默认采样窗口
+const DEFAULT_WINDOW_MS: u64 = 1000;
+/// 最短采样窗口
+const MIN_WINDOW_MS: u64 = 200;
+
+/// 流式监控的代数，每次启动/停止递增，旧的监控循环据此退出
+static MONITOR_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 单个运行中应用的资源占用
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppResourceUsage {
+    pub name: String,
+    pub version: String,
+    pub container_id: String,
+    pub pid: String,
+    /// 容器内进程数
+    pub process_count: usize,
+    /// 采样窗口内的 CPU 占用（单核满载为 100）
+    pub cpu_percent: f64,
+    pub rss_bytes: u64,
+    /// 部分进程无法读取 smaps_rollup 时为已读取部分之和
+    pub pss_bytes: Option<u64>,
+    pub threads: u64,
+    pub read_bytes: Option<u64>,
+    pub write_bytes: Option<u64>,
+    /// 采样时间（Unix 毫秒）
+    pub sampled_at: u64,
+}
+
+fn sum_option(total: Option<u64>, value: Option<u64>) -> Option<u64> {
+    match (total, value) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+/// 读取进程树中每个进程的计数
+fn snapshot(root: u32) -> HashMap<u32, ProcUsage> {
+    process_tree(root)
+        .into_iter()
+        .filter_map(|pid| read_usage(pid).map(|usage| (pid, usage)))
+        .collect()
+}
+
+/// 由窗口起止两次快照汇总出应用的资源占用
+///
+/// CPU 按每个进程的节拍增量计算；窗口内新出现的进程计入其全部节拍。
+pub(crate) fn aggregate(
+    app: &LinglongAppInfo,
+    before: &HashMap<u32, ProcUsage>,
+    after: &HashMap<u32, ProcUsage>,
+    elapsed: Duration,
+    ticks_per_second: u64,
+) -> AppResourceUsage {
+    let mut usage = AppResourceUsage {
+        name: app.name.clone(),
+        version: app.version.clone(),
+        container_id: app.container_id.clone(),
+        pid: app.pid.clone(),
+        process_count: after.len(),
+        sampled_at: now_millis(),
+        ..Default::default()
+    };
+
+    let mut delta_ticks = 0u64;
+    for (pid, current) in after {
+        let previous = before.get(pid).map(|p| p.cpu_ticks).unwrap_or(0);
+        delta_ticks += current.cpu_ticks.saturating_sub(previous);
+        usage.rss_bytes += current.rss_bytes;
+        usage.threads += current.threads;
+        usage.pss_bytes = sum_option(usage.pss_bytes, current.pss_bytes);
+        usage.read_bytes = sum_option(usage.read_bytes, current.read_bytes);
+        usage.write_bytes = sum_option(usage.write_bytes, current.write_bytes);
+    }
+
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 && ticks_per_second > 0 {
+        let percent = delta_ticks as f64 / ticks_per_second as f64 / seconds * 100.0;
+        usage.cpu_percent = (percent * 10.0).round() / 10.0;
+    }
+    usage
+}
+
+/// 在一个采样窗口内同时采样所有应用
+fn sample_apps(apps: &[LinglongAppInfo], window: Duration) -> Vec<AppResourceUsage> {
+    let roots: Vec<Option<u32>> = apps.iter().map(|app| app.pid.trim().parse().ok()).collect();
+    let before: Vec<HashMap<u32, ProcUsage>> = roots
+        .iter()
+        .map(|root| root.map(snapshot).unwrap_or_default())
+        .collect();
+    let started = Instant::now();
+    std::thread::sleep(window);
+    let elapsed = started.elapsed();
+    let ticks_per_second = clock_ticks_per_second();
+
+    apps.iter()
+        .zip(roots.iter().zip(before.iter()))
+        .map(|(app, (root, before))| {
+            let after = root.map(snapshot).unwrap_or_default();
+            aggregate(app, before, &after, elapsed, ticks_per_second)
+        })
+        .collect()
+}
+
+async fn collect_usage(window: Duration) -> Result<Vec<AppResourceUsage>, String> {
+    let apps = get_running_linglong_apps().await?;
+    if apps.is_empty() {
+        return Ok(Vec::new());
+    }
+    tokio::task::spawn_blocking(move || sample_apps(&apps, window))
+        .await
+        .map_err(|e| format!("资源采样任务执行失败: {}", e))
+}
+
+fn window_of(window_ms: Option<u64>) -> Duration {
+    Duration::from_millis(window_ms.unwrap_or(DEFAULT_WINDOW_MS).max(MIN_WINDOW_MS))
+}
+
+/// 获取所有运行中应用的资源占用
+///
+/// # Arguments
+/// * `window_ms` - CPU 采样窗口（毫秒），默认 1000
+pub async fn get_app_resource_usage(window_ms: Option<u64>) -> Result<Vec<AppResourceUsage>, String> {
+    collect_usage(window_of(window_ms)).await
+}
+
+/// 开始流式监控，每个采样窗口结束时推送一次 `app-resource-usage` 事件
+///
+/// 重复调用会替换正在进行的监控。
+pub async fn start_resource_monitor(app_handle: AppHandle, interval_ms: Option<u64>) -> Result<(), String> {
+    let window = window_of(interval_ms);
+    let generation = MONITOR_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    info!("[ResourceMonitor] Start monitor #{} with window {:?}", generation, window);
+
+    tauri::async_runtime::spawn(async move {
+        while MONITOR_GENERATION.load(Ordering::SeqCst) == generation {
+            match collect_usage(window).await {
+                Ok(samples) => {
+                    if MONITOR_GENERATION.load(Ordering::SeqCst) != generation {
+                        break;
+                    }
+                    let _ = app_handle.emit(RESOURCE_USAGE_EVENT, &samples);
+                    // 没有运行中的应用时 collect_usage 立即返回，避免空转
+                    if samples.is_empty() {
+                        tokio::time::sleep(window).await;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("[ResourceMonitor] Sampling failed: {}", e);
+                    tokio::time::sleep(window).await;
+                }
+            }
+        }
+        info!("[ResourceMonitor] Monitor #{} stopped", generation);
+    });
+    Ok(())
+}
+
+/// 停止流式监控
+pub async fn stop_resource_monitor() -> Result<(), String> {
+    MONITOR_GENERATION.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> LinglongAppInfo {
+        LinglongAppInfo {
+            name: "org.example.app".to_string(),
+            version: "1.0.0.0".to_string(),
+            arch: "x86_64".to_string(),
+            channel: "main".to_string(),
+            source: "main".to_string(),
+            pid: "100".to_string(),
+            container_id: "abc".to_string(),
+            partial: false,
+        }
+    }
+
+    fn usage(cpu_ticks: u64, rss_bytes: u64, pss_bytes: Option<u64>) -> ProcUsage {
+        ProcUsage {
+            cpu_ticks,
+            rss_bytes,
+            pss_bytes,
+            threads: 2,
+            read_bytes: Some(10),
+            write_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let before: HashMap<u32, ProcUsage> = [(100, usage(100, 0, None)), (101, usage(50, 0, None))].into();
+        // 101 已退出，102 为窗口内新进程
+        let after: HashMap<u32, ProcUsage> =
+            [(100, usage(150, 1000, Some(600))), (102, usage(30, 500, None))].into();
+
+        let result = aggregate(&app(), &before, &after, Duration::from_secs(2), 100);
+        assert_eq!(result.process_count, 2);
+        // (50 + 30) 节拍 / 100 / 2 秒 = 40%
+        assert_eq!(result.cpu_percent, 40.0);
+        assert_eq!(result.rss_bytes, 1500);
+        assert_eq!(result.pss_bytes, Some(600));
+        assert_eq!(result.threads, 4);
+        assert_eq!(result.read_bytes, Some(20));
+        assert_eq!(result.write_bytes, None);
+    }
+
+    #[test]
+    fn test_sample_own_process_tree() {
+        let mut me = app();
+        me.pid = std::process::id().to_string();
+        let samples = sample_apps(&[me], Duration::from_millis(MIN_WINDOW_MS));
+        assert_eq!(samples.len(), 1);
+        assert!(samples[0].process_count >= 1);
+        assert!(samples[0].rss_bytes > 0);
+    }
+}
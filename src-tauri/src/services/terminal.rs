@@ -0,0 +1,368 @@
+//! 内置终端会话
+//!
+//! 基于 portable-pty 在伪终端中运行 `ll-cli exec` / `ll-cli enter` 或任意 ll-cli 子命令，
+//! 输出通过 `terminal-output` 事件推送给前端，进程退出时发送 `terminal-exit` 事件。
+//! 前端可写入输入、调整窗口大小或关闭会话。
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+const TERMINAL_OUTPUT_EVENT: &str = "terminal-output";
+const TERMINAL_EXIT_EVENT: &str = "terminal-exit";
+/// 同时打开的会话上限
+const MAX_SESSIONS: usize = 8;
+/// 容器内默认启动的 shell
+const DEFAULT_SHELL: &str = "bash";
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+static SESSIONS: Lazy<Mutex<HashMap<String, Session>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct Session {
+    info: TerminalSession,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+}
+
+/// 打开终端的请求
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TerminalRequest {
+    /// "exec"：在应用容器中执行命令（默认 bash）；"enter"：进入容器；"cli"：运行 ll-cli 子命令
+    pub kind: String,
+    /// exec/enter 时为 appId 或容器 ID
+    pub target: Option<String>,
+    /// exec 时为容器内执行的命令，cli 时为 ll-cli 的参数
+    pub args: Vec<String>,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+/// 已打开的终端会话
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalSession {
+    pub session_id: String,
+    /// 执行的完整命令，用于显示
+    pub command: String,
+    pub pid: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TerminalOutput {
+    session_id: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TerminalExit {
+    session_id: String,
+    exit_code: Option<u32>,
+}
+
+/// 根据请求构建 ll-cli 参数
+fn build_terminal_args(request: &TerminalRequest) -> Result<Vec<String>, String> {
+    let target = || -> Result<String, String> {
+        let target = request.target.as_deref().map(str::trim).unwrap_or("");
+        if target.is_empty() {
+            return Err("请指定应用或容器".to_string());
+        }
+        if target.starts_with('-') {
+            return Err(format!("应用 ID 不能以 '-' 开头: {}", target));
+        }
+        Ok(target.to_string())
+    };
+
+    match request.kind.as_str() {
+        "exec" => {
+            let mut args = vec!["exec".to_string(), target()?, "--".to_string()];
+            if request.args.is_empty() {
+                args.push(DEFAULT_SHELL.to_string());
+            } else {
+                args.extend(request.args.iter().cloned());
+            }
+            Ok(args)
+        }
+        "enter" => Ok(vec!["enter".to_string(), target()?]),
+        "cli" => match request.args.first() {
+            Some(sub) if !sub.starts_with('-') => Ok(request.args.clone()),
+            _ => Err("请指定 ll-cli 子命令".to_string()),
+        },
+        other => Err(format!("不支持的终端类型: {}", other)),
+    }
+}
+
+/// 把读取到的字节转换为字符串，末尾不完整的 UTF-8 序列留到下次拼接
+fn decode_utf8_chunk(pending: &mut Vec<u8>, chunk: &[u8]) -> String {
+    pending.extend_from_slice(chunk);
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        // 非法序列按替换字符输出
+        Err(_) => pending.len(),
+    };
+    let text = String::from_utf8_lossy(&pending[..valid]).to_string();
+    pending.drain(..valid);
+    text
+}
+
+fn pty_size(cols: u16, rows: u16) -> PtySize {
+    PtySize {
+        rows: if rows == 0 { 24 } else { rows },
+        cols: if cols == 0 { 80 } else { cols },
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
+/// 在伪终端中启动程序，输出与退出通过回调通知
+fn spawn_session<O, X>(
+    program: &str,
+    args: &[String],
+    size: PtySize,
+    on_output: O,
+    on_exit: X,
+) -> Result<Session, String>
+where
+    O: Fn(String) + Send + 'static,
+    X: FnOnce(Option<u32>) + Send + 'static,
+{
+    let pair = native_pty_system()
+        .openpty(size)
+        .map_err(|e| format!("创建伪终端失败: {}", e))?;
+
+    let mut cmd = CommandBuilder::new(program);
+    cmd.args(args);
+    cmd.env("TERM", "xterm-256color");
+    let mut child = pair
+        .slave
+        .spawn_command(cmd)
+        .map_err(|e| format!("启动 {} 失败: {}", program, e))?;
+    // 子进程已持有从端，关闭本地副本才能在其退出时读到 EOF
+    drop(pair.slave);
+
+    let mut reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("读取伪终端失败: {}", e))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| format!("写入伪终端失败: {}", e))?;
+    let killer = child.clone_killer();
+    let pid = child.process_id();
+
+    let reader_thread = std::thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        let mut pending = Vec::new();
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let text = decode_utf8_chunk(&mut pending, &buf[..n]);
+                    if !text.is_empty() {
+                        on_output(text);
+                    }
+                }
+            }
+        }
+    });
+    std::thread::spawn(move || {
+        let status = child.wait().ok();
+        // 输出读完后再通知退出，保证事件顺序
+        let _ = reader_thread.join();
+        on_exit(status.map(|s| s.exit_code()));
+    });
+
+    Ok(Session {
+        info: TerminalSession {
+            pid,
+            ..Default::default()
+        },
+        master: pair.master,
+        writer,
+        killer,
+    })
+}
+
+/// 打开终端会话
+pub async fn open_terminal_session(app_handle: AppHandle, request: TerminalRequest) -> Result<TerminalSession, String> {
+    let args = build_terminal_args(&request)?;
+    // 持有锁直到会话登记完成：数量检查与登记不会被其他调用穿插，
+    // 退出回调也只能在登记之后移除会话
+    let mut sessions = SESSIONS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if sessions.len() >= MAX_SESSIONS {
+        return Err(format!("最多同时打开 {} 个终端", MAX_SESSIONS));
+    }
+
+    let session_id = format!("term-{}", NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst));
+    let output_handle = app_handle.clone();
+    let output_id = session_id.clone();
+    let exit_id = session_id.clone();
+    let mut session = spawn_session(
+        "ll-cli",
+        &args,
+        pty_size(request.cols, request.rows),
+        move |data| {
+            let _ = output_handle.emit(
+                TERMINAL_OUTPUT_EVENT,
+                TerminalOutput {
+                    session_id: output_id.clone(),
+                    data,
+                },
+            );
+        },
+        move |exit_code| {
+            info!("[Terminal] Session {} exited with {:?}", exit_id, exit_code);
+            if let Ok(mut sessions) = SESSIONS.lock() {
+                sessions.remove(&exit_id);
+            }
+            let _ = app_handle.emit(
+                TERMINAL_EXIT_EVENT,
+                TerminalExit {
+                    session_id: exit_id,
+                    exit_code,
+                },
+            );
+        },
+    )?;
+
+    session.info.session_id = session_id.clone();
+    session.info.command = format!("ll-cli {}", args.join(" "));
+    let info = session.info.clone();
+    info!("[Terminal] Session {} opened: {}", session_id, info.command);
+    sessions.insert(session_id, session);
+    Ok(info)
+}
+
+fn with_session<T>(session_id: &str, f: impl FnOnce(&mut Session) -> Result<T, String>) -> Result<T, String> {
+    let mut sessions = SESSIONS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let session = sessions
+        .get_mut(session_id)
+        .ok_or_else(|| format!("终端会话不存在或已退出: {}", session_id))?;
+    f(session)
+}
+
+/// 向终端写入输入
+pub async fn write_terminal(session_id: String, data: String) -> Result<(), String> {
+    with_session(&session_id, |session| {
+        session
+            .writer
+            .write_all(data.as_bytes())
+            .and_then(|_| session.writer.flush())
+            .map_err(|e| format!("写入终端失败: {}", e))
+    })
+}
+
+/// 调整终端大小
+pub async fn resize_terminal(session_id: String, cols: u16, rows: u16) -> Result<(), String> {
+    with_session(&session_id, |session| {
+        session
+            .master
+            .resize(pty_size(cols, rows))
+            .map_err(|e| format!("调整终端大小失败: {}", e))
+    })
+}
+
+/// 关闭终端会话并结束其中的进程
+pub async fn close_terminal(session_id: String) -> Result<(), String> {
+    let session = SESSIONS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .remove(&session_id);
+    if let Some(mut session) = session {
+        if let Err(e) = session.killer.kill() {
+            warn!("[Terminal] Failed to kill session {}: {}", session_id, e);
+        }
+        info!("[Terminal] Session {} closed", session_id);
+    }
+    Ok(())
+}
+
+/// 列出已打开的终端会话
+pub async fn list_terminal_sessions() -> Result<Vec<TerminalSession>, String> {
+    let sessions = SESSIONS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let mut list: Vec<TerminalSession> = sessions.values().map(|s| s.info.clone()).collect();
+    list.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn request(kind: &str, target: Option<&str>, args: &[&str]) -> TerminalRequest {
+        TerminalRequest {
+            kind: kind.to_string(),
+            target: target.map(str::to_string),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_terminal_args() {
+        assert_eq!(
+            build_terminal_args(&request("exec", Some("org.a"), &[])).unwrap(),
+            vec!["exec", "org.a", "--", "bash"]
+        );
+        assert_eq!(
+            build_terminal_args(&request("exec", Some("org.a"), &["ls", "-la"])).unwrap(),
+            vec!["exec", "org.a", "--", "ls", "-la"]
+        );
+        assert_eq!(
+            build_terminal_args(&request("cli", None, &["list", "--upgradable"])).unwrap(),
+            vec!["list", "--upgradable"]
+        );
+        assert!(build_terminal_args(&request("exec", Some("--help"), &[])).is_err());
+        assert!(build_terminal_args(&request("enter", None, &[])).is_err());
+        assert!(build_terminal_args(&request("cli", None, &["--version"])).is_err());
+        assert!(build_terminal_args(&request("shell", None, &[])).is_err());
+    }
+
+    #[test]
+    fn test_decode_utf8_chunk() {
+        let bytes = "你好".as_bytes();
+        let mut pending = Vec::new();
+        assert_eq!(decode_utf8_chunk(&mut pending, &bytes[..4]), "你");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(decode_utf8_chunk(&mut pending, &bytes[4..]), "好");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_pty_session_round_trip() {
+        let (tx, rx) = mpsc::channel();
+        let (exit_tx, exit_rx) = mpsc::channel();
+        let args = vec!["-c".to_string(), "read line; echo got:$line".to_string()];
+        let mut session = spawn_session(
+            "sh",
+            &args,
+            pty_size(80, 24),
+            move |data| {
+                let _ = tx.send(data);
+            },
+            move |code| {
+                let _ = exit_tx.send(code);
+            },
+        )
+        .unwrap();
+        session.master.resize(pty_size(100, 30)).unwrap();
+        session.writer.write_all(b"hello\n").unwrap();
+
+        assert_eq!(exit_rx.recv_timeout(Duration::from_secs(5)).unwrap(), Some(0));
+        let output: String = rx.try_iter().collect();
+        assert!(output.contains("got:hello"), "{}", output);
+    }
+}
@@ -0,0 +1,361 @@
+//! 后台定时检查更新
+//!
+//! 由 Rust 端按配置的间隔调用 [`check_app_updates`]，窗口隐藏时同样运行。
+//! 发现尚未通知过的新版本时发送桌面通知，点击通知打开主窗口并跳转到更新页面。
+//! 已通知过的版本记录在数据目录中，重启后也不会重复通知。
+//! 开启自动更新时，检查完成后先执行自动更新，只通知未能自动更新的应用。
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+use crate::services::auto_update::{has_pending, load_policy, run_auto_update};
+use crate::services::updates::{check_app_updates, AppUpdateInfo};
+use crate::utils::paths::app_data_file;
+use crate::utils::time::now_millis;
+
+/// 调度配置文件
+const CONFIG_FILE: &str = "update-scheduler.json";
+/// 已通知版本记录文件
+const ANNOUNCED_FILE: &str = "announced-updates.json";
+/// 最短检查间隔（分钟）
+const MIN_INTERVAL_MINUTES: u64 = 15;
+/// 有待重试的自动更新时的检查间隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// 启动后首次检查前的等待时间，避免与启动流程争抢资源
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+/// 通知正文中最多列出的应用数量
+const NOTIFY_NAME_LIMIT: usize = 5;
+/// 前端页面跳转事件
+const NAVIGATE_EVENT: &str = "navigate";
+/// 后台检查完成事件
+const UPDATES_CHECKED_EVENT: &str = "app-updates-checked";
+
+/// 后台检查更新配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateSchedulerConfig {
+    pub enabled: bool,
+    /// 检查间隔（分钟）
+    pub interval_minutes: u64,
+    /// 是否发送桌面通知
+    pub notify: bool,
+}
+
+impl Default for UpdateSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            // 默认关闭，避免升级后在用户不知情时开始后台轮询和发送通知
+            enabled: false,
+            interval_minutes: 60,
+            notify: false,
+        }
+    }
+}
+
+/// 后台检查更新状态
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSchedulerStatus {
+    pub config: UpdateSchedulerConfig,
+    /// 上次检查时间（Unix 毫秒）
+    pub last_checked_at: Option<u64>,
+    /// 下次检查时间（Unix 毫秒）
+    pub next_check_at: Option<u64>,
+    /// 上次检查得到的可更新应用
+    pub last_updates: Vec<AppUpdateInfo>,
+    /// 上次检查中查询失败的应用
+    pub last_failed: Vec<String>,
+    pub last_error: Option<String>,
+}
+
+static STATUS: Lazy<Mutex<UpdateSchedulerStatus>> = Lazy::new(|| Mutex::new(UpdateSchedulerStatus::default()));
+
+/// 配置变更时唤醒调度循环
+static CONFIG_CHANGED: Lazy<Notify> = Lazy::new(Notify::new);
+
+fn read_config(path: &Path) -> UpdateSchedulerConfig {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn load_config() -> UpdateSchedulerConfig {
+    match app_data_file(CONFIG_FILE) {
+        Ok(path) => read_config(&path),
+        Err(e) => {
+            warn!("[UpdateScheduler] {}", e);
+            UpdateSchedulerConfig::default()
+        }
+    }
+}
+
+/// 通知去重使用的版本标识
+fn announce_key(update: &AppUpdateInfo) -> String {
+    format!(
+        "{}|{}|{}|{}|{}",
+        update.app_id, update.channel, update.arch, update.module, update.latest_version
+    )
+}
+
+fn read_announced(path: &Path) -> BTreeSet<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_announced(path: &Path, keys: &BTreeSet<String>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(keys).map_err(|e| format!("序列化通知记录失败: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("写入通知记录失败: {}", e))
+}
+
+/// 过滤出尚未通知过的更新，并返回新的通知记录
+///
+/// 记录只保留当前仍可更新的版本，已更新或被更新版本替代的记录会被移除。
+pub(crate) fn select_unannounced(
+    updates: &[AppUpdateInfo],
+    announced: &BTreeSet<String>,
+) -> (Vec<AppUpdateInfo>, BTreeSet<String>) {
+    let fresh = updates
+        .iter()
+        .filter(|update| !announced.contains(&announce_key(update)))
+        .cloned()
+        .collect();
+    let keys = updates.iter().map(announce_key).collect();
+    (fresh, keys)
+}
+
+/// 通知正文：列出前几个应用名称
+pub(crate) fn notification_body(updates: &[AppUpdateInfo]) -> String {
+    let mut names: Vec<&str> = updates.iter().map(|u| u.name.as_str()).collect();
+    names.dedup();
+    let mut body = names
+        .iter()
+        .take(NOTIFY_NAME_LIMIT)
+        .copied()
+        .collect::<Vec<_>>()
+        .join("、");
+    if names.len() > NOTIFY_NAME_LIMIT {
+        body.push_str(&format!(" 等 {} 个应用", names.len()));
+    }
+    body
+}
+
+/// 打开主窗口并跳转到更新页面
+fn open_updates_page(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    let _ = app_handle.emit(NAVIGATE_EVENT, "/update_apps");
+}
+
+/// 发送桌面通知，返回通知是否成功显示
+async fn send_notification(app_handle: &AppHandle, updates: &[AppUpdateInfo]) -> Result<(), String> {
+    let summary = format!("有 {} 个应用可以更新", updates.len());
+    let body = notification_body(updates);
+    let app_handle = app_handle.clone();
+    let (shown_tx, shown_rx) = tokio::sync::oneshot::channel();
+
+    // 等待通知点击会阻塞，放到独立线程中
+    std::thread::spawn(move || {
+        let handle = notify_rust::Notification::new()
+            .appname("玲珑应用商店社区版")
+            .summary(&summary)
+            .body(&body)
+            .icon("linglong-store")
+            .action("default", "查看更新")
+            .show();
+        match handle {
+            Ok(handle) => {
+                let _ = shown_tx.send(Ok(()));
+                handle.wait_for_action(|action| {
+                    if action == "default" {
+                        open_updates_page(&app_handle);
+                    }
+                });
+            }
+            Err(e) => {
+                let _ = shown_tx.send(Err(format!("显示通知失败: {}", e)));
+            }
+        }
+    });
+
+    shown_rx.await.map_err(|_| "通知线程意外退出".to_string())?
+}
+
+/// 执行一次后台检查
+async fn run_check(app_handle: &AppHandle, config: &UpdateSchedulerConfig) {
+    info!("[UpdateScheduler] Running scheduled update check");
+    let result = check_app_updates(None).await;
+
+    if let Ok(mut status) = STATUS.lock() {
+        status.last_checked_at = Some(now_millis());
+        match &result {
+            Ok(check) => {
+                status.last_updates = check.updates.clone();
+                status.last_failed = check.failed.clone();
+                status.last_error = None;
+            }
+            Err(e) => status.last_error = Some(e.clone()),
+        }
+    }
+
+    let updates = match result {
+        Ok(check) => check.updates,
+        Err(e) => {
+            warn!("[UpdateScheduler] Update check failed: {}", e);
+            return;
+        }
+    };
+    let _ = app_handle.emit(UPDATES_CHECKED_EVENT, &updates);
+
+    let mut updates = updates;
+    if load_policy().enabled {
+        match run_auto_update(&updates).await {
+            Ok(summary) => {
+                updates.retain(|u| !summary.updated.iter().any(|item| item.app_id == u.app_id));
+            }
+            Err(e) => warn!("[UpdateScheduler] Auto update skipped: {}", e),
+        }
+    }
+
+    let path = match app_data_file(ANNOUNCED_FILE) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("[UpdateScheduler] {}", e);
+            return;
+        }
+    };
+    let (fresh, keys) = select_unannounced(&updates, &read_announced(&path));
+
+    info!(
+        "[UpdateScheduler] {} updates, {} not announced yet",
+        updates.len(),
+        fresh.len()
+    );
+    // 只有通知真正发出后才记录，避免关闭通知期间的更新被标记为已通知
+    if config.enabled && config.notify && !fresh.is_empty() {
+        match send_notification(app_handle, &fresh).await {
+            Ok(()) => {
+                if let Err(e) = write_announced(&path, &keys) {
+                    warn!("[UpdateScheduler] {}", e);
+                }
+            }
+            Err(e) => warn!("[UpdateScheduler] {}", e),
+        }
+    }
+}
+
+/// 启动后台检查循环，在应用 setup 阶段调用
+pub fn start_update_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            let config = load_config();
+            // 自动更新依赖定时检查，开启自动更新时即使关闭了检查通知也照常运行
+            let active = config.enabled || load_policy().enabled;
+            let mut interval = Duration::from_secs(config.interval_minutes.max(MIN_INTERVAL_MINUTES) * 60);
+
+            if active {
+                run_check(&app_handle, &config).await;
+                if has_pending() {
+                    interval = interval.min(RETRY_INTERVAL);
+                }
+            }
+
+            if let Ok(mut status) = STATUS.lock() {
+                status.config = config.clone();
+                status.next_check_at = active.then(|| now_millis() + interval.as_millis() as u64);
+            }
+
+            // 配置变更时立即按新配置重新调度
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = CONFIG_CHANGED.notified() => {
+                    info!("[UpdateScheduler] Config changed, rescheduling");
+                }
+            }
+        }
+    });
+}
+
+/// 获取后台检查配置与最近一次检查状态
+pub async fn get_update_scheduler_status() -> Result<UpdateSchedulerStatus, String> {
+    let mut status = STATUS.lock().map_err(|e| format!("Lock error: {}", e))?.clone();
+    status.config = load_config();
+    Ok(status)
+}
+
+/// 保存后台检查配置，并立即按新配置重新调度
+pub async fn set_update_scheduler_config(config: UpdateSchedulerConfig) -> Result<UpdateSchedulerConfig, String> {
+    if config.interval_minutes < MIN_INTERVAL_MINUTES {
+        return Err(format!("检查间隔不能小于 {} 分钟", MIN_INTERVAL_MINUTES));
+    }
+    let path = app_data_file(CONFIG_FILE)?;
+    let content = serde_json::to_string_pretty(&config).map_err(|e| format!("序列化配置失败: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("保存配置失败: {}", e))?;
+    info!("[UpdateScheduler] Config saved: {:?}", config);
+    CONFIG_CHANGED.notify_one();
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(app_id: &str, name: &str, version: &str) -> AppUpdateInfo {
+        AppUpdateInfo::test(app_id, version).with_name(name)
+    }
+
+    #[test]
+    fn test_select_unannounced() {
+        let first = vec![update("org.a", "A", "1.1.0.0"), update("org.b", "B", "2.0.0.0")];
+        let (fresh, keys) = select_unannounced(&first, &BTreeSet::new());
+        assert_eq!(fresh.len(), 2);
+
+        // 同一版本不重复通知
+        let (fresh, keys) = select_unannounced(&first, &keys);
+        assert!(fresh.is_empty());
+
+        // 新版本重新通知，已完成更新的记录被移除
+        let second = vec![update("org.a", "A", "1.2.0.0")];
+        let (fresh, keys) = select_unannounced(&second, &keys);
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(keys.len(), 1);
+        assert!(keys.iter().next().unwrap().ends_with("1.2.0.0"));
+    }
+
+    #[test]
+    fn test_notification_body() {
+        let updates: Vec<AppUpdateInfo> = (0..7)
+            .map(|i| update(&format!("org.app{}", i), &format!("App{}", i), "1.1.0.0"))
+            .collect();
+        assert_eq!(notification_body(&updates[..2]), "App0、App1");
+        assert_eq!(notification_body(&updates), "App0、App1、App2、App3、App4 等 7 个应用");
+    }
+
+    #[test]
+    fn test_config_defaults_for_missing_fields() {
+        let dir = std::env::temp_dir().join(format!("update-scheduler-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+        fs::write(&path, r#"{"intervalMinutes": 120}"#).unwrap();
+        let config = read_config(&path);
+        assert_eq!(config.interval_minutes, 120);
+        assert!(!config.enabled);
+        assert!(!config.notify);
+        assert_eq!(read_config(&dir.join("missing.json")), UpdateSchedulerConfig::default());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,377 @@
+//! 应用更新检测
+//!
+//! 不依赖商店服务端接口，直接以 `ll-cli search --show-all-version` 查询已配置仓库，
+//! 按频道、架构、模块与仓库优先级和本地已安装列表对比得出可更新的应用，因此同样适用于私有仓库中的应用。
+//! 查询失败的应用会在结果中列出，而不是当作没有更新。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::services::install::{get_installed_apps, InstalledApp};
+use crate::services::linglong::{app_id_of, arch_list, module_of, run_search, SearchResultItem};
+use crate::services::repo::{read_repo_config, LinglongRepoConfig};
+use crate::services::version::LinglongVersion;
+
+/// 同时执行的 ll-cli search 数量上限
+const MAX_CONCURRENT_SEARCHES: usize = 4;
+
+/// 单个应用的更新信息
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdateInfo {
+    pub app_id: String,
+    pub name: String,
+    /// 当前已安装的版本
+    pub current_version: String,
+    /// 仓库中的最新版本
+    pub latest_version: String,
+    pub channel: String,
+    pub arch: String,
+    pub module: String,
+    /// 最新版本的来源仓库
+    pub repo_name: String,
+    pub description: Option<String>,
+}
+
+/// 测试用的更新信息构造器
+#[cfg(test)]
+impl AppUpdateInfo {
+    /// 从 1.0.0.0 更新到指定版本的 binary 模块
+    pub(crate) fn test(app_id: &str, latest_version: &str) -> Self {
+        AppUpdateInfo {
+            app_id: app_id.to_string(),
+            name: app_id.to_string(),
+            current_version: "1.0.0.0".to_string(),
+            latest_version: latest_version.to_string(),
+            channel: "main".to_string(),
+            arch: "x86_64".to_string(),
+            module: "binary".to_string(),
+            repo_name: "stable".to_string(),
+            description: None,
+        }
+    }
+
+    pub(crate) fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub(crate) fn with_module(mut self, module: &str) -> Self {
+        self.module = module.to_string();
+        self
+    }
+}
+
+/// 更新检查结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdateCheck {
+    pub updates: Vec<AppUpdateInfo>,
+    /// 查询失败、无法确定是否有更新的应用
+    pub failed: Vec<String>,
+}
+
+fn installed_module(app: &InstalledApp) -> &str {
+    if app.module.is_empty() {
+        "binary"
+    } else {
+        app.module.as_str()
+    }
+}
+
+fn installed_channel(app: &InstalledApp) -> &str {
+    if app.channel.is_empty() {
+        "main"
+    } else {
+        app.channel.as_str()
+    }
+}
+
+/// 远程条目是否与已安装应用的 appId、频道、架构、模块一致
+fn is_same_target(app: &InstalledApp, item: &SearchResultItem) -> bool {
+    let arch = arch_list(&item.arch);
+    app_id_of(item) == app.app_id
+        && item.channel.as_deref().unwrap_or("main") == installed_channel(app)
+        && module_of(item) == installed_module(app)
+        && (arch.is_empty() || app.arch.is_empty() || arch.contains(&app.arch))
+}
+
+/// 对比已安装应用与远程版本，得出可更新列表
+///
+/// 同一 appId/频道/架构/模块 安装了多个版本时以最高版本为准；
+/// 最新版本在多个仓库中都存在时取优先级最高的仓库。
+pub(crate) fn detect_updates(
+    installed: &[InstalledApp],
+    remote: &[SearchResultItem],
+    config: &LinglongRepoConfig,
+) -> Vec<AppUpdateInfo> {
+    let mut current: HashMap<(&str, &str, &str, &str), &InstalledApp> = HashMap::new();
+    for app in installed {
+        let key = (
+            app.app_id.as_str(),
+            installed_channel(app),
+            app.arch.as_str(),
+            installed_module(app),
+        );
+        let newer = current.get(&key).is_none_or(|existing| {
+            LinglongVersion::parse(&app.version) > LinglongVersion::parse(&existing.version)
+        });
+        if newer {
+            current.insert(key, app);
+        }
+    }
+
+    let mut updates: Vec<AppUpdateInfo> = current
+        .into_values()
+        .filter_map(|app| {
+            let current_version = LinglongVersion::parse(&app.version)?;
+            let (latest_version, latest) = remote
+                .iter()
+                .filter(|item| is_same_target(app, item))
+                .filter_map(|item| LinglongVersion::parse(&item.version).map(|v| (v, item)))
+                .max_by(|(va, a), (vb, b)| {
+                    va.cmp(vb).then_with(|| {
+                        config
+                            .rank_of(a.repo_name.as_deref().unwrap_or(""))
+                            .cmp(&config.rank_of(b.repo_name.as_deref().unwrap_or("")))
+                    })
+                })?;
+            if latest_version <= current_version {
+                return None;
+            }
+            Some(AppUpdateInfo {
+                app_id: app.app_id.clone(),
+                name: if latest.name.is_empty() { app.name.clone() } else { latest.name.clone() },
+                current_version: app.version.clone(),
+                latest_version: latest.version.clone(),
+                channel: installed_channel(app).to_string(),
+                arch: app.arch.clone(),
+                module: installed_module(app).to_string(),
+                repo_name: latest.repo_name.clone().unwrap_or_default(),
+                description: latest.description.clone(),
+            })
+        })
+        .collect();
+
+    updates.sort_by(|a, b| a.app_id.cmp(&b.app_id).then_with(|| a.module.cmp(&b.module)));
+    updates
+}
+
+/// 检查已安装应用的更新
+///
+/// # Arguments
+/// * `repos` - 指定检查的仓库列表，为空时检查所有已配置仓库
+pub async fn check_app_updates(repos: Option<Vec<String>>) -> Result<AppUpdateCheck, String> {
+    let installed = get_installed_apps(false).await?;
+    if installed.is_empty() {
+        return Ok(AppUpdateCheck::default());
+    }
+
+    // 仓库配置仅用于同版本时的来源选择，读取失败不影响更新检测
+    let config = read_repo_config().unwrap_or_else(|e| {
+        warn!("[AppUpdates] Failed to read repo config: {}", e);
+        LinglongRepoConfig::default()
+    });
+
+    let mut app_ids: Vec<String> = installed.iter().map(|app| app.app_id.clone()).collect();
+    app_ids.sort();
+    app_ids.dedup();
+
+    // 未指定仓库时一次搜索覆盖所有仓库，否则逐个仓库搜索
+    let repo_targets: Vec<Option<String>> = match repos.filter(|r| !r.is_empty()) {
+        Some(repos) => repos.into_iter().map(Some).collect(),
+        None => vec![None],
+    };
+
+    info!(
+        "[AppUpdates] Checking {} apps against {} repo target(s)",
+        app_ids.len(),
+        repo_targets.len()
+    );
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_SEARCHES));
+    let mut tasks = JoinSet::new();
+    for app_id in &app_ids {
+        for repo in &repo_targets {
+            let semaphore = semaphore.clone();
+            let app_id = app_id.clone();
+            let repo = repo.clone();
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let query = app_id.clone();
+                let result = tokio::task::spawn_blocking(move || run_search(&query, repo.as_deref(), true))
+                    .await
+                    .map_err(|e| format!("搜索任务执行失败: {}", e))
+                    .and_then(|r| r);
+                (app_id, result)
+            });
+        }
+    }
+
+    let mut remote = Vec::new();
+    let mut failed: Vec<String> = Vec::new();
+    let mut panicked = 0usize;
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((_, Ok(items))) => remote.extend(items),
+            Ok((app_id, Err(e))) => {
+                warn!("[AppUpdates] Search failed for {}: {}", app_id, e);
+                failed.push(app_id);
+            }
+            Err(e) => {
+                panicked += 1;
+                warn!("[AppUpdates] Search task panicked: {}", e);
+            }
+        }
+    }
+
+    // 全部搜索失败时多半是网络或仓库不可用，此时不应报告“无更新”
+    if (!failed.is_empty() || panicked > 0) && remote.is_empty() {
+        return Err("检查更新失败：无法从仓库获取版本信息".to_string());
+    }
+    failed.sort();
+    failed.dedup();
+
+    let updates = detect_updates(&installed, &remote, &config);
+    info!("[AppUpdates] Found {} updates, {} app(s) failed", updates.len(), failed.len());
+    Ok(AppUpdateCheck { updates, failed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::repo::LinglongRepo;
+
+    fn installed(app_id: &str, version: &str, channel: &str, module: &str) -> InstalledApp {
+        InstalledApp::test(app_id, version).with_channel(channel).with_module(module)
+    }
+
+    fn remote(app_id: &str, version: &str, channel: &str, module: &str, repo: &str) -> SearchResultItem {
+        SearchResultItem {
+            app_id: Some(app_id.to_string()),
+            name: app_id.to_string(),
+            version: version.to_string(),
+            arch: Some(serde_json::json!(["x86_64"])),
+            description: None,
+            module: Some(module.to_string()),
+            icon: None,
+            channel: Some(channel.to_string()),
+            repo_name: Some(repo.to_string()),
+        }
+    }
+
+    fn config() -> LinglongRepoConfig {
+        LinglongRepoConfig {
+            default_repo: Some("stable".to_string()),
+            repos: vec![
+                LinglongRepo {
+                    name: "stable".to_string(),
+                    url: "https://mirror-repo-linglong.deepin.com".to_string(),
+                    alias: None,
+                    priority: Some("0".to_string()),
+                },
+                LinglongRepo {
+                    name: "private".to_string(),
+                    url: "https://repo.example.com".to_string(),
+                    alias: None,
+                    priority: Some("10".to_string()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_detect_updates_honours_channel_and_module() {
+        let installed = vec![
+            installed("org.example.app", "1.0.0.9", "main", "binary"),
+            installed("org.example.app", "1.0.0.9", "main", "develop"),
+            installed("org.example.beta", "2.0.0.0", "beta", "binary"),
+        ];
+        let remote = vec![
+            remote("org.example.app", "1.0.0.10", "main", "binary", "stable"),
+            // 只有 develop 模块的旧版本，不应产生更新
+            remote("org.example.app", "1.0.0.8", "main", "develop", "stable"),
+            // 其他频道的新版本不应影响 beta 频道
+            remote("org.example.beta", "3.0.0.0", "main", "binary", "stable"),
+            remote("org.example.beta", "2.0.0.1", "beta", "binary", "stable"),
+        ];
+
+        let updates = detect_updates(&installed, &remote, &config());
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].app_id, "org.example.app");
+        assert_eq!(updates[0].module, "binary");
+        assert_eq!(updates[0].current_version, "1.0.0.9");
+        assert_eq!(updates[0].latest_version, "1.0.0.10");
+        assert_eq!(updates[1].app_id, "org.example.beta");
+        assert_eq!(updates[1].latest_version, "2.0.0.1");
+    }
+
+    #[test]
+    fn test_detect_updates_uses_highest_installed_and_repo_priority() {
+        let installed = vec![
+            installed("org.example.app", "1.0.0.0", "main", ""),
+            installed("org.example.app", "1.2.0.0", "main", ""),
+        ];
+        let mut other_arch = remote("org.example.app", "9.0.0.0", "main", "binary", "stable");
+        other_arch.arch = Some(serde_json::json!("arm64"));
+        let remote = vec![
+            remote("org.example.app", "1.2.0.0", "main", "binary", "stable"),
+            remote("org.example.app", "1.3.0.0", "main", "binary", "stable"),
+            remote("org.example.app", "1.3.0.0", "main", "binary", "private"),
+            other_arch,
+        ];
+
+        let updates = detect_updates(&installed, &remote, &config());
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].current_version, "1.2.0.0");
+        assert_eq!(updates[0].latest_version, "1.3.0.0");
+        assert_eq!(updates[0].repo_name, "private");
+    }
+
+    /// `ll-cli --json search --show-all-version` 在两个仓库中的输出
+    const SEARCH_FIXTURE: &str = r#"{
+        "stable": [
+            {"id": "org.example.app", "name": "app", "version": "1.0.0.9", "arch": ["x86_64"], "channel": "main", "module": "binary"},
+            {"id": "org.example.app", "name": "app", "version": "1.1.0.0", "arch": ["x86_64"], "channel": "main", "module": "binary"},
+            {"id": "org.example.app", "name": "app", "version": "1.1.0.0", "arch": ["x86_64"], "channel": "main", "module": "develop"},
+            {"id": "org.example.app", "name": "app", "version": "2.0.0.0", "arch": ["arm64"], "channel": "main", "module": "binary"},
+            {"id": "org.example.beta", "name": "beta", "version": "3.0.0.0", "arch": ["x86_64"], "channel": "main", "module": "binary"}
+        ],
+        "private": [
+            {"id": "org.example.app", "name": "app", "version": "1.1.0.0", "arch": ["x86_64"], "channel": "main", "module": "binary"},
+            {"id": "org.example.beta", "name": "beta", "version": "2.0.0.1", "arch": ["x86_64"], "channel": "beta", "module": "binary"}
+        ]
+    }"#;
+
+    #[test]
+    fn test_detect_updates_from_search_fixture() {
+        let installed = vec![
+            installed("org.example.app", "1.0.0.9", "main", "binary"),
+            installed("org.example.beta", "2.0.0.0", "beta", "binary"),
+        ];
+        let remote = crate::services::linglong::parse_search_items(SEARCH_FIXTURE, None).unwrap();
+
+        let updates = detect_updates(&installed, &remote, &config());
+        assert_eq!(updates.len(), 2);
+        // arm64 的 2.0 不属于本机架构，同版本取优先级更高的 private 仓库
+        assert_eq!(updates[0].app_id, "org.example.app");
+        assert_eq!(updates[0].latest_version, "1.1.0.0");
+        assert_eq!(updates[0].module, "binary");
+        assert_eq!(updates[0].repo_name, "private");
+        // beta 频道只比较 beta 频道的版本
+        assert_eq!(updates[1].app_id, "org.example.beta");
+        assert_eq!(updates[1].latest_version, "2.0.0.1");
+        assert_eq!(updates[1].channel, "beta");
+    }
+
+    #[test]
+    fn test_detect_updates_none_when_up_to_date() {
+        let installed = vec![installed("org.example.app", "1.3.0.0", "main", "binary")];
+        let remote = vec![remote("org.example.app", "1.3.0.0", "main", "binary", "stable")];
+        assert!(detect_updates(&installed, &remote, &config()).is_empty());
+    }
+}
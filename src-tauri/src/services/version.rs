@@ -0,0 +1,304 @@
+//! 玲珑版本号
+//!
+//! 玲珑应用版本一般为 4 段数字（如 `1.2.3.4`），ll-cli 及发行版软件包版本则可能带有后缀：
+//! - `-rc1`、`-beta.2`、`~rc1` 等以字母开头的后缀视为预发布版本，排在正式版之前；
+//! - `-1`、`-1deepin1` 等以数字开头的后缀视为修订版本，排在正式版之后；
+//! - `+` 之后的构建信息不参与比较。
+//!
+//! 数字段缺失时按 0 处理，因此 `1.9` 与 `1.9.0.0` 相等。
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// 版本后缀类型，顺序即排序先后
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SuffixKind {
+    Prerelease,
+    Release,
+    Revision,
+}
+
+#[derive(Debug, Clone)]
+pub struct LinglongVersion {
+    parts: Vec<u64>,
+    kind: SuffixKind,
+    /// 后缀按分隔符拆分后的标识
+    suffix: Vec<String>,
+    raw: String,
+}
+
+impl LinglongVersion {
+    /// 解析单个版本号，如 `1.2.3.4`、`1.9.9-1`、`v1.0.0-rc.1+build5`
+    pub fn parse(input: &str) -> Option<Self> {
+        let raw = input.trim();
+        let body = raw.strip_prefix(['v', 'V']).unwrap_or(raw);
+        let body = body.split('+').next().unwrap_or("");
+
+        let core_len = body
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(body.len());
+        let core = body[..core_len].trim_end_matches('.');
+        if core.is_empty() || !core.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let parts = core
+            .split('.')
+            .map(|p| p.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        let rest = &body[core.len()..];
+        let (kind, rest) = match rest.chars().next() {
+            None => (SuffixKind::Release, ""),
+            Some('~') => (SuffixKind::Prerelease, &rest[1..]),
+            Some('-' | '_' | '.') => {
+                let rest = &rest[1..];
+                if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                    (SuffixKind::Revision, rest)
+                } else {
+                    (SuffixKind::Prerelease, rest)
+                }
+            }
+            Some(_) => (SuffixKind::Prerelease, rest),
+        };
+        let suffix: Vec<String> = rest
+            .split(['.', '-', '_', '~'])
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        // 仅有分隔符没有内容时视为正式版
+        let kind = if suffix.is_empty() { SuffixKind::Release } else { kind };
+
+        Some(Self {
+            parts,
+            kind,
+            suffix,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// 从 ll-cli 等命令输出中提取首个版本号，如 `linyaps CLI version 1.9.9`、`{"version":"1.9.9"}`
+    pub fn extract(text: &str) -> Option<Self> {
+        text.split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | ':' | '{' | '}' | '(' | ')'))
+            .filter(|token| {
+                let token = token.strip_prefix(['v', 'V']).unwrap_or(token);
+                token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.')
+            })
+            .find_map(Self::parse)
+    }
+}
+
+/// 将标识拆成连续数字/非数字片段，数字按数值比较，且数字排在字母之前
+fn compare_identifier(a: &str, b: &str) -> Ordering {
+    fn chunks(s: &str) -> Vec<&str> {
+        let mut result = Vec::new();
+        let mut start = 0;
+        let bytes = s.as_bytes();
+        for i in 1..=bytes.len() {
+            if i == bytes.len() || bytes[i].is_ascii_digit() != bytes[start].is_ascii_digit() {
+                result.push(&s[start..i]);
+                start = i;
+            }
+        }
+        result
+    }
+
+    let (ca, cb) = (chunks(a), chunks(b));
+    for (x, y) in ca.iter().zip(cb.iter()) {
+        let x_num = x.starts_with(|c: char| c.is_ascii_digit());
+        let y_num = y.starts_with(|c: char| c.is_ascii_digit());
+        let ord = match (x_num, y_num) {
+            (true, true) => {
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => x.cmp(y),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    ca.len().cmp(&cb.len())
+}
+
+impl Ord for LinglongVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.parts.len().max(other.parts.len());
+        for i in 0..len {
+            let a = self.parts.get(i).copied().unwrap_or(0);
+            let b = other.parts.get(i).copied().unwrap_or(0);
+            match a.cmp(&b) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            }
+        }
+        self.kind.cmp(&other.kind).then_with(|| {
+            for (a, b) in self.suffix.iter().zip(other.suffix.iter()) {
+                match compare_identifier(a, b) {
+                    Ordering::Equal => continue,
+                    ord => return ord,
+                }
+            }
+            self.suffix.len().cmp(&other.suffix.len())
+        })
+    }
+}
+
+impl PartialOrd for LinglongVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for LinglongVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for LinglongVersion {}
+
+impl FromStr for LinglongVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("无法解析版本号: {}", s))
+    }
+}
+
+impl fmt::Display for LinglongVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// 比较两个版本字符串；无法解析的版本排在最前，均无法解析时按字符串比较
+pub fn compare_version_str(a: &str, b: &str) -> Ordering {
+    match (LinglongVersion::parse(a), LinglongVersion::parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn v(s: &str) -> LinglongVersion {
+        LinglongVersion::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(v("1.2.3.4").parts, vec![1, 2, 3, 4]);
+        assert_eq!(v("v1.0.0-rc.1+build5").parts, vec![1, 0, 0]);
+        assert_eq!(v("1.0.0-rc.1").kind, SuffixKind::Prerelease);
+        assert_eq!(v("1.9.9-1").kind, SuffixKind::Revision);
+        assert_eq!(v("1.9.9-1deepin1").to_string(), "1.9.9-1deepin1");
+        assert!(LinglongVersion::parse("").is_none());
+        assert!(LinglongVersion::parse("unknown").is_none());
+        assert!(LinglongVersion::parse(".1").is_none());
+    }
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(LinglongVersion::extract("linyaps CLI version 1.9.9").unwrap(), v("1.9.9"));
+        assert_eq!(LinglongVersion::extract("{\"version\":\"1.10.2-1\"}").unwrap().to_string(), "1.10.2-1");
+        assert!(LinglongVersion::extract("ll-cli 3 apps").is_none());
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(v("1.10.0") > v("1.9.9"));
+        assert!(v("1.0.0.10") > v("1.0.0.9"));
+        assert_eq!(v("1.9"), v("1.9.0.0"));
+        // 预发布 < 正式版 < 修订版
+        assert!(v("1.0.0-rc1") < v("1.0.0"));
+        assert!(v("1.0.0~beta") < v("1.0.0"));
+        assert!(v("1.0.0") < v("1.0.0-1"));
+        assert!(v("1.0.0-alpha") < v("1.0.0-beta"));
+        assert!(v("1.0.0-rc2") < v("1.0.0-rc10"));
+        assert!(v("1.0.0-1") < v("1.0.0-1deepin1"));
+        // 构建信息不参与比较
+        assert_eq!(v("1.0.0+abc"), v("1.0.0+def"));
+        assert_eq!(compare_version_str("garbage", "1.0"), Ordering::Less);
+    }
+
+    fn version_strategy() -> impl Strategy<Value = String> {
+        (
+            prop::collection::vec(0u64..1000, 1..5),
+            prop_oneof![
+                Just(String::new()),
+                "-(alpha|beta|rc)[0-9]{0,2}",
+                "-[0-9]{1,2}(deepin[0-9])?",
+                "~rc[0-9]",
+            ],
+            prop_oneof![Just(String::new()), "\\+[a-z0-9]{1,6}"],
+        )
+            .prop_map(|(parts, suffix, build)| {
+                let core = parts.iter().map(u64::to_string).collect::<Vec<_>>().join(".");
+                format!("{}{}{}", core, suffix, build)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_roundtrip(s in version_strategy()) {
+            let parsed = v(&s);
+            prop_assert_eq!(parsed.to_string(), s.clone());
+            prop_assert_eq!(v(&parsed.to_string()), parsed);
+        }
+
+        #[test]
+        fn prop_antisymmetric(a in version_strategy(), b in version_strategy()) {
+            prop_assert_eq!(v(&a).cmp(&v(&b)), v(&b).cmp(&v(&a)).reverse());
+        }
+
+        #[test]
+        fn prop_transitive(a in version_strategy(), b in version_strategy(), c in version_strategy()) {
+            let (a, b, c) = (v(&a), v(&b), v(&c));
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+        }
+
+        #[test]
+        fn prop_numeric_matches_tuple(a in prop::collection::vec(0u64..10000, 4), b in prop::collection::vec(0u64..10000, 4)) {
+            let join = |p: &[u64]| p.iter().map(u64::to_string).collect::<Vec<_>>().join(".");
+            prop_assert_eq!(v(&join(&a)).cmp(&v(&join(&b))), a.cmp(&b));
+        }
+
+        #[test]
+        fn prop_trailing_zeros_equal(parts in prop::collection::vec(0u64..1000, 1..4), zeros in 1usize..3) {
+            let core = parts.iter().map(u64::to_string).collect::<Vec<_>>().join(".");
+            let padded = format!("{}{}", core, ".0".repeat(zeros));
+            prop_assert_eq!(v(&core), v(&padded));
+        }
+
+        #[test]
+        fn prop_suffix_order(parts in prop::collection::vec(0u64..1000, 1..5), pre in "(alpha|beta|rc)[0-9]{0,2}", rev in 0u64..100) {
+            let core = parts.iter().map(u64::to_string).collect::<Vec<_>>().join(".");
+            let release = v(&core);
+            let prerelease = v(&format!("{}-{}", core, pre));
+            let revision = v(&format!("{}-{}", core, rev));
+            prop_assert!(prerelease < release);
+            prop_assert!(revision > release);
+        }
+
+        #[test]
+        fn prop_bump_increases(parts in prop::collection::vec(0u64..1000, 1..5), idx in 0usize..4, suffix in "(-rc1|-1)?") {
+            let idx = idx % parts.len();
+            let mut bumped = parts.clone();
+            bumped[idx] += 1;
+            let join = |p: &[u64]| p.iter().map(u64::to_string).collect::<Vec<_>>().join(".");
+            let newer = v(&format!("{}{}", join(&bumped), suffix));
+            let older = v(&format!("{}{}", join(&parts), suffix));
+            prop_assert!(newer > older);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! JSONL 记录文件
+//!
+//! 每行一条 JSON 记录，追加写入，超出上限时只保留最新的记录。
+//! 读取时跳过空行与无法解析的行，文件不存在视为没有记录。
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// 追加一条记录，超出 `limit` 条时只保留最新的 `limit` 条
+pub fn append<T: Serialize>(path: &Path, record: &T, limit: usize) -> Result<(), String> {
+    let line = serde_json::to_string(record).map_err(|e| format!("序列化记录失败: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("打开 {:?} 失败: {}", path, e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("写入 {:?} 失败: {}", path, e))?;
+    drop(file);
+
+    let content = fs::read_to_string(path).map_err(|e| format!("读取 {:?} 失败: {}", path, e))?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() > limit {
+        let kept = lines[lines.len() - limit..].join("\n") + "\n";
+        fs::write(path, kept).map_err(|e| format!("写入 {:?} 失败: {}", path, e))?;
+    }
+    Ok(())
+}
+
+/// 读取全部记录，按写入顺序排列
+pub fn read<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取 {:?} 失败: {}", path, e))?;
+    Ok(content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_trims_to_limit() {
+        let path = std::env::temp_dir().join(format!("jsonl-test-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert!(read::<u32>(&path).unwrap().is_empty());
+
+        for i in 0..5u32 {
+            append(&path, &i, 3).unwrap();
+        }
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+        assert_eq!(read::<u32>(&path).unwrap(), vec![2, 3, 4]);
+        let _ = fs::remove_file(&path);
+    }
+}
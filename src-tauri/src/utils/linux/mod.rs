@@ -1 +1,2 @@
+pub mod proc;
 pub mod workarounds;
@@ -0,0 +1,186 @@
+//! /proc 读取辅助函数
+//!
+//! 用于遍历进程树、判断进程存活、读取资源占用等，仅适用于 Linux。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 返回 /proc/<pid>/stat 中进程名之后的字段（从 state 开始）
+///
+/// 进程名可能包含空格和括号，因此以最后一个 ')' 作为分隔。
+pub fn parse_stat_fields(stat: &str) -> Option<Vec<&str>> {
+    let end = stat.rfind(')')?;
+    Some(stat[end + 1..].split_whitespace().collect())
+}
+
+/// 读取进程的 stat 文件
+pub fn read_stat(pid: u32) -> Option<String> {
+    fs::read_to_string(format!("/proc/{}/stat", pid)).ok()
+}
+
+/// 读取父进程 PID
+pub fn read_ppid(pid: u32) -> Option<u32> {
+    let stat = read_stat(pid)?;
+    // 字段依次为 state, ppid, ...
+    parse_stat_fields(&stat)?.get(1)?.parse().ok()
+}
+
+/// 列出当前所有进程的 PID
+pub fn list_pids() -> Vec<u32> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+        .collect()
+}
+
+/// 进程是否存活（已退出或僵尸进程均视为不存活）
+pub fn is_alive(pid: u32) -> bool {
+    if !Path::new(&format!("/proc/{}", pid)).exists() {
+        return false;
+    }
+    match read_stat(pid).as_deref().and_then(parse_stat_fields) {
+        Some(fields) => fields.first() != Some(&"Z"),
+        None => false,
+    }
+}
+
+/// 获取以 root 为根的进程树，root 在前，子孙进程按层次顺序排列
+pub fn process_tree(root: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for pid in list_pids() {
+        if let Some(ppid) = read_ppid(pid) {
+            children.entry(ppid).or_default().push(pid);
+        }
+    }
+
+    let mut tree = vec![root];
+    let mut index = 0;
+    while index < tree.len() {
+        if let Some(kids) = children.get(&tree[index]) {
+            for kid in kids {
+                if !tree.contains(kid) {
+                    tree.push(*kid);
+                }
+            }
+        }
+        index += 1;
+    }
+    tree
+}
+
+/// 单个进程的资源计数
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcUsage {
+    /// 用户态 + 内核态 CPU 时间（时钟节拍）
+    pub cpu_ticks: u64,
+    pub rss_bytes: u64,
+    /// 需读取 smaps_rollup，无权限或内核不支持时为 None
+    pub pss_bytes: Option<u64>,
+    pub threads: u64,
+    /// 需读取 /proc/<pid>/io，无权限时为 None
+    pub read_bytes: Option<u64>,
+    pub write_bytes: Option<u64>,
+}
+
+/// 每秒时钟节拍数（CLK_TCK）
+pub fn clock_ticks_per_second() -> u64 {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as u64
+    } else {
+        100
+    }
+}
+
+fn page_size() -> u64 {
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 {
+        size as u64
+    } else {
+        4096
+    }
+}
+
+/// 读取 `Key: <value> kB` 或 `key: <value>` 形式文件中的数值
+pub fn parse_kv_number(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        if k.trim() != key {
+            return None;
+        }
+        v.split_whitespace().next()?.parse().ok()
+    })
+}
+
+/// 读取进程的 CPU 时间、内存、线程数与 I/O 计数，进程不存在时返回 None
+pub fn read_usage(pid: u32) -> Option<ProcUsage> {
+    let stat = read_stat(pid)?;
+    let fields = parse_stat_fields(&stat)?;
+    // 以 state 为第 0 个字段：utime=11, stime=12, num_threads=17, rss=21（页）
+    let field = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok()).unwrap_or(0);
+
+    let mut usage = ProcUsage {
+        cpu_ticks: field(11) + field(12),
+        rss_bytes: field(21) * page_size(),
+        threads: field(17),
+        ..Default::default()
+    };
+
+    if let Ok(rollup) = fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)) {
+        usage.pss_bytes = parse_kv_number(&rollup, "Pss").map(|kb| kb * 1024);
+        if let Some(rss_kb) = parse_kv_number(&rollup, "Rss") {
+            usage.rss_bytes = rss_kb * 1024;
+        }
+    }
+    if let Ok(io) = fs::read_to_string(format!("/proc/{}/io", pid)) {
+        usage.read_bytes = parse_kv_number(&io, "read_bytes");
+        usage.write_bytes = parse_kv_number(&io, "write_bytes");
+    }
+    Some(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_fields() {
+        let stat = "1234 (Web Content (x)) S 1 1234 1234 0 -1 4194560 100 0 0 0 7 3 0 0 20 0 12 0";
+        let fields = parse_stat_fields(stat).unwrap();
+        assert_eq!(fields[0], "S");
+        assert_eq!(fields[1], "1");
+        assert!(parse_stat_fields("garbage").is_none());
+    }
+
+    #[test]
+    fn test_parse_kv_number() {
+        let rollup = "55d0c0a00000-7ffd [rollup]\nRss:               10240 kB\nPss:                4096 kB\n";
+        assert_eq!(parse_kv_number(rollup, "Pss"), Some(4096));
+        assert_eq!(parse_kv_number("read_bytes: 512\nwrite_bytes: 0\n", "read_bytes"), Some(512));
+        assert_eq!(parse_kv_number(rollup, "Swap"), None);
+    }
+
+    #[test]
+    fn test_process_tree_includes_children() {
+        let mut child = std::process::Command::new("sleep").arg("5").spawn().unwrap();
+        let me = std::process::id();
+        assert_eq!(read_ppid(child.id()), Some(me));
+        assert!(is_alive(child.id()));
+
+        let tree = process_tree(me);
+        assert_eq!(tree[0], me);
+        assert!(tree.contains(&child.id()));
+
+        let usage = read_usage(child.id()).unwrap();
+        assert!(usage.threads >= 1);
+        assert!(usage.rss_bytes > 0);
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(!is_alive(child.id()));
+    }
+}
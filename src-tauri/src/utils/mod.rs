@@ -1 +1,4 @@
+pub mod jsonl;
 pub mod linux;
+pub mod paths;
+pub mod time;
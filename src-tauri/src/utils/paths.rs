@@ -0,0 +1,117 @@
+//! 商店自身的数据目录
+//!
+//! 与 tauri-plugin-log 使用的目录一致：`$XDG_DATA_HOME/<identifier>`，
+//! 未设置时为 `~/.local/share/<identifier>`。
+
+use std::path::{Path, PathBuf};
+
+/// 与 tauri.conf.json 中的 identifier 保持一致
+const APP_IDENTIFIER: &str = "com.dongpl.linglong-store.v2";
+
+/// 用户主目录
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 商店数据目录（不保证已存在）
+pub fn app_data_dir() -> PathBuf {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(".local/share")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join(APP_IDENTIFIER)
+}
+
+/// 商店数据目录下的文件路径，并确保父目录存在
+pub fn app_data_file(name: &str) -> Result<PathBuf, String> {
+    let dir = app_data_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建数据目录失败 {:?}: {}", dir, e))?;
+    Ok(dir.join(name))
+}
+
+/// 商店数据目录下仅当前用户可访问（0700）的子目录，并确保其存在
+pub fn app_private_dir(name: &str) -> Result<PathBuf, String> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let dir = app_data_dir().join(name);
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|e| format!("创建目录失败 {:?}: {}", dir, e))?;
+    // 拒绝符号链接，避免被重定向到其他位置
+    let meta = std::fs::symlink_metadata(&dir).map_err(|e| format!("读取目录信息失败 {:?}: {}", dir, e))?;
+    if !meta.is_dir() {
+        return Err(format!("{:?} 不是目录", dir));
+    }
+    // 目录可能已存在且权限较宽，统一收紧
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))
+        .map_err(|e| format!("设置目录权限失败 {:?}: {}", dir, e))?;
+    Ok(dir)
+}
+
+/// 解析 `~/.config/user-dirs.dirs` 中的目录配置，如 `XDG_DESKTOP_DIR="$HOME/桌面"`
+fn parse_user_dir(content: &str, key: &str, home: &Path) -> Option<PathBuf> {
+    content.lines().find_map(|line| {
+        let (k, v) = line.trim().split_once('=')?;
+        if k.trim() != key {
+            return None;
+        }
+        let value = v.trim().trim_matches('"');
+        let path = match value.strip_prefix("$HOME") {
+            Some(rest) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(value),
+        };
+        path.is_absolute().then_some(path)
+    })
+}
+
+/// 用户配置目录 `$XDG_CONFIG_HOME`，默认为 `~/.config`
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(".config")))
+}
+
+/// 用户桌面目录，优先读取 XDG 用户目录配置，默认为 `~/Desktop`
+pub fn desktop_dir() -> Option<PathBuf> {
+    let home = home_dir()?;
+    let config = config_dir()?;
+    std::fs::read_to_string(config.join("user-dirs.dirs"))
+        .ok()
+        .and_then(|content| parse_user_dir(&content, "XDG_DESKTOP_DIR", &home))
+        .or_else(|| Some(home.join("Desktop")))
+}
+
+/// 用户级应用菜单目录 `$XDG_DATA_HOME/applications`
+pub fn user_applications_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(".local/share")))
+        .map(|base| base.join("applications"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_dir() {
+        let content = "# comment\nXDG_DOWNLOAD_DIR=\"$HOME/下载\"\nXDG_DESKTOP_DIR=\"$HOME/桌面\"\n";
+        let home = Path::new("/home/user");
+        assert_eq!(
+            parse_user_dir(content, "XDG_DESKTOP_DIR", home),
+            Some(PathBuf::from("/home/user/桌面"))
+        );
+        assert_eq!(
+            parse_user_dir("XDG_DESKTOP_DIR=\"/data/desk\"", "XDG_DESKTOP_DIR", home),
+            Some(PathBuf::from("/data/desk"))
+        );
+        assert_eq!(parse_user_dir(content, "XDG_MUSIC_DIR", home), None);
+    }
+}
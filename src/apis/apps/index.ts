@@ -154,16 +154,6 @@ export const uploadLog = (file: File) => {
   return upload<API.Common.BaseResponse<string>>('/app/uploadLog', file)
 }
 
-/**
- * 获取组件源执行脚本
- * @returns Shell脚本字符串
- */
-export const findShellString = () => {
-  return get<API.Common.BaseResponse<API.APP.GetShellStringRes>>(
-    '/app/findShellString',
-  )
-}
-
 /**
  * 修改组件源执行脚本
  * @param data 配置数据
@@ -13,6 +13,38 @@ export const getRunningLinglongApps = async() => {
   return await invoke('get_running_linglong_apps')
 }
 
+/**
+ * 监听应用启动事件（后端监视器发现新的运行容器）
+ * @param callback - 回调函数
+ * @returns Promise<UnlistenFn> 取消监听函数
+ */
+export const onAppStarted = async(
+  callback: (event: API.INVOKE.AppRunEvent) => void,
+): Promise<UnlistenFn> => {
+  return await listen<API.INVOKE.AppRunEvent>(
+    'app-started',
+    (event) => {
+      callback(event.payload)
+    },
+  )
+}
+
+/**
+ * 监听应用退出事件
+ * @param callback - 回调函数，参数包含运行时长与退出码（仅商店启动的应用）
+ * @returns Promise<UnlistenFn> 取消监听函数
+ */
+export const onAppExited = async(
+  callback: (event: API.INVOKE.AppRunEvent) => void,
+): Promise<UnlistenFn> => {
+  return await listen<API.INVOKE.AppRunEvent>(
+    'app-exited',
+    (event) => {
+      callback(event.payload)
+    },
+  )
+}
+
 /**
  * 终止指定玲珑应用的运行
  * @param appName - 要终止的应用名称
@@ -22,6 +54,65 @@ export const killLinglongApp = async(appName: string) => {
   return await invoke('kill_linglong_app', { appName })
 }
 
+/**
+ * 停止玲珑应用：先发送 SIGTERM，宽限期内未退出再强制结束
+ * @param target - 应用 ID 或容器 ID
+ * @param graceMs - 等待应用自行退出的时间（毫秒），默认 5000
+ * @returns Promise<API.INVOKE.StopAppResult> 停止结果，包含最终生效的信号
+ */
+export const stopLinglongApp = async(target: string, graceMs?: number): Promise<API.INVOKE.StopAppResult> => {
+  return await invoke('stop_linglong_app_cmd', { target, graceMs: graceMs || null })
+}
+
+/**
+ * 停止所有运行中的玲珑应用
+ * @param graceMs - 等待应用自行退出的时间（毫秒），默认 5000
+ * @returns Promise<API.INVOKE.StopAppResult[]> 每个应用的停止结果
+ */
+export const stopAllLinglongApps = async(graceMs?: number): Promise<API.INVOKE.StopAppResult[]> => {
+  return await invoke('stop_all_linglong_apps_cmd', { graceMs: graceMs || null })
+}
+
+/**
+ * 获取运行中玲珑应用的 CPU、内存与磁盘读写占用
+ * @param windowMs - CPU 采样窗口（毫秒），默认 1000
+ * @returns Promise<API.INVOKE.AppResourceUsage[]> 每个运行中应用的资源占用
+ */
+export const getAppResourceUsage = async(windowMs?: number): Promise<API.INVOKE.AppResourceUsage[]> => {
+  return await invoke('get_app_resource_usage_cmd', { windowMs: windowMs || null })
+}
+
+/**
+ * 开始持续监控运行中应用的资源占用，结果通过 onAppResourceUsage 推送
+ * @param intervalMs - 采样间隔（毫秒），最小 200，默认 1000
+ */
+export const startResourceMonitor = async(intervalMs?: number): Promise<void> => {
+  return await invoke('start_resource_monitor_cmd', { intervalMs: intervalMs || null })
+}
+
+/**
+ * 停止资源占用监控
+ */
+export const stopResourceMonitor = async(): Promise<void> => {
+  return await invoke('stop_resource_monitor_cmd')
+}
+
+/**
+ * 监听资源占用采样结果
+ * @param callback - 每个采样周期回调一次
+ * @returns Promise<UnlistenFn> 取消监听函数
+ */
+export const onAppResourceUsage = async(
+  callback: (usage: API.INVOKE.AppResourceUsage[]) => void,
+): Promise<UnlistenFn> => {
+  return await listen<API.INVOKE.AppResourceUsage[]>(
+    'app-resource-usage',
+    (event) => {
+      callback(event.payload)
+    },
+  )
+}
+
 /**
  * 获取已安装的玲珑应用列表
  * @param includeBaseService - 是否包含基础服务
@@ -33,15 +124,65 @@ export const getInstalledLinglongApps = async(includeBaseService = false): Promi
 
 /**
  * 卸载指定版本的应用
+ * 默认会检查依赖，卸载后有其他应用无法运行时拒绝卸载
  * @param appId - 要卸载的应用ID
  * @param version - 要卸载的应用版本
+ * @param force - 跳过依赖检查
  * @returns Promise<string> 卸载操作的结果
  */
 export const uninstallApp = async(
   appId: string,
   version: string,
+  force?: boolean,
 ): Promise<string> => {
-  return await invoke('uninstall_app', { appId, version })
+  return await invoke('uninstall_app', { appId, version, force: force || null })
+}
+
+/**
+ * 检查卸载指定版本是否会导致其他应用无法运行
+ * @param appId - 应用ID
+ * @param version - 应用版本
+ * @returns Promise<UninstallCheck> 检查结果，包含受影响的应用
+ */
+export const checkUninstall = async(
+  appId: string,
+  version: string,
+): Promise<API.INVOKE.UninstallCheck> => {
+  return await invoke('check_uninstall_cmd', { appId, version })
+}
+
+/**
+ * 获取已安装应用、运行时与基础环境的依赖图
+ * @returns Promise<DependencyGraph> 依赖图
+ */
+export const getDependencyGraph = async(): Promise<API.INVOKE.DependencyGraph> => {
+  return await invoke('get_dependency_graph_cmd')
+}
+
+/**
+ * 查询依赖指定应用的应用和运行时（包括间接依赖）
+ * @param appId - 应用ID
+ * @param version - 应用版本，不传时包含所有已安装版本
+ * @returns Promise<DependencyNode[]> 依赖该应用的节点
+ */
+export const getAppDependents = async(
+  appId: string,
+  version?: string,
+): Promise<API.INVOKE.DependencyNode[]> => {
+  return await invoke('get_app_dependents_cmd', { appId, version: version || null })
+}
+
+/**
+ * 查询指定应用需要的运行时与基础环境（包括间接依赖）
+ * @param appId - 应用ID
+ * @param version - 应用版本，不传时包含所有已安装版本
+ * @returns Promise<DependencyNode[]> 该应用依赖的节点
+ */
+export const getAppDependencies = async(
+  appId: string,
+  version?: string,
+): Promise<API.INVOKE.DependencyNode[]> => {
+  return await invoke('get_app_dependencies_cmd', { appId, version: version || null })
 }
 
 /**
@@ -58,12 +199,190 @@ export const searchVersions = async(
 /**
  * 运行指定的玲珑应用
  * @param appId - 要运行的应用ID
+ * @param options - 可选的启动选项：版本、应用参数、打开的文件/URL、环境变量
  * @returns Promise<string> 运行操作的结果
  */
 export const runApp = async(
   appId: string,
+  options?: API.INVOKE.RunAppOptions,
 ): Promise<string> => {
-  return await invoke('run_app', { appId })
+  return await invoke('run_app', { appId, options: options || null })
+}
+
+/**
+ * 监听应用启动失败事件（ll-cli run 在启动后短时间内异常退出）
+ * @param callback - 回调函数，参数包含退出码与启动输出
+ * @returns Promise<UnlistenFn> 取消监听函数
+ */
+export const onAppLaunchFailed = async(
+  callback: (failed: API.INVOKE.AppLaunchFailed) => void,
+): Promise<UnlistenFn> => {
+  return await listen<API.INVOKE.AppLaunchFailed>(
+    'app-launch-failed',
+    (event) => {
+      callback(event.payload)
+    },
+  )
+}
+
+/**
+ * 获取应用启动日志列表
+ * @param appId - 可选，仅返回该应用的日志
+ * @returns Promise<API.INVOKE.LaunchLogEntry[]> 按启动时间倒序
+ */
+export const getLaunchLogs = async(appId?: string): Promise<API.INVOKE.LaunchLogEntry[]> => {
+  return await invoke('get_launch_logs_cmd', { appId: appId || null })
+}
+
+/**
+ * 读取启动日志内容
+ * @param name - getLaunchLogs 返回的日志文件名
+ * @returns Promise<string> 日志内容（过大时仅包含末尾部分）
+ */
+export const readLaunchLog = async(name: string): Promise<string> => {
+  return await invoke('read_launch_log_cmd', { name })
+}
+
+/**
+ * 获取应用导出的桌面入口及校验结果
+ * @param appIds - 可选，要检查的应用；为空时检查所有已安装应用
+ * @returns Promise<API.INVOKE.DesktopEntryInfo[]> 桌面入口列表，problems 为空表示正常
+ */
+export const listDesktopEntries = async(appIds?: string[]): Promise<API.INVOKE.DesktopEntryInfo[]> => {
+  return await invoke('list_desktop_entries_cmd', { appIds: appIds || null })
+}
+
+/**
+ * 在用户桌面创建应用快捷方式
+ * @param appId - 应用ID
+ * @returns Promise<string[]> 创建的快捷方式路径
+ */
+export const createDesktopShortcut = async(appId: string): Promise<string[]> => {
+  return await invoke('create_desktop_shortcut_cmd', { appId })
+}
+
+/**
+ * 删除用户桌面上的应用快捷方式
+ * @param appId - 应用ID
+ * @returns Promise<string[]> 删除的快捷方式路径
+ */
+export const removeDesktopShortcut = async(appId: string): Promise<string[]> => {
+  return await invoke('remove_desktop_shortcut_cmd', { appId })
+}
+
+/**
+ * 补全应用缺失的菜单项（写入用户级应用目录）
+ * @param appId - 应用ID
+ * @returns Promise<API.INVOKE.DesktopRepairReport> 修复结果
+ */
+export const repairDesktopEntries = async(appId: string): Promise<API.INVOKE.DesktopRepairReport> => {
+  return await invoke('repair_desktop_entries_cmd', { appId })
+}
+
+/**
+ * 获取会在登录时自启动的玲珑应用
+ * @returns Promise<API.INVOKE.AutostartEntry[]> 自启动应用列表
+ */
+export const listAutostartApps = async(): Promise<API.INVOKE.AutostartEntry[]> => {
+  return await invoke('list_autostart_apps_cmd')
+}
+
+/**
+ * 开启应用自启动
+ * @param appId - 应用ID
+ * @param options - 可选，延迟秒数、是否最小化启动与附加参数
+ * @returns Promise<API.INVOKE.AutostartEntry> 写入的自启动项
+ */
+export const enableAutostart = async(
+  appId: string,
+  options?: API.INVOKE.AutostartOptions,
+): Promise<API.INVOKE.AutostartEntry> => {
+  return await invoke('enable_autostart_cmd', { appId, options: options || null })
+}
+
+/**
+ * 关闭应用自启动
+ * @param appId - 应用ID
+ * @returns Promise<string[]> 删除的自启动文件
+ */
+export const disableAutostart = async(appId: string): Promise<string[]> => {
+  return await invoke('disable_autostart_cmd', { appId })
+}
+
+/**
+ * 打开内置终端会话
+ * @param request - exec：在应用容器中执行命令（默认 bash）；enter：进入容器；cli：运行 ll-cli 子命令
+ * @returns Promise<API.INVOKE.TerminalSession> 会话信息
+ */
+export const openTerminalSession = async(request: API.INVOKE.TerminalRequest): Promise<API.INVOKE.TerminalSession> => {
+  return await invoke('open_terminal_session_cmd', { request })
+}
+
+/**
+ * 向终端写入输入
+ * @param sessionId - 会话ID
+ * @param data - 输入内容（包含换行、控制字符等）
+ */
+export const writeTerminal = async(sessionId: string, data: string): Promise<void> => {
+  return await invoke('write_terminal_cmd', { sessionId, data })
+}
+
+/**
+ * 调整终端大小
+ * @param sessionId - 会话ID
+ * @param cols - 列数
+ * @param rows - 行数
+ */
+export const resizeTerminal = async(sessionId: string, cols: number, rows: number): Promise<void> => {
+  return await invoke('resize_terminal_cmd', { sessionId, cols, rows })
+}
+
+/**
+ * 关闭终端会话并结束其中的进程
+ * @param sessionId - 会话ID
+ */
+export const closeTerminal = async(sessionId: string): Promise<void> => {
+  return await invoke('close_terminal_cmd', { sessionId })
+}
+
+/**
+ * 获取已打开的终端会话
+ * @returns Promise<API.INVOKE.TerminalSession[]> 会话列表
+ */
+export const listTerminalSessions = async(): Promise<API.INVOKE.TerminalSession[]> => {
+  return await invoke('list_terminal_sessions_cmd')
+}
+
+/**
+ * 监听终端输出
+ * @param callback - 回调函数
+ * @returns Promise<UnlistenFn> 取消监听函数
+ */
+export const onTerminalOutput = async(
+  callback: (output: API.INVOKE.TerminalOutput) => void,
+): Promise<UnlistenFn> => {
+  return await listen<API.INVOKE.TerminalOutput>(
+    'terminal-output',
+    (event) => {
+      callback(event.payload)
+    },
+  )
+}
+
+/**
+ * 监听终端进程退出
+ * @param callback - 回调函数
+ * @returns Promise<UnlistenFn> 取消监听函数
+ */
+export const onTerminalExit = async(
+  callback: (exit: API.INVOKE.TerminalExit) => void,
+): Promise<UnlistenFn> => {
+  return await listen<API.INVOKE.TerminalExit>(
+    'terminal-exit',
+    (event) => {
+      callback(event.payload)
+    },
+  )
 }
 
 /**
@@ -134,12 +453,111 @@ export const onInstallProgress = async(
 /**
  * 搜索远程应用
  * @param appId - 应用ID
- * @returns Promise<SearchResultItem[]> 搜索结果
+ * @param repos - 可选，指定搜索的仓库名称列表，不传则搜索所有已配置仓库
+ * @returns Promise<SearchResultItem[]> 按匹配程度排序、按仓库优先级去重后的搜索结果
  */
 export const searchRemoteApp = async(
   appId: string,
+  repos?: string[],
 ): Promise<API.INVOKE.SearchResultItem[]> => {
-  return await invoke('search_remote_app_cmd', { appId })
+  return await invoke('search_remote_app_cmd', { appId, repos: repos || null })
+}
+
+/**
+ * 获取应用在远程仓库中的所有可用版本
+ * @param appId - 应用ID
+ * @returns Promise<RemoteAppVersion[]> 按版本从新到旧排序的版本列表，标注来源仓库、频道、架构及是否已安装
+ */
+export const getRemoteAppVersions = async(
+  appId: string,
+): Promise<API.INVOKE.RemoteAppVersion[]> => {
+  return await invoke('get_remote_app_versions_cmd', { appId })
+}
+
+/**
+ * 由后端直接对比本地已安装应用与仓库中的版本，检查可更新应用
+ * 不依赖商店服务端接口，适用于私有仓库中的应用
+ * @param repos - 可选，指定检查的仓库名称列表，不传则检查所有已配置仓库
+ * @returns Promise<AppUpdateCheck> 可更新应用列表（按频道、架构、模块分别比较）及查询失败的应用
+ */
+export const checkAppUpdates = async(
+  repos?: string[],
+): Promise<API.INVOKE.AppUpdateCheck> => {
+  return await invoke('check_app_updates_cmd', { repos: repos || null })
+}
+
+/**
+ * 获取后台定时检查更新的配置与最近一次检查状态
+ * @returns Promise<UpdateSchedulerStatus>
+ */
+export const getUpdateSchedulerStatus = async(): Promise<API.INVOKE.UpdateSchedulerStatus> => {
+  return await invoke('get_update_scheduler_status_cmd')
+}
+
+/**
+ * 保存后台定时检查更新的配置，保存后立即按新配置重新调度
+ * @param config - 调度配置，检查间隔不能小于 15 分钟
+ * @returns Promise<UpdateSchedulerConfig> 保存后的配置
+ */
+export const setUpdateSchedulerConfig = async(
+  config: API.INVOKE.UpdateSchedulerConfig,
+): Promise<API.INVOKE.UpdateSchedulerConfig> => {
+  return await invoke('set_update_scheduler_config_cmd', { config })
+}
+
+/**
+ * 获取自动更新策略
+ * @returns Promise<AutoUpdatePolicy>
+ */
+export const getAutoUpdatePolicy = async(): Promise<API.INVOKE.AutoUpdatePolicy> => {
+  return await invoke('get_auto_update_policy_cmd')
+}
+
+/**
+ * 保存自动更新策略
+ * 开启后由后台定时检查自动安装可用更新，运行中的应用会跳过并稍后重试
+ * @param policy - 自动更新策略，同一应用不能同时出现在 include 与 exclude 中
+ * @returns Promise<AutoUpdatePolicy> 保存后的策略
+ */
+export const setAutoUpdatePolicy = async(
+  policy: API.INVOKE.AutoUpdatePolicy,
+): Promise<API.INVOKE.AutoUpdatePolicy> => {
+  return await invoke('set_auto_update_policy_cmd', { policy })
+}
+
+/**
+ * 立即检查更新并执行一次自动更新（需已开启自动更新）
+ * @returns Promise<AutoUpdateSummary> 本次自动更新结果
+ */
+export const runAutoUpdate = async(): Promise<API.INVOKE.AutoUpdateSummary> => {
+  return await invoke('run_auto_update_cmd')
+}
+
+/**
+ * 获取操作历史，最新的在前
+ * @param kind - 可选，仅返回指定类型的记录，如 'auto-update'
+ * @returns Promise<OperationRecord[]>
+ */
+export const getOperationHistory = async(
+  kind?: string,
+): Promise<API.INVOKE.OperationRecord[]> => {
+  return await invoke('get_operation_history_cmd', { kind: kind || null })
+}
+
+/**
+ * 监听后端发起的页面跳转（如点击更新通知后跳转到更新页面）
+ * @param callback - 收到目标路由路径时回调
+ * @returns Promise<UnlistenFn> 取消监听的函数
+ */
+export const onNavigate = async(
+  callback: (path: string) => void,
+): Promise<UnlistenFn> => {
+  return await listen<string>(
+    'navigate',
+    (event) => {
+      callback(event.payload)
+    },
+  )
 }
 
 /**
@@ -157,21 +575,199 @@ export const checkLinglongEnv = async(): Promise<API.INVOKE.LinglongEnvCheckResu
   return await invoke('check_linglong_env_cmd')
 }
 
+/**
+ * 校验内置的玲珑环境安装脚本
+ * @returns Promise<ScriptVerification> 校验结果，trusted 为 false 时后端会拒绝执行
+ */
+export const verifyLinglongEnvScript = async(): Promise<API.INVOKE.ScriptVerification> => {
+  return await invoke('verify_install_linglong_env_script_cmd')
+}
+
 /**
  * 执行玲珑环境自动安装
- * @param script 安装脚本字符串
+ * 使用随商店发布的内置安装脚本，执行前后端会校验脚本哈希
+ */
+export const installLinglongEnv = async(): Promise<API.INVOKE.InstallLinglongResult> => {
+  return await invoke('install_linglong_env_cmd')
+}
+
+/**
+ * 取消正在进行的玲珑环境安装
+ * @returns Promise<string> 取消结果消息
  */
-export const installLinglongEnv = async(
-  script: string,
-): Promise<API.INVOKE.InstallLinglongResult> => {
-  return await invoke('install_linglong_env_cmd', { script })
+export const cancelInstallLinglongEnv = async(): Promise<string> => {
+  return await invoke('cancel_install_linglong_env_cmd')
+}
+
+/**
+ * 获取最近一次玲珑环境安装的输出记录
+ * @returns Promise<EnvInstallOutput[]> 按时间顺序排列的输出行
+ */
+export const getInstallLinglongEnvTranscript = async(): Promise<API.INVOKE.EnvInstallOutput[]> => {
+  return await invoke('get_install_linglong_env_transcript_cmd')
+}
+
+/**
+ * 监听玲珑环境安装脚本的输出事件
+ * @param callback - 每收到一行输出时回调
+ * @returns Promise<UnlistenFn> 取消监听的函数
+ */
+export const onLinglongEnvInstallOutput = async(
+  callback: (output: API.INVOKE.EnvInstallOutput) => void,
+): Promise<UnlistenFn> => {
+  return await listen<API.INVOKE.EnvInstallOutput>(
+    'linglong-env-install-output',
+    (event) => {
+      callback(event.payload)
+    },
+  )
+}
+
+/**
+ * 预览清理
+ * 列出不再被任何已安装应用引用的运行时和基础环境，以及可回收的空间
+ * @returns Promise<PrunePreview> 可清理项目及总大小
+ */
+export const previewPrune = async(): Promise<API.INVOKE.PrunePreview> => {
+  return await invoke('preview_prune_cmd')
 }
 
 /**
  * 清理废弃的基础服务
  * 调用 ll-cli prune 命令清理不再使用的运行时和基础服务
- * @returns Promise<string> 清理操作的结果消息
+ * @returns Promise<PruneReport> 实际删除的项目与释放的空间
  */
-export const pruneApps = async(): Promise<string> => {
+export const pruneApps = async(): Promise<API.INVOKE.PruneReport> => {
   return await invoke('prune_apps')
 }
+
+/**
+ * 磁盘占用分析
+ * 按应用、运行时、基础环境和模块统计占用空间，包含层之间共享的空间和用户数据目录
+ * @returns Promise<DiskUsageReport> 磁盘占用报告
+ */
+export const getDiskUsage = async(): Promise<API.INVOKE.DiskUsageReport> => {
+  return await invoke('get_disk_usage_cmd')
+}
+
+/**
+ * 监听清理进度
+ * @param callback 进度回调，包含阶段与 ll-cli prune 的输出
+ * @returns 取消监听的函数
+ */
+export const onPruneProgress = async(
+  callback: (progress: API.INVOKE.PruneProgress) => void,
+) => {
+  return await listen<API.INVOKE.PruneProgress>(
+    'prune-progress',
+    (event) => {
+      callback(event.payload)
+    },
+  )
+}
+
+/**
+ * 获取玲珑仓库配置
+ * @returns Promise<LinglongRepoConfig> 默认仓库及仓库列表
+ */
+export const getRepoConfig = async(): Promise<API.INVOKE.LinglongRepoConfig> => {
+  return await invoke('get_repo_config_cmd')
+}
+
+/**
+ * 添加仓库（需要管理员授权）
+ * @param name - 仓库名称
+ * @param url - 仓库地址，支持 http(s):// 及 file:// 离线镜像
+ * @param alias - 可选的仓库别名
+ * @returns Promise<LinglongRepoConfig> 操作后的仓库配置
+ */
+export const addRepo = async(
+  name: string,
+  url: string,
+  alias?: string,
+): Promise<API.INVOKE.LinglongRepoConfig> => {
+  return await invoke('add_repo_cmd', { name, url, alias: alias || null })
+}
+
+/**
+ * 删除仓库（需要管理员授权）
+ * @param name - 仓库名称或别名
+ * @returns Promise<LinglongRepoConfig> 操作后的仓库配置
+ */
+export const removeRepo = async(name: string): Promise<API.INVOKE.LinglongRepoConfig> => {
+  return await invoke('remove_repo_cmd', { name })
+}
+
+/**
+ * 修改仓库地址（需要管理员授权）
+ * @param name - 仓库名称或别名
+ * @param url - 新的仓库地址
+ * @returns Promise<LinglongRepoConfig> 操作后的仓库配置
+ */
+export const updateRepo = async(
+  name: string,
+  url: string,
+): Promise<API.INVOKE.LinglongRepoConfig> => {
+  return await invoke('update_repo_cmd', { name, url })
+}
+
+/**
+ * 设置默认仓库（需要管理员授权）
+ * @param name - 仓库名称或别名
+ * @returns Promise<LinglongRepoConfig> 操作后的仓库配置
+ */
+export const setDefaultRepo = async(name: string): Promise<API.INVOKE.LinglongRepoConfig> => {
+  return await invoke('set_default_repo_cmd', { name })
+}
+
+/**
+ * 设置仓库优先级（需要管理员授权），数值越大优先级越高
+ * @param name - 仓库名称或别名
+ * @param priority - 优先级
+ * @returns Promise<LinglongRepoConfig> 操作后的仓库配置
+ */
+export const setRepoPriority = async(
+  name: string,
+  priority: number,
+): Promise<API.INVOKE.LinglongRepoConfig> => {
+  return await invoke('set_repo_priority_cmd', { name, priority })
+}
+
+/**
+ * 探测所有已配置仓库的连通性与延迟
+ * @returns Promise<RepoHealthReport> 各仓库的探测结果及整体在线状态
+ */
+export const checkRepoHealth = async(): Promise<API.INVOKE.RepoHealthReport> => {
+  return await invoke('check_repo_health_cmd')
+}
+
+/**
+ * 对仓库镜像测速，结果会记录到本地历史
+ * @param repos - 可选，只对指定名称的仓库测速
+ * @returns Promise<MirrorBenchmarkRun> 按吞吐量从高到低排序的测速结果
+ */
+export const runMirrorBenchmark = async(
+  repos?: string[],
+): Promise<API.INVOKE.MirrorBenchmarkRun> => {
+  return await invoke('run_mirror_benchmark_cmd', { repos: repos || null })
+}
+
+/**
+ * 获取镜像测速历史记录（从旧到新）
+ */
+export const getMirrorBenchmarkHistory = async(): Promise<API.INVOKE.MirrorBenchmarkRun[]> => {
+  return await invoke('get_mirror_benchmark_history_cmd')
+}
+
+/**
+ * 按用户确认的测速排序调整仓库优先级（需要管理员授权）
+ * @param order - 从快到慢的仓库名称列表
+ * @param setDefault - 是否将最快的仓库设为默认仓库
+ * @returns Promise<LinglongRepoConfig> 操作后的仓库配置
+ */
+export const applyMirrorRanking = async(
+  order: string[],
+  setDefault = false,
+): Promise<API.INVOKE.LinglongRepoConfig> => {
+  return await invoke('apply_mirror_ranking_cmd', { order, setDefault })
+}
@@ -1,6 +1,6 @@
 import { useCallback } from 'react'
 import { message, Modal } from 'antd'
-import { getRunningLinglongApps, uninstallApp } from '@/apis/invoke'
+import { checkUninstall, getRunningLinglongApps, uninstallApp } from '@/apis/invoke'
 import { useInstalledAppsStore } from '@/stores/installedApps'
 import { useUpdatesStore } from '@/stores/updates'
 import { sendUninstallRecord } from '@/services/analyticsService'
@@ -16,6 +16,8 @@ type UninstallOptions = {
   confirmTitle?: string
   /** 自定义文案 */
   confirmMessage?: string
+  /** 跳过依赖检查，卸载会导致其他应用无法运行时仍然卸载 */
+  force?: boolean
 }
 
 type BasicAppInfo = {
@@ -38,7 +40,7 @@ export const useAppUninstall = () => {
   const performUninstall = useCallback(
     async(appId: string, version: string, appInfo?: BasicAppInfo, options?: UninstallOptions) => {
       try {
-        await uninstallApp(appId, version)
+        await uninstallApp(appId, version, options?.force)
 
         removeApp(appId, version)
 
@@ -99,10 +101,27 @@ export const useAppUninstall = () => {
         }
       })()
 
+      // 检查是否有其他应用依赖该版本
+      const dependencyCheck = await checkUninstall(appId, version).catch((err) => {
+        console.warn('[useAppUninstall] Failed to check dependents:', err)
+        return null
+      })
+
       // 根据运行状态构建弹窗配置
       const appDisplayName = appInfo.zhName || appInfo.name || appId
       let modalConfig = {}
-      if (options?.confirmTitle || options?.confirmMessage) {
+      let uninstallOptions = options
+      if (dependencyCheck && !dependencyCheck.safe) {
+        modalConfig = {
+          title: dependencyCheck.dependents.length > 0
+            ? `${appDisplayName} 仍被其他应用依赖`
+            : `${appDisplayName} 可能仍被其他应用依赖`,
+          content: `${dependencyCheck.message}。是否仍然卸载？`,
+          okText: '仍然卸载',
+        }
+        // 用户已确认，跳过后端的依赖检查
+        uninstallOptions = { ...options, force: true }
+      } else if (options?.confirmTitle || options?.confirmMessage) {
         modalConfig = {
           title: options.confirmTitle,
           content: options.confirmMessage,
@@ -132,7 +151,7 @@ export const useAppUninstall = () => {
           cancelButtonProps: { type: 'primary' },
           onOk: async() => {
             try {
-              const result = await performUninstall(appId, version, appInfo, options)
+              const result = await performUninstall(appId, version, appInfo, uninstallOptions)
               resolve(result)
             } catch (error) {
               resolve(false)
@@ -1,7 +1,6 @@
 import { useCallback } from 'react'
 import { message } from 'antd'
-import { findShellString } from '@/apis/apps'
-import { checkLinglongEnv, installLinglongEnv } from '@/apis/invoke'
+import { checkLinglongEnv, installLinglongEnv, onLinglongEnvInstallOutput, verifyLinglongEnvScript } from '@/apis/invoke'
 import { useGlobalStore } from '@/stores/global'
 
 const DEFAULT_REASON = '检测到系统未安装玲珑环境，请先安装'
@@ -88,15 +87,22 @@ export const useLinglongEnv = () => {
   const runInstall = useCallback(async(): Promise<API.INVOKE.InstallLinglongResult> => {
     setInstalling(true)
     const hide = message.loading({ content: '正在自动安装玲珑环境...', key: 'install-linglong', duration: 0 })
+    // 将脚本最新一行输出展示在提示中，避免长时间无反馈
+    const unlisten = await onLinglongEnvInstallOutput((output) => {
+      if (output.stream === 'system' || !output.line.trim()) {
+        return
+      }
+      message.loading({ content: `正在自动安装玲珑环境：${output.line}`, key: 'install-linglong', duration: 0 })
+    })
     try {
-      const res = await findShellString()
-      const shellString = res.data
-      if (res.code !== 200 || !shellString) {
-        throw new Error('获取安装脚本失败，请稍后重试')
+      const verification = await verifyLinglongEnvScript()
+      console.info('[useLinglongEnv] script verification', verification)
+      if (!verification.trusted) {
+        throw new Error(verification.message)
       }
-      const output = await installLinglongEnv(shellString)
+      const output = await installLinglongEnv()
       hide()
-      message.success({ content: '玲珑环境安装完成，正在重新检测...', key: 'install-linglong' })
+      message.success({ content: `${output.verification.message}，玲珑环境安装完成，正在重新检测...`, key: 'install-linglong' })
       console.info('[useLinglongEnv] installEnv success', output)
       return output
     } catch (error) {
@@ -107,6 +113,7 @@ export const useLinglongEnv = () => {
       console.warn('[useLinglongEnv] installEnv error', errMsg)
       throw error
     } finally {
+      unlisten()
       setInstalling(false)
     }
   }, [])
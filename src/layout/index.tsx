@@ -5,7 +5,7 @@
  */
 
 import styles from './index.module.scss'
-import { Outlet } from 'react-router-dom'
+import { Outlet, useNavigate } from 'react-router-dom'
 import { Suspense, useEffect } from 'react'
 import Titlebar from './titlebar'
 import Sidebar from './sidebar'
@@ -18,6 +18,8 @@ import { useConfigStore } from '@/stores/appConfig'
 import { useInstalledAppsStore } from '@/stores/installedApps'
 import { sendVisitRecord } from '@/services/analyticsService'
 import { getCustomMenuCategory } from '@/apis/apps'
+import { message } from 'antd'
+import { onAppLaunchFailed, onNavigate } from '@/apis/invoke'
 // import { arch } from '@tauri-apps/plugin-os'
 
 // 暂时注释的 Antd Layout 组件，可能用于未来的布局重构
@@ -55,6 +57,29 @@ const AppLayout = () => {
       stopAutoRefresh()
     }
   }, [startAutoRefresh, stopAutoRefresh])
+
+  // 响应后端发起的页面跳转（点击更新通知后打开更新页面）
+  const navigate = useNavigate()
+  useEffect(() => {
+    const unlisten = onNavigate((path) => {
+      navigate(path)
+    })
+    return () => {
+      unlisten.then((f) => f())
+    }
+  }, [navigate])
+
+  // 应用启动后很快异常退出时提示用户，详细输出可在启动日志中查看
+  useEffect(() => {
+    const unlisten = onAppLaunchFailed((failed) => {
+      console.error('[onAppLaunchFailed]', failed.appId, failed.exitCode, failed.output)
+      const lastLine = failed.output.trim().split('\n').pop() || `退出码 ${failed.exitCode ?? '未知'}`
+      message.error(`${failed.appId} 启动失败: ${lastLine}`)
+    })
+    return () => {
+      unlisten.then((f) => f())
+    }
+  }, [])
   // // 从全局状态store中获取初始化相关方法
   // const onInited = useGlobalStore((state) => state.onInited)
   // const getUpdateAppNum = useGlobalStore((state) => state.getUpdateAppNum)
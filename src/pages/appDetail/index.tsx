@@ -209,17 +209,17 @@ const AppDetail = () => {
     }
   }
 
-  const handleRun = async() => {
+  const handleRun = async(version?: string) => {
     if (!currentApp?.appId) {
       console.info('[handleRun] currentApp.appId is empty')
       return
     }
 
-    console.info('[handleRun] Starting app:', currentApp.appId)
+    console.info('[handleRun] Starting app:', currentApp.appId, version)
 
     try {
-      // 根据 ll-cli 文档，启动应用只需要 appId，不需要版本号
-      await runApp(currentApp.appId)
+      // 从版本列表启动时运行对应的已安装版本
+      await runApp(currentApp.appId, version ? { version } : undefined)
       message.success('应用启动成功')
     } catch (error) {
       const errorMessage = error instanceof Error ? error.message : String(error)
@@ -342,7 +342,7 @@ const AppDetail = () => {
                 type='primary'
                 size='small'
                 shape='round'
-                onClick={() => handleRun()}
+                onClick={() => handleRun(versionValue)}
                 disabled={isUninstalling}
               >
                 启动
@@ -1,6 +1,6 @@
 import { Table, Button, message } from 'antd'
 import { useState, useEffect } from 'react'
-import { getRunningLinglongApps, killLinglongApp } from '@/apis/invoke'
+import { getRunningLinglongApps, killLinglongApp, onAppExited, onAppStarted } from '@/apis/invoke'
 import styles from './index.module.scss'
 interface LinglongAppInfo {
   key: string
@@ -11,6 +11,8 @@ interface LinglongAppInfo {
   source: string
   pid: string
   container_id: string
+  /** 未能获取完整应用信息时为 true，来源等字段可能为空 */
+  partial?: boolean
 }
 
 const Process = () => {
@@ -35,14 +37,18 @@ const Process = () => {
     // 初始加载
     fetchRunningApps()
 
-    // 设置定时器，每秒刷新一次
-    const intervalId = setInterval(() => {
+    // 后端监视器在应用启动或退出时推送事件，收到后刷新列表
+    const unlistenStarted = onAppStarted(() => {
       fetchRunningApps()
-    }, 1000)
+    })
+    const unlistenExited = onAppExited(() => {
+      fetchRunningApps()
+    })
 
-    // 清理函数：组件卸载时清除定时器
+    // 清理函数：组件卸载时取消监听
     return () => {
-      clearInterval(intervalId)
+      unlistenStarted.then((f) => f())
+      unlistenExited.then((f) => f())
     }
   }, [])
 
@@ -117,6 +123,7 @@ const Process = () => {
       headerCellStyle: {
         backgroundColor: 'var(--color-bg-2)',
       },
+      render: (source: string, record: LinglongAppInfo) => (record.partial && !source ? '-' : source),
     },
     {
       title: '进程ID',
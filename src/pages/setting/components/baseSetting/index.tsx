@@ -1,8 +1,9 @@
-import { Switch, message } from 'antd'
+import { Modal, Switch, message } from 'antd'
 import styles from './index.module.scss'
 import { useConfigStore } from '@/stores/appConfig'
 import { useState } from 'react'
-import { pruneApps } from '@/apis/invoke'
+import { onPruneProgress, previewPrune, pruneApps } from '@/apis/invoke'
+import { formatFileSize } from '@/util/format'
 
 const BasicSetting = ()=>{
   const checkVersion = useConfigStore((state) => state.checkVersion)
@@ -17,6 +18,21 @@ const BasicSetting = ()=>{
   const showBaseServiceClick = ()=>{
     changeBaseServiceStatus(!showBaseService)
   }
+  const runPrune = async() => {
+    const unlisten = await onPruneProgress((progress) => {
+      console.info('[prune]', progress.stage, progress.message)
+    })
+    try {
+      const report = await pruneApps()
+      const freed = report.diskFreedBytes ?? report.freedBytes
+      message.success(report.removed.length > 0
+        ? `已清理 ${report.removed.length} 项，释放 ${formatFileSize(freed)}`
+        : report.message || '清理完成')
+    } finally {
+      unlisten()
+    }
+  }
+
   const clearAbandonServiceClick = async() => {
     if (isPruning) {
       return
@@ -24,8 +40,47 @@ const BasicSetting = ()=>{
 
     setIsPruning(true)
     try {
-      const result = await pruneApps()
-      message.success(result || '清理完成')
+      const preview = await previewPrune()
+      if (preview.items.length === 0) {
+        if (preview.missingInfo.length > 0) {
+          message.warning('部分应用无法获取依赖信息，暂无法确认可清理的基础环境')
+        } else {
+          message.info('没有可清理的基础服务')
+        }
+        return
+      }
+      await new Promise<void>((resolve) => {
+        Modal.confirm({
+          title: '清除废弃基础服务',
+          content: (
+            <div>
+              <p>以下运行时和基础环境不再被任何已安装应用使用，预计可释放 {formatFileSize(preview.totalBytes)}：</p>
+              <ul style={{ maxHeight: 240, overflowY: 'auto', paddingLeft: 20 }}>
+                {preview.items.map((item) => (
+                  <li key={`${item.appId}/${item.version}/${item.module}`}>
+                    {item.appId} {item.version}（{item.module}）{formatFileSize(item.sizeBytes)}
+                  </li>
+                ))}
+              </ul>
+              {preview.missingInfo.length > 0 && (
+                <p>部分应用无法获取依赖信息，基础环境暂不列入清理预览：{preview.missingInfo.join('、')}</p>
+              )}
+            </div>
+          ),
+          okText: '确认清理',
+          cancelText: '取消',
+          onOk: async() => {
+            try {
+              await runPrune()
+            } catch (error) {
+              message.error(`清理失败: ${error}`)
+            } finally {
+              resolve()
+            }
+          },
+          onCancel: () => resolve(),
+        })
+      })
     } catch (error) {
       message.error(`清理失败: ${error}`)
     } finally {
@@ -1,5 +1,5 @@
 import { create } from 'zustand'
-import { getInstalledLinglongApps } from '@/apis/invoke'
+import { checkAppUpdates, getInstalledLinglongApps, getUpdateSchedulerStatus } from '@/apis/invoke'
 import { appCheckUpdate } from '@/apis/apps'
 import { useGlobalStore } from './global'
 
@@ -38,6 +38,8 @@ const AUTO_REFRESH_INTERVAL = 60 * 60 * 1000
 
 /** 定时器引用 */
 let autoRefreshTimer: NodeJS.Timeout | null = null
+/** 自动刷新是否处于开启状态（查询后台配置期间可能已被停止） */
+let autoRefreshActive = false
 
 // ==================== 辅助函数 ====================
 
@@ -95,6 +97,47 @@ function mapRemoteUpdatesToStore(
   return updateList
 }
 
+/**
+ * 合并后端仓库检测结果，补充服务端接口未覆盖的应用（如私有仓库应用）
+ * @param updateList 服务端接口得到的更新列表
+ * @param installedApps 已安装的应用列表
+ * @param localUpdates 后端直接对比仓库得到的更新列表
+ * @returns 合并后的更新信息列表
+ */
+function mergeLocalUpdates(
+  updateList: UpdateInfo[],
+  installedApps: API.INVOKE.InstalledApp[],
+  localUpdates: API.INVOKE.AppUpdateInfo[],
+): UpdateInfo[] {
+  const known = new Set(updateList.map(item => item.appId))
+  const installedMap = new Map(installedApps.map(app => [app.appId, app]))
+  const merged = [...updateList]
+
+  for (const update of localUpdates) {
+    // 开发模块不单独展示，与服务端接口保持一致
+    if (known.has(update.appId) || update.module === 'devel' || update.module === 'develop') {
+      continue
+    }
+    const installedApp = installedMap.get(update.appId)
+    known.add(update.appId)
+    merged.push({
+      appId: update.appId,
+      name: update.name,
+      version: update.latestVersion,
+      currentVersion: update.currentVersion,
+      description: update.description || installedApp?.description || '',
+      icon: installedApp?.icon,
+      arch: update.arch,
+      channel: update.channel,
+      module: update.module,
+      repoName: update.repoName,
+      zhName: installedApp?.zhName || update.name,
+    })
+  }
+
+  return merged
+}
+
 // ==================== Store 定义 ====================
 
 export const useUpdatesStore = create<UpdatesStore>((set, get) => ({
@@ -104,7 +147,8 @@ export const useUpdatesStore = create<UpdatesStore>((set, get) => ({
 
   /**
    * 检查应用更新
-   * 通过批量查询接口获取远程版本信息，与本地版本对比生成更新列表
+   * 通过批量查询接口获取远程版本信息，与本地版本对比生成更新列表；
+   * 同时由后端直接对比仓库版本，补充接口未覆盖或接口不可用时的结果
    * @param force 是否强制检查（忽略正在进行的检查）
    */
   checkUpdates: async(force = false) => {
@@ -141,17 +185,28 @@ export const useUpdatesStore = create<UpdatesStore>((set, get) => ({
         return
       }
 
-      // 4. 批量查询远程版本信息
-      const response = await appCheckUpdate(searchParams)
-      if (!response.data) {
-        console.warn('[checkUpdates] No data returned from appCheckUpdate')
-        set({ updates: [], lastChecked: Date.now() })
-        useGlobalStore.getState().getUpdateAppNum(0)
-        return
+      // 4. 批量查询远程版本信息，并行由后端直接对比仓库版本
+      const [remoteResult, localResult] = await Promise.allSettled([
+        appCheckUpdate(searchParams),
+        checkAppUpdates(),
+      ])
+      const remoteData = remoteResult.status === 'fulfilled' ? remoteResult.value.data : undefined
+      if (!remoteData) {
+        console.warn('[checkUpdates] No data returned from appCheckUpdate',
+          remoteResult.status === 'rejected' ? remoteResult.reason : '')
+      }
+      if (localResult.status === 'rejected') {
+        console.warn('[checkUpdates] Backend update check failed:', localResult.reason)
+      } else if (localResult.value.failed.length > 0) {
+        console.warn('[checkUpdates] Backend update check failed for:', localResult.value.failed)
       }
 
       // 5. 处理远程数据，生成更新列表
-      const updateList = mapRemoteUpdatesToStore(installedApps, response.data)
+      const updateList = mergeLocalUpdates(
+        mapRemoteUpdatesToStore(installedApps, remoteData || []),
+        installedApps,
+        localResult.status === 'fulfilled' ? localResult.value.updates : [],
+      )
 
       // 6. 更新状态
       set({ updates: updateList, lastChecked: Date.now() })
@@ -166,26 +221,37 @@ export const useUpdatesStore = create<UpdatesStore>((set, get) => ({
 
   /**
    * 启动自动刷新
-   * 立即执行一次检查，然后每小时自动检查一次
+   * 立即执行一次检查，未开启后台定时检查时每小时自动检查一次
    */
   startAutoRefresh: () => {
-    if (autoRefreshTimer) {
+    if (autoRefreshActive) {
       return
     }
+    autoRefreshActive = true
 
     // 立即检查一次
     get().checkUpdates()
 
-    // 设置定时刷新
-    autoRefreshTimer = setInterval(() => {
-      get().checkUpdates()
-    }, AUTO_REFRESH_INTERVAL)
+    // 后台定时检查已开启时由后端负责，不再重复轮询
+    getUpdateSchedulerStatus()
+      .then((status) => status.config.enabled)
+      .catch(() => false)
+      .then((backendEnabled) => {
+        if (backendEnabled || !autoRefreshActive || autoRefreshTimer) {
+          return
+        }
+        // 设置定时刷新
+        autoRefreshTimer = setInterval(() => {
+          get().checkUpdates()
+        }, AUTO_REFRESH_INTERVAL)
+      })
   },
 
   /**
    * 停止自动刷新
    */
   stopAutoRefresh: () => {
+    autoRefreshActive = false
     if (autoRefreshTimer) {
       clearInterval(autoRefreshTimer)
       autoRefreshTimer = null
@@ -431,10 +431,5 @@ declare namespace API {
      * 获取应用版本列表 - 响应参数
      */
     type GetAppVersionListRes = AppMainDto[];
-
-    /**
-     * 获取Shell脚本 - 响应参数
-     */
-    type GetShellStringRes = string;
   }
 }
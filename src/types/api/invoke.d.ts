@@ -15,6 +15,7 @@ declare namespace API {
       module: string;
       runtime: string;
       size: string;
+      sizeBytes: number; // 由 size 解析得到的字节数
       repoName: string;
       zhName?: string;
       categoryName?: string;
@@ -79,6 +80,21 @@ declare namespace API {
       description?: string;
       module?: string;
       icon?: string;
+      channel?: string;
+      repoName?: string; // 来源仓库
+    }
+
+    // 远程仓库中的应用版本
+    interface RemoteAppVersion {
+      appId: string;
+      name: string;
+      version: string;
+      repoName: string; // 来源仓库
+      channel: string;
+      arch: string[];
+      module: string;
+      description?: string;
+      installed: boolean; // 本地是否已安装
     }
 
     interface LinglongRepo {
@@ -88,6 +104,85 @@ declare namespace API {
       priority?: string;
     }
 
+    interface LinglongRepoConfig {
+      defaultRepo?: string;
+      repos: LinglongRepo[];
+    }
+
+    /**
+     * 仓库探测状态
+     * - online: 可访问
+     * - degraded: 服务器可达但仓库配置异常
+     * - dnsFailed / connectFailed / httpFailed / timeout: 各阶段失败
+     * - invalid: 地址无效
+     */
+    type RepoHealthStatus = 'online' | 'degraded' | 'dnsFailed' | 'connectFailed' | 'httpFailed' | 'timeout' | 'invalid';
+
+    interface RepoHealth {
+      name: string;
+      url: string;
+      status: RepoHealthStatus;
+      dnsMs?: number; // DNS 解析耗时（毫秒）
+      connectMs?: number; // TCP 连接耗时（毫秒）
+      httpMs?: number; // HTTP HEAD 耗时（毫秒，含 TLS）
+      latencyMs?: number; // 总耗时（毫秒）
+      httpStatus?: number;
+      error?: string;
+    }
+
+    interface RepoHealthReport {
+      online: boolean; // 至少一个仓库可达
+      repos: RepoHealth[];
+      checkedAt: number;
+    }
+
+    // 单个镜像的测速结果
+    interface MirrorBenchmarkEntry {
+      name: string;
+      url: string;
+      bytes: number; // 实际下载字节数
+      elapsedMs: number;
+      ttfbMs?: number; // 首字节耗时
+      throughputBps: number; // 吞吐量（字节/秒）
+      success: boolean;
+      error?: string;
+    }
+
+    // 一次镜像测速的结果
+    interface MirrorBenchmarkRun {
+      startedAt: number;
+      entries: MirrorBenchmarkEntry[];
+      fastest?: string;
+    }
+
+    type DistroFamily = 'debian' | 'fedora' | 'arch' | 'suse' | 'unknown';
+
+    // 软件包版本信息
+    interface PackageVersionInfo {
+      name: string;
+      installed?: string; // 已安装版本
+      candidate?: string; // 软件源候选版本
+    }
+
+    // 玲珑相关软件包查询结果
+    interface LinglongPackageReport {
+      distroId: string;
+      family: DistroFamily;
+      packageManager: string;
+      packages: PackageVersionInfo[];
+    }
+
+    type EnvCheckSeverity = 'pass' | 'warn' | 'fail';
+
+    // 单个环境检查项
+    interface EnvCheckItem {
+      id: string; // 如 ll-cli-installed、repo-configured、ll-version
+      severity: EnvCheckSeverity;
+      observed?: string; // 实际检测到的值
+      expected?: string; // 期望的值
+      remediation?: string; // 修复建议
+    }
+
     interface LinglongEnvCheckResult {
       ok: boolean;
       reason?: string;
@@ -101,11 +196,282 @@ declare namespace API {
       repoName?: string;
       repos?: LinglongRepo[];
       isContainer?: boolean;
+      packages?: LinglongPackageReport; // 玲珑相关软件包版本
+      checks?: EnvCheckItem[]; // 所有检查项，ok 由此推导
+    }
+
+    interface AppUpdateInfo {
+      appId: string;
+      name: string;
+      currentVersion: string; // 当前已安装版本
+      latestVersion: string; // 仓库中的最新版本
+      channel: string;
+      arch: string;
+      module: string;
+      repoName: string; // 最新版本的来源仓库
+      description?: string;
+    }
+
+    interface AppUpdateCheck {
+      updates: AppUpdateInfo[];
+      failed: string[]; // 查询失败、无法确定是否有更新的应用
+    }
+
+    interface UpdateSchedulerConfig {
+      enabled: boolean;
+      intervalMinutes: number; // 检查间隔（分钟）
+      notify: boolean; // 是否发送桌面通知
+    }
+
+    interface UpdateSchedulerStatus {
+      config: UpdateSchedulerConfig;
+      lastCheckedAt?: number; // 上次检查时间（Unix 毫秒）
+      nextCheckAt?: number; // 下次检查时间（Unix 毫秒）
+      lastUpdates: AppUpdateInfo[]; // 上次检查得到的可更新应用
+      lastFailed: string[]; // 上次检查中查询失败的应用
+      lastError?: string;
+    }
+
+    interface AutoUpdatePolicy {
+      enabled: boolean;
+      include: string[]; // 仅自动更新这些应用，为空表示全部
+      exclude: string[]; // 不自动更新的应用，优先于 include
+    }
+
+    interface OperationItem {
+      appId: string;
+      fromVersion?: string;
+      toVersion?: string;
+      status: 'success' | 'failed' | 'skipped';
+      message?: string;
+    }
+
+    interface AutoUpdateSummary {
+      updated: OperationItem[];
+      failed: OperationItem[];
+      skipped: OperationItem[];
+      pending: string[]; // 待重试的应用
+    }
+
+    interface OperationRecord {
+      kind: string; // 操作类型，如 auto-update
+      startedAt: number; // Unix 毫秒
+      finishedAt: number; // Unix 毫秒
+      summary: string;
+      items: OperationItem[];
     }
 
     interface InstallLinglongResult {
       stdout: string;
       stderr: string;
+      transcriptPath?: string; // 安装记录文件路径
+      verification: ScriptVerification; // 实际执行的脚本的校验结果
+    }
+
+    interface ScriptVerification {
+      sha256: string;
+      trusted: boolean;
+      message: string; // 展示给用户的说明
+    }
+
+    interface EnvInstallOutput {
+      stream: 'stdout' | 'stderr' | 'system';
+      line: string;
+      timestamp: number; // Unix 毫秒时间戳
+    }
+
+    interface AppResourceUsage {
+      name: string;
+      version: string;
+      containerId: string;
+      pid: string;
+      processCount: number; // 容器内进程数
+      cpuPercent: number; // 单核满载为 100
+      rssBytes: number;
+      pssBytes?: number; // 无权限读取时为空
+      threads: number;
+      readBytes?: number;
+      writeBytes?: number;
+      sampledAt: number; // Unix 毫秒时间戳
+    }
+
+    interface RunAppOptions {
+      version?: string; // 已安装的版本，为空时由 ll-cli 选择
+      args?: string[]; // 传给应用的参数（放在 -- 之后）
+      files?: string[]; // 绝对路径或 URL
+      env?: Record<string, string>;
+    }
+
+    interface AppLaunchFailed {
+      appId: string;
+      exitCode?: number; // 被信号终止时为空
+      output: string; // 启动日志末尾的输出
+      logName?: string; // 可传给 readLaunchLog
+    }
+
+    interface LaunchLogEntry {
+      name: string; // 日志文件名
+      appId: string;
+      startedAt: number; // Unix 毫秒时间戳
+      sizeBytes: number;
+    }
+
+    interface AppRunEvent {
+      appId: string;
+      containerId: string;
+      pid: string;
+      startedAt: number; // 首次发现运行的时间，Unix 毫秒
+      exitCode?: number; // 仅商店启动的应用可用
+      durationMs?: number; // 运行时长，仅 app-exited 事件有值
+    }
+
+    interface DesktopEntryInfo {
+      appId: string;
+      fileName: string;
+      sourcePath: string; // 应用层中的原始文件
+      name: string;
+      exec: string;
+      icon: string;
+      menuPath?: string; // 应用菜单中的文件，未导出时为空
+      hasShortcut: boolean;
+      problems: string[]; // 为空表示正常
+    }
+
+    interface DesktopRepairReport {
+      appId: string;
+      repaired: string[]; // 新写入的菜单文件
+      unchanged: string[];
+      failed: string[];
+    }
+
+    interface AutostartOptions {
+      delaySeconds?: number; // 登录后延迟启动的秒数，最大 600
+      startMinimized?: boolean; // 最小化启动，使用应用声明的最小化参数
+      args?: string[]; // 其他附加参数
+    }
+
+    interface AutostartEntry {
+      appId: string;
+      path: string;
+      enabled: boolean;
+      managed: boolean; // 是否由商店创建
+      options: AutostartOptions;
+    }
+
+    interface TerminalRequest {
+      kind: 'exec' | 'enter' | 'cli';
+      target?: string; // exec/enter 时为应用ID或容器ID
+      args?: string[]; // exec 时为容器内命令，cli 时为 ll-cli 参数
+      cols?: number;
+      rows?: number;
+    }
+
+    interface TerminalSession {
+      sessionId: string;
+      command: string; // 执行的完整命令
+      pid?: number;
+    }
+
+    interface TerminalOutput {
+      sessionId: string;
+      data: string;
+    }
+
+    interface TerminalExit {
+      sessionId: string;
+      exitCode?: number;
+    }
+
+    interface PruneItem {
+      appId: string
+      name: string
+      version: string
+      arch: string
+      channel: string
+      kind: string // runtime 或 base
+      module: string
+      sizeBytes: number
+    }
+
+    interface PrunePreview {
+      items: PruneItem[]
+      totalBytes: number // 可回收空间总量
+      missingInfo: string[] // 无法获取依赖信息的应用，存在时基础环境不列入预览
+    }
+
+    interface PruneReport {
+      removed: PruneItem[] // 实际被删除的项目
+      freedBytes: number // 删除项目的大小之和
+      diskFreedBytes: number | null // 磁盘可用空间的增加量
+      message: string
+    }
+
+    interface PruneProgress {
+      stage: 'scanning' | 'pruning' | 'verifying' | 'done'
+      message: string
+    }
+
+    interface LayerUsage {
+      appId: string
+      name: string
+      version: string
+      arch: string
+      channel: string
+      kind: string // app、runtime 或 base
+      module: string
+      sizeBytes: number // ll-cli 报告的大小
+      path: string | null // 层目录
+      diskBytes: number | null // 层目录中文件的总大小
+      exclusiveBytes: number | null // 仅由该层引用的文件大小
+    }
+
+    interface UserDataUsage {
+      appId: string
+      path: string
+      sizeBytes: number
+    }
+
+    interface DiskUsageReport {
+      layers: LayerUsage[]
+      byKind: Record<string, number> // 按类型汇总的独占空间
+      byModule: Record<string, number> // 按模块汇总的独占空间
+      sharedBytes: number // 被多个层共享的空间
+      totalBytes: number
+      userData: UserDataUsage[]
+      userDataBytes: number
+    }
+
+    interface DependencyNode {
+      appId: string
+      version: string
+      kind: string // app、runtime 或 base
+      modules: string[]
+      reason: 'explicit' | 'dependency' // 安装原因：主动安装或作为依赖安装
+      dependencies: string[] // 直接依赖的节点，格式为 appId/version
+      dependents: string[] // 直接依赖该节点的节点
+      missing: string[] // 未能匹配到已安装项的引用
+    }
+
+    interface DependencyGraph {
+      nodes: DependencyNode[]
+      missingInfo: string[] // 未能获取依赖信息的节点，它们使用的 base 未知
+    }
+
+    interface UninstallCheck {
+      appId: string
+      version: string
+      safe: boolean // 卸载后没有应用会因此无法运行
+      dependents: DependencyNode[] // 卸载后会失去依赖的节点
+      missingInfo: string[] // 依赖信息未知、可能受影响的节点
+      message: string
+    }
+
+    interface StopAppResult {
+      target: string; // 传入的应用 ID 或容器 ID
+      appId: string;
+      signal?: 'SIGTERM' | 'SIGKILL'; // 调用时应用已不在运行则为空
+      stopped: boolean;
+      message: string;
     }
   }
 }